@@ -1,11 +1,46 @@
+use std::path::PathBuf;
+
 use clap::Parser;
+use clap_complete::ArgValueCandidates;
+use rustwarrior_core::{Color, Config};
 
-use crate::commands::{add::Add, list::List};
+use crate::{
+    commands::{
+        add::Add, backup::Backup, completions::Completions, config, context::Context,
+        delete::Delete, done::Done, edit::Edit, export::Export, git::Git, import::Import,
+        info::Info, list::List, modify::Modify, purge::Purge, reopen::Reopen, report::Report,
+        reports::Reports, restore::Restore, start::Start, stop::Stop, sync, timesheet::Timesheet,
+        tui::Tui,
+    },
+    completion,
+    output::Output,
+};
 
 #[derive(Debug, Parser)]
 pub struct Cli {
     #[clap(subcommand)]
     command: Option<Command>,
+    /// How to print results and errors
+    ///
+    /// `json` and `jsonl` print a stable schema for scripts. Errors are then
+    /// printed to stderr as `{"error": {"code": ..., "message": ...}}`.
+    #[clap(long, global = true, value_enum, default_value_t)]
+    output: Output,
+    /// When to colour tables: auto, always or never
+    #[clap(long, global = true, value_name = "WHEN")]
+    color: Option<Color>,
+    /// Where tasks are stored, instead of `RUSTWARRIOR_DATA_DIR` or the
+    /// config file
+    #[clap(long, global = true, value_name = "DIR")]
+    data_dir: Option<PathBuf>,
+    /// The context to use instead of the chosen one, or `none`
+    #[clap(
+        long,
+        global = true,
+        value_name = "NAME",
+        add = ArgValueCandidates::new(completion::contexts)
+    )]
+    context: Option<String>,
 }
 
 impl Default for Cli {
@@ -15,41 +50,200 @@ impl Default for Cli {
 }
 
 impl Cli {
+    pub const fn output(&self) -> Output {
+        self.output
+    }
+
     pub fn run(self) -> anyhow::Result<()> {
-        self.command.unwrap_or_default().run()
+        match self.command {
+            // These don't need a valid config, so `rw config` can repair one
+            Some(Command::Config(config)) => config.run(self.output),
+            Some(Command::Completions(completions)) => completions.run(),
+            command => {
+                let config = resolve(self.color, self.data_dir, self.context)?;
+                command
+                    .unwrap_or_else(|| Command::List(List::default()))
+                    .run(self.output, &config)
+            }
+        }
+    }
+}
+
+/// Load the config file and the environment variables which override it,
+/// then apply the flags, which override both
+fn resolve(
+    color: Option<Color>,
+    data_dir: Option<PathBuf>,
+    context: Option<String>,
+) -> anyhow::Result<Config> {
+    let mut config = Config::load_default()?;
+    if color.is_some() {
+        config.color = color;
     }
+    if data_dir.is_some() {
+        config.data_dir = data_dir;
+    }
+    if context.is_some() {
+        config.context = context;
+        config.context()?;
+    }
+    Ok(config)
 }
 
-#[derive(Debug, Parser, Default)]
+#[derive(Debug, Parser)]
 pub enum Command {
     Add(Add),
-    #[default]
-    List,
+    List(List),
+    Info(Info),
+    Done(Done),
+    Reopen(Reopen),
+    Start(Start),
+    Stop(Stop),
+    Timesheet(Timesheet),
+    Reports(Reports),
+    Modify(Modify),
+    Edit(Edit),
+    Delete(Delete),
+    Purge(Purge),
+    Import(Import),
+    Export(Export),
+    Sync(sync::Sync),
+    Git(Git),
+    Backup(Backup),
+    Restore(Restore),
+    Config(config::Config),
+    Context(Context),
+    Completions(Completions),
+    Tui(Tui),
+    /// Run a report by name, like `rw next`
+    #[clap(external_subcommand)]
+    Report(Vec<String>),
 }
 
 impl Command {
-    pub fn run(self) -> anyhow::Result<()> {
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
         match self {
-            Self::Add(add) => add.run(),
-            Self::List => List::run(),
+            Self::Add(add) => add.run(output, config),
+            Self::List(list) => list.run(output, config),
+            Self::Info(info) => info.run(output, config),
+            Self::Done(done) => done.run(output, config),
+            Self::Reopen(reopen) => reopen.run(output, config),
+            Self::Start(start) => start.run(output, config),
+            Self::Stop(stop) => stop.run(output, config),
+            Self::Timesheet(timesheet) => timesheet.run(output, config),
+            Self::Reports(_) => Reports::run(output, config),
+            Self::Modify(modify) => modify.run(output, config),
+            Self::Edit(edit) => edit.run(output, config),
+            Self::Delete(delete) => delete.run(output, config),
+            Self::Purge(purge) => purge.run(output, config),
+            Self::Import(import) => import.run(output, config),
+            Self::Export(export) => export.run(config),
+            Self::Sync(sync) => sync.run(output, config),
+            Self::Git(git) => git.run(output, config),
+            Self::Backup(backup) => backup.run(output, config),
+            Self::Restore(restore) => restore.run(output, config),
+            Self::Config(command) => command.run(output),
+            Self::Context(context) => context.run(output, config),
+            Self::Completions(completions) => completions.run(),
+            Self::Tui(_) => Tui::run(config),
+            Self::Report(words) => Report::new(words).run(output, config),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use clap::Parser;
+    use clap::{CommandFactory, Parser};
+    use rustwarrior_core::report;
     use test_case::test_case;
 
     use super::Cli;
 
     #[test_case("rw" ; "empty")]
     #[test_case("rw list")]
+    #[test_case("rw list +work -home" ; "list tags")]
+    #[test_case(r#"rw list "(+work or priority.above:3) and not status:completed""# ; "list expression")]
+    #[test_case("rw list --sort priority-,created+ --columns id,priority,description,age +work" ; "list sort columns")]
+    #[test_case("rw config show" ; "config show")]
+    #[test_case("rw config get date_format" ; "config get")]
+    #[test_case("rw config set reports.work.filter '+work status:open'" ; "config set")]
+    #[test_case("rw config unset default_priority" ; "config unset")]
+    #[test_case("rw --color never --data-dir /tmp/tasks list" ; "global settings")]
+    #[test_case("rw add -" ; "add stdin")]
+    #[test_case("rw add --file notes.txt -p 2" ; "add file")]
+    #[test_case("rw completions bash" ; "completions")]
+    #[test_case("rw tui" ; "tui")]
+    #[test_case("rw context" ; "context")]
+    #[test_case("rw context work" ; "context switch")]
+    #[test_case("rw context list" ; "context list")]
+    #[test_case("rw --context none add 'Water plants'" ; "context flag")]
+    #[test_case("rw reports" ; "reports")]
+    #[test_case("rw next" ; "report")]
+    #[test_case("rw overdue +work project:home" ; "report filter")]
+    #[test_case("rw --output json completed" ; "report output")]
     #[test_case(r#"rw add "some new task""# ; "add")]
     #[test_case(r#"rw add "some new task" -p 1"# ; "priority short")]
     #[test_case(r#"rw add "some new task" --priority 1"# ; "priority long")]
     #[test_case(r#"rw add "some new task" --priority=1"# ; "priority long alt")]
+    #[test_case("rw info 3" ; "info")]
+    #[test_case("rw done 3" ; "done")]
+    #[test_case("rw done 1 3 5-8" ; "done list and range")]
+    #[test_case("rw done 1,3,5-8" ; "done comma separated")]
+    #[test_case("rw reopen 2-4" ; "reopen")]
+    #[test_case("rw start 3" ; "start")]
+    #[test_case("rw start 3 --exclusive" ; "start exclusive")]
+    #[test_case("rw stop" ; "stop all")]
+    #[test_case("rw stop 3" ; "stop")]
+    #[test_case("rw timesheet" ; "timesheet")]
+    #[test_case("rw timesheet --from 2024-01-01 --to 2024-01-31 --group-by project --round 15 --csv" ; "timesheet options")]
+    #[test_case("rw modify 3 call the plumber" ; "modify description")]
+    #[test_case("rw modify 1,3 priority:1 +urgent -someday due:" ; "modify attributes")]
+    #[test_case("rw modify '+work project:' project:office --yes" ; "modify filter")]
+    #[test_case("rw modify +work -y --bulk 10 -work +office" ; "modify options first")]
+    #[test_case("rw edit 3" ; "edit")]
+    #[test_case("rw delete 3" ; "delete")]
+    #[test_case("rw delete '+someday project:' --yes" ; "delete filter")]
+    #[test_case("rw purge" ; "purge")]
+    #[test_case("rw purge --older-than 0" ; "purge all")]
+    #[test_case("rw --output json list" ; "output json")]
+    #[test_case("rw list --output=jsonl" ; "output jsonl after command")]
+    #[test_case("rw --output table" ; "output table")]
+    #[test_case("rw import tasks.json" ; "import")]
+    #[test_case("rw import --format taskwarrior -" ; "import stdin")]
+    #[test_case("rw export" ; "export")]
+    #[test_case("rw export -f taskwarrior tasks.json" ; "export file")]
+    #[test_case("rw import --format todotxt todo.txt" ; "import todotxt")]
+    #[test_case("rw export --format todotxt" ; "export todotxt")]
+    #[test_case("rw export --format csv --columns description,priority" ; "export csv columns")]
+    #[test_case("rw import -f csv --map Title=description --map Prio=priority tasks.csv" ; "import csv mapping")]
+    #[test_case("rw import --format ics reminders.ics" ; "import ics")]
+    #[test_case("rw export -f ics tasks.ics" ; "export ics")]
+    #[test_case("rw import --format markdown notes.md" ; "import markdown")]
+    #[test_case("rw export --format markdown" ; "export markdown")]
+    #[test_case("rw import --format org tasks.org" ; "import org")]
+    #[test_case("rw export -f org tasks.org" ; "export org")]
+    #[test_case("rw sync" ; "sync")]
+    #[test_case("rw sync --server http://localhost:8765" ; "sync server")]
+    #[test_case("rw git init" ; "git init")]
+    #[test_case("rw git clone git@example.com:tasks.git" ; "git clone")]
+    #[test_case("rw git merge-driver base ours theirs" ; "git merge driver")]
+    #[test_case("rw git push origin main" ; "git passthrough")]
+    #[test_case("rw backup list" ; "backup list")]
+    #[test_case("rw restore 20240101T090000Z" ; "restore")]
+    #[test_case("rw restore 20240101 --dry-run" ; "restore dry run")]
+    #[test_case("rw restore 20240101 -y" ; "restore yes")]
     fn parse(input: &str) {
         Cli::parse_from(shlex::split(input).unwrap());
     }
+
+    #[test]
+    fn commands_are_reserved_report_names() {
+        for command in Cli::command().get_subcommands() {
+            assert!(
+                report::RESERVED.contains(&command.get_name()),
+                "{} can be the name of a report",
+                command.get_name()
+            );
+        }
+    }
 }
@@ -0,0 +1,211 @@
+//! The columns of task tables, chosen with `--columns`
+
+use chrono::{DateTime, Local, Utc};
+use clap::ValueEnum;
+use comfy_table::{
+    Attribute, Cell, Color as CellColor, ColumnConstraint, ContentArrangement, Table,
+    presets::UTF8_HORIZONTAL_ONLY,
+};
+use rustwarrior_core::{Color, Config, OpenTask, Priority, report::Report};
+
+use crate::{duration, error::CodedError, output::status};
+
+/// A column of a task table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Column {
+    /// The short ID
+    Id,
+    /// The permanent identifier
+    Uuid,
+    /// Pending, started, completed or deleted
+    Status,
+    /// 1 (highest) to 4 (lowest)
+    Priority,
+    /// The project
+    Project,
+    /// The tags, separated by spaces
+    Tags,
+    /// What the task is
+    Description,
+    /// The date the task was created
+    Created,
+    /// The date the task was last changed
+    Modified,
+    /// How long ago the task was created
+    Age,
+    /// When work on the task started, while it is in progress
+    Start,
+    /// The date the task was closed
+    End,
+    /// The date the task was completed
+    Completed,
+    /// The time worked on the task
+    Tracked,
+}
+
+impl Column {
+    /// The columns shown when none are chosen
+    pub const DEFAULT: [Self; 4] = [Self::Id, Self::Priority, Self::Description, Self::Tracked];
+
+    /// The columns a report shows, which are only names in the config file
+    pub fn of_report(report: &Report) -> Result<Vec<Self>, CodedError> {
+        report
+            .columns()
+            .iter()
+            .map(|name| {
+                Self::from_str(name, true).map_err(|_| {
+                    CodedError::usage(format!(
+                        "report '{}' has an unknown column '{name}'",
+                        report.name()
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    const fn heading(self) -> &'static str {
+        match self {
+            Self::Id => "ID",
+            Self::Uuid => "UUID",
+            Self::Status => "Status",
+            Self::Priority => "Priority",
+            Self::Project => "Project",
+            Self::Tags => "Tags",
+            Self::Description => "Description",
+            Self::Created => "Created",
+            Self::Modified => "Modified",
+            Self::Age => "Age",
+            Self::Start => "Start",
+            Self::End => "End",
+            Self::Completed => "Completed",
+            Self::Tracked => "Tracked",
+        }
+    }
+
+    /// The text of the column for a task, which is empty if it has no value
+    fn value(self, task: &OpenTask, now: DateTime<Utc>, date_format: &str) -> String {
+        let date = |date: Option<DateTime<Utc>>| {
+            date.map(|date| date.with_timezone(&Local).format(date_format).to_string())
+                .unwrap_or_default()
+        };
+        match self {
+            Self::Id => task.id().to_string(),
+            Self::Uuid => task.uuid().to_string(),
+            Self::Status => status(task).to_string(),
+            Self::Priority => task
+                .priority()
+                .map(|priority| priority.to_string())
+                .unwrap_or_default(),
+            Self::Project => task.project().unwrap_or_default().to_string(),
+            Self::Tags => {
+                let tags: Vec<&str> = task.tags().iter().map(String::as_str).collect();
+                tags.join(" ")
+            }
+            Self::Description => task.description().clone(),
+            Self::Created => date(Some(task.created())),
+            Self::Modified => date(Some(task.modified())),
+            Self::Age => duration::age(now - task.created()),
+            Self::Start => date(task.start()),
+            Self::End => date(task.end()),
+            Self::Completed => date(task.completed()),
+            Self::Tracked => {
+                let tracked = task.tracked(now);
+                if task.is_started() {
+                    format!("{} (active)", duration::format(tracked))
+                } else if tracked.is_zero() {
+                    String::new()
+                } else {
+                    duration::format(tracked)
+                }
+            }
+        }
+    }
+
+    /// The colour of the column for a task, if it stands out
+    fn color(self, task: &OpenTask) -> Option<CellColor> {
+        match self {
+            Self::Priority => match task.priority()? {
+                Priority::One => Some(CellColor::Red),
+                Priority::Two => Some(CellColor::Yellow),
+                Priority::Three | Priority::Four => None,
+            },
+            Self::Tracked | Self::Start if task.is_started() => Some(CellColor::Green),
+            _ => None,
+        }
+    }
+
+    /// Whether the column is worth narrowing the table for
+    const fn wraps(self) -> bool {
+        matches!(self, Self::Description | Self::Tags | Self::Project)
+    }
+}
+
+/// Build a table of tasks with the given columns
+///
+/// Columns with no values are left out. The table fits the terminal, or
+/// `$COLUMNS` characters if it is set, by wrapping descriptions, tags and
+/// projects. Dates and colours follow the config.
+pub fn table(
+    tasks: &[&OpenTask],
+    columns: &[Column],
+    now: DateTime<Utc>,
+    config: &Config,
+) -> Table {
+    let rows: Vec<Vec<String>> = tasks
+        .iter()
+        .map(|task| {
+            columns
+                .iter()
+                .map(|column| column.value(task, now, config.date_format()))
+                .collect()
+        })
+        .collect();
+    let shown: Vec<usize> = (0..columns.len())
+        .filter(|index| rows.iter().any(|row| !row[*index].is_empty()))
+        .collect();
+
+    let mut table = Table::new();
+    let styled = match config.color() {
+        Color::Always => {
+            table.enforce_styling();
+            true
+        }
+        Color::Auto => std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()),
+        Color::Never => false,
+    };
+    table
+        .load_preset(UTF8_HORIZONTAL_ONLY)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(shown.iter().map(|index| {
+            let cell = Cell::new(columns[*index].heading());
+            if styled {
+                cell.add_attribute(Attribute::Bold)
+            } else {
+                cell
+            }
+        }));
+    if let Some(width) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|width| width.parse().ok())
+    {
+        table.set_width(width);
+    }
+    for (task, row) in tasks.iter().zip(&rows) {
+        table.add_row(shown.iter().map(|index| {
+            let cell = Cell::new(&row[*index]);
+            match columns[*index].color(task) {
+                Some(color) if styled => cell.fg(color),
+                _ => cell,
+            }
+        }));
+    }
+    for (position, index) in shown.iter().enumerate() {
+        if !columns[*index].wraps() {
+            if let Some(column) = table.column_mut(position) {
+                column.set_constraint(ColumnConstraint::ContentWidth);
+            }
+        }
+    }
+
+    table
+}
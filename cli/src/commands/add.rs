@@ -1,30 +1,158 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+use anyhow::{Context, bail};
 use clap::Parser;
-use rustwarrior_core::{Priority, Store, Task};
+use rustwarrior_core::{Config, Priority, Store, Task};
 
-use crate::store_ext::StoreExt;
+use crate::{
+    error::CodedError,
+    output::{Output, TaskInfo},
+    store_ext::StoreExt,
+};
 
-/// Add a new task
+/// Add a new task, or a task for each line of a file
+///
+/// The task gets the tags, project and priority the active context's filter
+/// asks for, like `+work` or `project:home`.
+///
+/// With `-` or `--file`, each non-empty line is a task. A word `!1` to `!4`,
+/// or `priority:1` to `priority:4`, sets the priority of its line's task. The
+/// tasks are saved together, and their IDs printed. For example:
+///
+/// rw add --file notes.txt
+///
+/// pbpaste | rw add -
 #[derive(Debug, Parser)]
 pub struct Add {
-    /// The description of the task
-    description: String,
-    /// Priority level (1-4)
+    /// The description of the task, or `-` to read tasks from standard input
+    #[clap(required_unless_present = "file", conflicts_with = "file")]
+    description: Option<String>,
+    /// Read tasks from a file, one per line
+    #[clap(long, short, value_name = "PATH")]
+    file: Option<PathBuf>,
+    /// Priority level (1-4), or the configured default priority. Priority
+    /// markers in a line take precedence
     #[clap(long, short)]
     priority: Option<Priority>,
 }
 
 impl Add {
     /// Run the add command
-    pub fn run(self) -> anyhow::Result<()> {
-        let mut store = Store::load_default()?;
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        let lines = match (&self.file, self.description.as_deref()) {
+            (Some(path), _) => fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?,
+            (None, Some("-")) => {
+                let mut lines = String::new();
+                io::stdin()
+                    .read_to_string(&mut lines)
+                    .context("failed to read standard input")?;
+                lines
+            }
+            _ => return self.add_one(output, config),
+        };
 
-        let mut task = Task::new(self.description);
-        if let Some(p) = self.priority {
-            task = task.with_priority(p);
+        let tasks = lines
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                let (description, priority) = parse_line(line)
+                    .map_err(|e| CodedError::usage(format!("line {}: {e}", index + 1)))?;
+                new_task(description, priority.or(self.priority), config)
+            })
+            .collect::<anyhow::Result<Vec<Task>>>()?;
+        if tasks.is_empty() {
+            bail!(CodedError::usage("no tasks to add: every line is empty"));
         }
+
+        let mut store = Store::load_default(config)?;
+        let ids: Vec<usize> = tasks.into_iter().map(|task| store.push(task)).collect();
+        store.save_default(config)?;
+
+        let added: Vec<TaskInfo> = ids
+            .iter()
+            .filter_map(|id| store.get(*id))
+            .map(TaskInfo::from)
+            .collect();
+        output.print_list(&added, || {
+            for task in &added {
+                println!("Added task {} '{}'", task.id, task.description);
+            }
+        })
+    }
+
+    fn add_one(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        let description = self.description.expect("clap requires a description");
+        let task = new_task(description, self.priority, config)?;
+        let mut store = Store::load_default(config)?;
         let id = store.push(task);
-        store.save_default()?;
-        println!("Added task {id}");
-        Ok(())
+        store.save_default(config)?;
+
+        let task = store.get(id).expect("task was just added");
+        output.print(&TaskInfo::from(task), || println!("Added task {id}"))
+    }
+}
+
+/// The description of a line of tasks, without its priority marker, and the
+/// priority
+fn parse_line(line: &str) -> Result<(String, Option<Priority>), String> {
+    let mut priority = None;
+    let mut words = Vec::new();
+    for word in line.split_whitespace() {
+        if let Some(value) = word.strip_prefix("priority:") {
+            priority = Some(value.parse::<Priority>().map_err(|e| e.to_string())?);
+        } else if let Some(marker) = word.strip_prefix('!').and_then(|value| value.parse().ok()) {
+            priority = Some(marker);
+        } else {
+            words.push(word);
+        }
+    }
+    if words.is_empty() {
+        return Err("a task needs a description".to_string());
+    }
+    Ok((words.join(" "), priority))
+}
+
+/// A new task with the active context's attributes, and the given priority,
+/// the context's or the default priority
+pub fn new_task(
+    description: String,
+    priority: Option<Priority>,
+    config: &Config,
+) -> anyhow::Result<Task> {
+    let mut task = Task::new(description);
+    if let Some(context) = config.context()? {
+        context.apply_to(&mut task);
+    }
+    if let Some(p) = priority
+        .or_else(|| task.priority())
+        .or(config.default_priority)
+    {
+        task = task.with_priority(p);
+    }
+    Ok(task)
+}
+
+#[cfg(test)]
+mod tests {
+    use rustwarrior_core::Priority;
+    use test_case::test_case;
+
+    use super::parse_line;
+
+    #[test_case("Buy milk" => Ok(("Buy milk".to_string(), None)) ; "plain")]
+    #[test_case("  Call  mum  " => Ok(("Call mum".to_string(), None)) ; "spaces")]
+    #[test_case("Pay rent !1" => Ok(("Pay rent".to_string(), Some(Priority::One))) ; "marker")]
+    #[test_case("priority:3 Water plants" => Ok(("Water plants".to_string(), Some(Priority::Three))) ; "attribute")]
+    #[test_case("Wow! !5 stars" => Ok(("Wow! !5 stars".to_string(), None)) ; "not a marker")]
+    #[test_case("Fix bug priority:9" => Err("invalid priority value '9'. Expected 1-4".to_string()) ; "invalid")]
+    #[test_case("!2" => Err("a task needs a description".to_string()) ; "no description")]
+    fn parse(line: &str) -> Result<(String, Option<Priority>), String> {
+        parse_line(line)
     }
 }
@@ -0,0 +1,92 @@
+use chrono::{DateTime, Local, Utc};
+use clap::Parser;
+use comfy_table::{Attribute, Cell, ContentArrangement, Table, presets::UTF8_HORIZONTAL_ONLY};
+use rustwarrior_core::{Config, paths, store::backup};
+use serde::Serialize;
+
+use crate::output::Output;
+
+/// Manage snapshots of the tasks file
+///
+/// A snapshot is taken before every change, and old snapshots are removed
+/// according to the `backup_count` (default 20) and `backup_days` (default
+/// none, meaning unlimited) settings. Use `rw restore` to go back to one.
+#[derive(Debug, Parser)]
+pub struct Backup {
+    #[clap(subcommand)]
+    command: BackupCommand,
+}
+
+#[derive(Debug, Parser)]
+enum BackupCommand {
+    /// List the snapshots, newest first
+    List,
+}
+
+/// A snapshot, as printed by `rw backup list`
+#[derive(Debug, Serialize)]
+struct SnapshotInfo {
+    id: String,
+    created: String,
+    #[serde(skip)]
+    taken: DateTime<Utc>,
+    tasks: usize,
+    path: String,
+}
+
+impl Backup {
+    /// Run the backup command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        match self.command {
+            BackupCommand::List => list(output, config),
+        }
+    }
+}
+
+fn list(output: Output, config: &Config) -> anyhow::Result<()> {
+    let backups_dir = paths::get_backups_dir(Some(&paths::get_configured_data_dir(config)?))?;
+    let snapshots = backup::list(&backups_dir)?
+        .iter()
+        .map(|snapshot| {
+            Ok(SnapshotInfo {
+                id: snapshot.id().to_string(),
+                created: snapshot.created().to_rfc3339(),
+                taken: snapshot.created(),
+                tasks: snapshot.load()?.len(),
+                path: snapshot.path().display().to_string(),
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    output.print_list(&snapshots, || print_table(&snapshots))
+}
+
+fn print_table(snapshots: &[SnapshotInfo]) {
+    if snapshots.is_empty() {
+        println!("no snapshots yet");
+        return;
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_HORIZONTAL_ONLY)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Snapshot").add_attribute(Attribute::Bold),
+            Cell::new("Taken").add_attribute(Attribute::Bold),
+            Cell::new("Tasks").add_attribute(Attribute::Bold),
+        ]);
+    for snapshot in snapshots {
+        let taken = snapshot
+            .taken
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S");
+        table.add_row(vec![
+            Cell::new(&snapshot.id),
+            Cell::new(taken),
+            Cell::new(snapshot.tasks),
+        ]);
+    }
+
+    println!("{table}");
+}
@@ -0,0 +1,52 @@
+use std::io;
+
+use clap::{Parser, ValueEnum};
+use clap_complete::env::{Bash, EnvCompleter, Fish, Zsh};
+
+use crate::completion;
+
+/// Print a shell script which completes commands, options and task IDs
+///
+/// Task IDs are completed with their descriptions, tags and projects, read
+/// from the tasks as they are when Tab is pressed. Load the script from the
+/// shell's startup file:
+///
+/// bash: echo 'source <(rw completions bash)' >> ~/.bashrc
+///
+/// zsh: echo 'source <(rw completions zsh)' >> ~/.zshrc
+///
+/// fish: echo 'rw completions fish | source' >> ~/.config/fish/config.fish
+#[derive(Debug, Parser)]
+pub struct Completions {
+    /// The shell to complete in
+    #[clap(value_enum)]
+    shell: Shell,
+}
+
+/// A shell with completions
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Completions {
+    /// Run the completions command
+    pub fn run(self) -> anyhow::Result<()> {
+        let shell: &dyn EnvCompleter = match self.shell {
+            Shell::Bash => &Bash,
+            Shell::Zsh => &Zsh,
+            Shell::Fish => &Fish,
+        };
+        let exe = std::env::current_exe()?;
+        shell.write_registration(
+            completion::VAR,
+            "rw",
+            "rw",
+            &exe.to_string_lossy(),
+            &mut io::stdout(),
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,424 @@
+use std::{fs, io, path::Path};
+
+use anyhow::Context;
+use clap::Parser;
+use comfy_table::{Attribute, Cell, ContentArrangement, Table, presets::UTF8_HORIZONTAL_ONLY};
+use rustwarrior_core::{
+    config::{self, Setting},
+    paths,
+    report::Definition,
+};
+use serde::Serialize;
+use toml_edit::{Array, DocumentMut, Item};
+
+use crate::{columns::Column, error::CodedError, output::Output};
+
+/// Show and change settings in the config file
+///
+/// The settings are `data_dir`, `default_priority`, `date_format`, `color`,
+/// `context`, `backup_count`, `backup_days`, `bulk`, `exclusive` and
+/// `timesheet_round`. Contexts are set with keys like `contexts.work`, and
+/// reports with keys like `reports.work.filter`. Environment variables, like
+/// `RUSTWARRIOR_DATA_DIR`, override the file, and flags override both.
+/// Changes keep the comments and layout of the file.
+#[derive(Debug, Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: ConfigCommand,
+}
+
+#[derive(Debug, Parser)]
+enum ConfigCommand {
+    /// Show every setting, where its value comes from, and the contexts and
+    /// reports in the file
+    Show,
+    /// Print the value of a setting
+    Get {
+        /// The setting, like `date_format` or `reports.next.limit`
+        key: String,
+    },
+    /// Change a setting in the config file
+    Set {
+        /// The setting, like `date_format` or `reports.next.limit`
+        key: String,
+        /// The new value. Columns are separated by commas
+        value: String,
+    },
+    /// Remove a setting from the config file, restoring its default
+    Unset {
+        /// The setting, like `date_format` or `reports.next.limit`
+        key: String,
+    },
+}
+
+/// A setting, as printed by `rw config`
+#[derive(Debug, Serialize)]
+struct SettingInfo {
+    key: String,
+    value: Option<String>,
+    /// `default`, `file` or `environment`
+    source: &'static str,
+}
+
+const REPORT_FIELDS: [&str; 5] = ["description", "filter", "sort", "columns", "limit"];
+
+/// A key of the config file
+enum Key {
+    Setting(Setting),
+    Context(String),
+    Report { name: String, field: &'static str },
+}
+
+impl Key {
+    fn parse(key: &str) -> anyhow::Result<Self> {
+        if let Ok(setting) = key.parse() {
+            return Ok(Self::Setting(setting));
+        }
+        let parts: Vec<&str> = key.split('.').collect();
+        if let ["contexts", name] = parts[..] {
+            return Ok(Self::Context(name.to_string()));
+        }
+        if let ["reports", name, field] = parts[..] {
+            if let Some(field) = REPORT_FIELDS.into_iter().find(|known| *known == field) {
+                return Ok(Self::Report {
+                    name: name.to_string(),
+                    field,
+                });
+            }
+        }
+        Err(CodedError::usage(format!(
+            "unknown setting '{key}'. Expected data_dir, default_priority, date_format, color, \
+             context, backup_count, backup_days, bulk, exclusive, timesheet_round, \
+             contexts.<name> or reports.<name>.<field>, where the field is description, filter, \
+             sort, columns or limit"
+        ))
+        .into())
+    }
+
+    /// The tables leading to the key, and its name
+    fn path(&self) -> (Vec<&str>, &str) {
+        match self {
+            Self::Setting(setting) => (vec![], setting.key()),
+            Self::Context(name) => (vec!["contexts"], name),
+            Self::Report { name, field } => (vec!["reports", name], field),
+        }
+    }
+
+    /// The value as it is written in the file
+    fn item(&self, value: &str) -> anyhow::Result<Item> {
+        let whole_number = || {
+            value.parse::<i64>().map_err(|_| {
+                CodedError::usage(format!("invalid value '{value}'. Expected a whole number"))
+            })
+        };
+        Ok(match self {
+            Self::Setting(Setting::BackupCount | Setting::BackupDays)
+                if value.trim().eq_ignore_ascii_case("none") =>
+            {
+                toml_edit::value("none")
+            }
+            Self::Setting(
+                Setting::DefaultPriority
+                | Setting::BackupCount
+                | Setting::BackupDays
+                | Setting::Bulk
+                | Setting::TimesheetRound,
+            )
+            | Self::Report { field: "limit", .. } => toml_edit::value(whole_number()?),
+            Self::Setting(Setting::Exclusive) => {
+                toml_edit::value(value.parse::<bool>().map_err(|_| {
+                    CodedError::usage(format!("invalid value '{value}'. Expected true or false"))
+                })?)
+            }
+            Self::Report {
+                field: "columns", ..
+            } => toml_edit::value(
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|column| !column.is_empty())
+                    .collect::<Array>(),
+            ),
+            _ => toml_edit::value(value),
+        })
+    }
+}
+
+impl Config {
+    /// Run the config command
+    pub fn run(self, output: Output) -> anyhow::Result<()> {
+        let path = paths::get_config_file()?;
+        match self.command {
+            ConfigCommand::Show => show(output, &path),
+            ConfigCommand::Get { key } => get(output, &path, &key),
+            ConfigCommand::Set { key, value } => set(output, &path, key, value),
+            ConfigCommand::Unset { key } => unset(output, &path, key),
+        }
+    }
+}
+
+fn show(output: Output, path: &Path) -> anyhow::Result<()> {
+    let file = config::Config::load(path)?;
+    let mut settings = Setting::ALL
+        .into_iter()
+        .map(|setting| lookup(&Key::Setting(setting), setting.key(), path))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    for (name, filter) in &file.contexts {
+        settings.push(SettingInfo {
+            key: format!("contexts.{name}"),
+            value: Some(filter.clone()),
+            source: "file",
+        });
+    }
+    for (name, definition) in &file.reports {
+        for (field, value) in report_fields(definition) {
+            settings.push(SettingInfo {
+                key: format!("reports.{name}.{field}"),
+                value: Some(value),
+                source: "file",
+            });
+        }
+    }
+
+    output.print_list(&settings, || {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_HORIZONTAL_ONLY)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(
+                ["Key", "Value", "Source"]
+                    .map(|heading| Cell::new(heading).add_attribute(Attribute::Bold)),
+            );
+        for setting in &settings {
+            table.add_row([
+                setting.key.as_str(),
+                setting.value.as_deref().unwrap_or_default(),
+                setting.source,
+            ]);
+        }
+        println!("{table}");
+        println!("Config file: {}", path.display());
+    })
+}
+
+fn get(output: Output, path: &Path, key: &str) -> anyhow::Result<()> {
+    let setting = lookup(&Key::parse(key)?, key, path)?;
+    let Some(value) = &setting.value else {
+        return Err(CodedError::not_found(format!("{key} isn't set")).into());
+    };
+    output.print(&setting, || println!("{value}"))
+}
+
+fn set(output: Output, path: &Path, key: String, value: String) -> anyhow::Result<()> {
+    let parsed = Key::parse(&key)?;
+    let (tables, name) = parsed.path();
+    let mut document = read(path)?;
+    let mut table = document.as_table_mut();
+    for (depth, name) in tables.into_iter().enumerate() {
+        let item = table.entry(name).or_insert_with(|| {
+            let mut table = toml_edit::Table::new();
+            // `[reports.work]` rather than an empty `[reports]`
+            table.set_implicit(depth == 0);
+            Item::Table(table)
+        });
+        table = item.as_table_mut().ok_or_else(|| {
+            CodedError::conflict(format!("{name} in {} isn't a table", path.display()))
+        })?;
+    }
+    // Replace just the value, keeping the comments around it
+    match (table.get_mut(name), parsed.item(&value)?) {
+        (Some(Item::Value(existing)), Item::Value(mut new)) => {
+            *new.decor_mut() = existing.decor().clone();
+            *existing = new;
+        }
+        (_, item) => {
+            table.insert(name, item);
+        }
+    }
+    write(path, &document)?;
+
+    let setting = SettingInfo {
+        key,
+        value: Some(value),
+        source: "file",
+    };
+    output.print(&setting, || {
+        println!(
+            "Set {} to {}",
+            setting.key,
+            setting.value.as_deref().unwrap_or_default()
+        );
+    })
+}
+
+fn unset(output: Output, path: &Path, key: String) -> anyhow::Result<()> {
+    let parsed = Key::parse(&key)?;
+    let mut document = read(path)?;
+    if !remove(&mut document, &parsed) {
+        return Err(CodedError::not_found(format!("{key} isn't set in {}", path.display())).into());
+    }
+    write(path, &document)?;
+
+    let setting = SettingInfo {
+        key,
+        value: None,
+        source: "file",
+    };
+    output.print(&setting, || println!("Unset {}", setting.key))
+}
+
+/// The value of a key after the defaults, the file and the environment
+fn lookup(key: &Key, name: &str, path: &Path) -> anyhow::Result<SettingInfo> {
+    let file = config::Config::load(path)?;
+    let (value, source) = match key {
+        Key::Setting(setting) => {
+            let config = file.clone().with_env()?;
+            let source = if std::env::var(setting.env()).is_ok_and(|value| !value.is_empty()) {
+                "environment"
+            } else if file.value(*setting).is_some() {
+                "file"
+            } else {
+                "default"
+            };
+            let value = config.value(*setting).or_else(|| match setting {
+                Setting::DataDir => paths::default_data_dir()
+                    .ok()
+                    .map(|dir| dir.display().to_string()),
+                Setting::DateFormat => Some(config.date_format().to_string()),
+                Setting::Color => Some(config.color().to_string()),
+                Setting::BackupCount => Some(
+                    config
+                        .retention()
+                        .count
+                        .map_or_else(|| "none".to_string(), |count| count.to_string()),
+                ),
+                Setting::BackupDays => Some(
+                    config
+                        .retention()
+                        .age
+                        .map_or_else(|| "none".to_string(), |age| age.num_days().to_string()),
+                ),
+                Setting::Bulk => Some(config.bulk().to_string()),
+                Setting::Exclusive => Some(config.exclusive().to_string()),
+                Setting::TimesheetRound => Some(config.timesheet_round().to_string()),
+                Setting::DefaultPriority | Setting::Context => None,
+            });
+            (value, source)
+        }
+        Key::Context(name) => (file.contexts.get(name).cloned(), "file"),
+        Key::Report { name, field } => {
+            let reports = file
+                .reports()
+                .map_err(|e| CodedError::usage(e.to_string()))?;
+            let report = reports
+                .get(name)
+                .ok_or_else(|| CodedError::not_found(format!("no report named '{name}'")))?;
+            let value = match *field {
+                "description" => Some(report.description().to_string()),
+                "filter" => Some(report.filter().to_string()),
+                "sort" => Some(report.sort().to_string()),
+                "columns" => Some(report.columns().join(",")),
+                _ => report.limit().map(|limit| limit.to_string()),
+            }
+            .filter(|value| !value.is_empty());
+            let in_file = file
+                .reports
+                .get(name)
+                .is_some_and(|definition| report_fields(definition).any(|(set, _)| set == *field));
+            (value, if in_file { "file" } else { "default" })
+        }
+    };
+    Ok(SettingInfo {
+        key: name.to_string(),
+        value,
+        source,
+    })
+}
+
+/// The fields a report definition sets, and their values
+fn report_fields(definition: &Definition) -> impl Iterator<Item = (&'static str, String)> {
+    [
+        ("description", definition.description.clone()),
+        ("filter", definition.filter.clone()),
+        ("sort", definition.sort.clone()),
+        (
+            "columns",
+            definition.columns.as_ref().map(|columns| columns.join(",")),
+        ),
+        ("limit", definition.limit.map(|limit| limit.to_string())),
+    ]
+    .into_iter()
+    .filter_map(|(field, value)| Some((field, value?)))
+}
+
+/// Remove a key, and the context or report table holding it if it is left
+/// empty
+fn remove(document: &mut DocumentMut, key: &Key) -> bool {
+    match key {
+        Key::Setting(setting) => document.remove(setting.key()).is_some(),
+        Key::Context(name) => {
+            let Some(contexts) = document
+                .get_mut("contexts")
+                .and_then(Item::as_table_like_mut)
+            else {
+                return false;
+            };
+            let removed = contexts.remove(name).is_some();
+            if contexts.is_empty() {
+                document.remove("contexts");
+            }
+            removed
+        }
+        Key::Report { name, field } => {
+            let Some(reports) = document
+                .get_mut("reports")
+                .and_then(Item::as_table_like_mut)
+            else {
+                return false;
+            };
+            let Some(report) = reports.get_mut(name).and_then(Item::as_table_like_mut) else {
+                return false;
+            };
+            let removed = report.remove(field).is_some();
+            if report.is_empty() {
+                reports.remove(name);
+            }
+            if reports.is_empty() {
+                document.remove("reports");
+            }
+            removed
+        }
+    }
+}
+
+/// Read the config file for changing, keeping its comments and layout
+pub fn read(path: &Path) -> anyhow::Result<DocumentMut> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).context(format!("failed to read {}", path.display())),
+    };
+    text.parse()
+        .map_err(|e| CodedError::usage(format!("invalid config file {}: {e}", path.display())))
+        .map_err(Into::into)
+}
+
+/// Check the changed file is still valid, then save it
+pub fn write(path: &Path, document: &DocumentMut) -> anyhow::Result<()> {
+    let text = document.to_string();
+    let config = text
+        .parse::<config::Config>()
+        .map_err(|e| CodedError::usage(e.to_string()))?;
+    // Columns are only names to the core, so aren't checked when parsing
+    for report in config
+        .reports()
+        .map_err(|e| CodedError::usage(e.to_string()))?
+        .iter()
+    {
+        Column::of_report(report)?;
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, text).with_context(|| format!("failed to write {}", path.display()))
+}
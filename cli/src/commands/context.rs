@@ -0,0 +1,135 @@
+use clap::Parser;
+use clap_complete::ArgValueCandidates;
+use comfy_table::{Attribute, Cell, ContentArrangement, Table, presets::UTF8_HORIZONTAL_ONLY};
+use rustwarrior_core::{Config, config::Setting, context, paths};
+use serde::Serialize;
+
+use crate::{
+    commands::config::{read, write},
+    completion,
+    error::CodedError,
+    output::Output,
+};
+
+/// Choose the context, a filter applied to every listing and report
+///
+/// New tasks get the tags, project and priority the context's filter asks
+/// for. Contexts are defined in the config file, for example with `rw config
+/// set contexts.work "+work or project:office"`. `RUSTWARRIOR_CONTEXT` and
+/// `--context` override the chosen context.
+///
+/// rw context work
+///
+/// rw context none
+///
+/// rw context list
+#[derive(Debug, Parser)]
+pub struct Context {
+    /// The context to switch to, `none` to turn contexts off, or `list` to
+    /// list them. Shows the active context if left out
+    #[clap(add = ArgValueCandidates::new(completion::contexts))]
+    name: Option<String>,
+}
+
+/// A context, as printed by `rw context`
+#[derive(Debug, Serialize)]
+struct ContextInfo {
+    name: String,
+    filter: String,
+    active: bool,
+}
+
+impl Context {
+    /// Run the context command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        match self.name.as_deref() {
+            None => show(output, config),
+            Some("list") => list(output, config),
+            Some(name) => switch(output, name),
+        }
+    }
+}
+
+fn show(output: Output, config: &Config) -> anyhow::Result<()> {
+    let context = config.context()?.map(|context| ContextInfo {
+        name: context.name().to_string(),
+        filter: context.filter_text().to_string(),
+        active: true,
+    });
+    output.print(&context, || match &context {
+        Some(context) => println!("Context {}: {}", context.name, context.filter),
+        None => println!("No context"),
+    })
+}
+
+fn list(output: Output, config: &Config) -> anyhow::Result<()> {
+    let active = config.context()?;
+    let contexts: Vec<ContextInfo> = config
+        .contexts()?
+        .into_iter()
+        .map(|context| ContextInfo {
+            active: active
+                .as_ref()
+                .is_some_and(|active| active.name() == context.name()),
+            name: context.name().to_string(),
+            filter: context.filter_text().to_string(),
+        })
+        .collect();
+
+    output.print_list(&contexts, || {
+        if contexts.is_empty() {
+            println!("no contexts are defined");
+            return;
+        }
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_HORIZONTAL_ONLY)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(
+                ["Name", "Filter", "Active"]
+                    .map(|heading| Cell::new(heading).add_attribute(Attribute::Bold)),
+            );
+        for context in &contexts {
+            table.add_row([
+                context.name.as_str(),
+                context.filter.as_str(),
+                if context.active { "yes" } else { "" },
+            ]);
+        }
+        println!("{table}");
+    })
+}
+
+fn switch(output: Output, name: &str) -> anyhow::Result<()> {
+    let path = paths::get_config_file()?;
+    let file = Config::load(&path)?;
+    let context = if name == context::NONE {
+        None
+    } else {
+        Some(
+            file.find_context(name)
+                .map_err(|e| CodedError::not_found(e.to_string()))?,
+        )
+    };
+
+    let mut document = read(&path)?;
+    match &context {
+        Some(context) => {
+            document.insert(Setting::Context.key(), toml_edit::value(context.name()));
+        }
+        None => {
+            document.remove(Setting::Context.key());
+        }
+    }
+    write(&path, &document)?;
+
+    let info = context.as_ref().map(|context| ContextInfo {
+        name: context.name().to_string(),
+        filter: context.filter_text().to_string(),
+        active: true,
+    });
+    output.print(&info, || match &info {
+        Some(info) => println!("Switched to context {}: {}", info.name, info.filter),
+        None => println!("Turned contexts off"),
+    })
+}
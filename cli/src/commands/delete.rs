@@ -0,0 +1,78 @@
+use anyhow::bail;
+use clap::Parser;
+use clap_complete::ArgValueCandidates;
+use rustwarrior_core::{Config, Store};
+
+use crate::{
+    completion,
+    error::CodedError,
+    output::{Output, TaskInfo},
+    prompt,
+    selector::Selector,
+    store_ext::StoreExt,
+};
+
+/// Delete tasks
+///
+/// Shows the tasks which will be deleted, and asks for confirmation. Deleted
+/// tasks are hidden, and kept until `rw purge` removes them for good.
+#[derive(Debug, Parser)]
+pub struct Delete {
+    /// The tasks to delete: IDs like `1,3,5-8`, or a filter like `+work` or
+    /// `project:home`. Quote filters with more than one term.
+    #[clap(add = ArgValueCandidates::new(completion::selectors))]
+    selector: Selector,
+    /// Delete the tasks without asking for confirmation
+    #[clap(long, short)]
+    yes: bool,
+}
+
+impl Delete {
+    /// Run the delete command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        let mut store = Store::load_default(config)?;
+        let selection = self.selector.select(&store);
+        if selection.ids.is_empty() && selection.missing.is_empty() {
+            bail!(CodedError::not_found("no tasks match the filter"));
+        }
+
+        if !selection.ids.is_empty() && !self.yes {
+            if output == Output::Table {
+                println!("The following tasks will be deleted:");
+                for task in selection.ids.iter().filter_map(|id| store.get(*id)) {
+                    println!("  {} {}", task.id(), task.description());
+                }
+            }
+            let question = format!("Delete {} task(s)?", selection.ids.len());
+            if !prompt::confirm(&question, "--yes")? {
+                return output.print_list::<TaskInfo>(&[], || println!("Nothing deleted"));
+            }
+        }
+
+        for id in &selection.ids {
+            if let Some(task) = store.get_mut(*id) {
+                task.task_mut().mark_deleted();
+            }
+        }
+        if !selection.ids.is_empty() {
+            store.save_default(config)?;
+        }
+
+        let deleted: Vec<TaskInfo> = selection
+            .ids
+            .iter()
+            .filter_map(|id| store.get(*id))
+            .map(TaskInfo::from)
+            .collect();
+        output.print_list(&deleted, || {
+            for task in &deleted {
+                println!("Deleted task {} '{}'", task.id, task.description);
+            }
+        })?;
+
+        if !selection.missing.is_empty() {
+            bail!(CodedError::tasks_not_found(&selection.missing));
+        }
+        Ok(())
+    }
+}
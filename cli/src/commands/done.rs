@@ -0,0 +1,154 @@
+use anyhow::bail;
+use clap::Parser;
+use clap_complete::ArgValueCandidates;
+use rustwarrior_core::{Config, Store, Task};
+use serde::Serialize;
+
+use crate::{
+    completion,
+    error::CodedError,
+    ids::{self, IdRange},
+    output::{Output, TaskInfo},
+    store_ext::StoreExt,
+};
+
+/// Mark tasks as completed
+///
+/// IDs can be listed separately or as ranges, e.g. `rw done 1 3 5-8`.
+#[derive(Debug, Parser)]
+pub struct Done {
+    /// The IDs of the tasks, or ranges like `5-8`
+    #[clap(
+        required = true,
+        value_delimiter = ',',
+        add = ArgValueCandidates::new(completion::open_ids)
+    )]
+    ids: Vec<IdRange>,
+}
+
+impl Done {
+    /// Run the done command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        update_each(
+            &self.ids,
+            output,
+            config,
+            &Action {
+                changed: "Completed",
+                unchanged: "is already completed",
+                apply: |task| {
+                    if task.is_completed() {
+                        return false;
+                    }
+                    task.mark_completed();
+                    true
+                },
+            },
+        )
+    }
+}
+
+/// A change applied by [`update_each`] to each task
+pub struct Action {
+    /// What was done to a task which changed, e.g. "Completed"
+    pub changed: &'static str,
+    /// Why a task didn't change, e.g. "is already completed"
+    pub unchanged: &'static str,
+    /// Change the task, returning whether anything changed
+    pub apply: fn(&mut Task) -> bool,
+}
+
+/// What happened to one of the tasks named on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Outcome {
+    Changed,
+    Unchanged,
+    NotFound,
+}
+
+/// The result for one ID, as printed in JSON mode
+#[derive(Debug, Serialize)]
+struct IdResult {
+    id: usize,
+    outcome: Outcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task: Option<TaskInfo>,
+}
+
+/// Apply an [`Action`] to each of the given tasks, reporting on each ID
+///
+/// Missing IDs don't stop the other tasks from being changed, but make the
+/// command fail once the changes are saved.
+pub fn update_each(
+    ranges: &[IdRange],
+    output: Output,
+    config: &Config,
+    action: &Action,
+) -> anyhow::Result<()> {
+    let mut store = Store::load_default(config)?;
+
+    let expanded = ids::expand(ranges, &ids::live(&store));
+    let mut results: Vec<IdResult> = expanded
+        .ids
+        .into_iter()
+        .map(|id| {
+            let Some(task) = store.get_mut(id).filter(|task| !task.is_deleted()) else {
+                return IdResult {
+                    id,
+                    outcome: Outcome::NotFound,
+                    task: None,
+                };
+            };
+            let outcome = if (action.apply)(task.task_mut()) {
+                Outcome::Changed
+            } else {
+                Outcome::Unchanged
+            };
+            IdResult {
+                id,
+                outcome,
+                task: Some(TaskInfo::from(&*task)),
+            }
+        })
+        .collect();
+    // Missing ranges are only reported in the error, as they can be huge
+    results.extend(
+        expanded
+            .missing
+            .iter()
+            .filter(|range| range.is_single())
+            .map(|range| IdResult {
+                id: range.first(),
+                outcome: Outcome::NotFound,
+                task: None,
+            }),
+    );
+    results.sort_by_key(|result| result.id);
+
+    if results
+        .iter()
+        .any(|result| result.outcome == Outcome::Changed)
+    {
+        store.save_default(config)?;
+    }
+
+    output.print_list(&results, || {
+        for result in &results {
+            let Some(task) = &result.task else { continue };
+            match result.outcome {
+                Outcome::Changed => println!(
+                    "{} task {} '{}'",
+                    action.changed, result.id, task.description
+                ),
+                Outcome::Unchanged => println!("Task {} {}", result.id, action.unchanged),
+                Outcome::NotFound => {}
+            }
+        }
+    })?;
+
+    if !expanded.missing.is_empty() {
+        bail!(CodedError::tasks_not_found(&expanded.missing));
+    }
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use std::{env, fs, path::Path, process::Command};
+
+use anyhow::{Context, bail};
+use clap::Parser;
+use clap_complete::ArgValueCandidates;
+use rustwarrior_core::{Config, Store, Task};
+use serde::Serialize;
+
+use crate::{
+    completion,
+    diff::{FieldChange, diff, print_changes},
+    editable,
+    error::CodedError,
+    output::{Output, TaskInfo},
+    store_ext::StoreExt,
+};
+
+/// Edit a task in your editor
+///
+/// The task is opened as `name: value` lines in `$VISUAL` or `$EDITOR`. If
+/// the result has errors, the editor is reopened with them marked, until the
+/// task is valid or the file is closed without changes.
+#[derive(Debug, Parser)]
+pub struct Edit {
+    /// The ID of the task
+    #[clap(add = ArgValueCandidates::new(completion::ids))]
+    id: usize,
+}
+
+/// The changes made to the task, as printed in JSON mode
+#[derive(Debug, Serialize)]
+struct Edited {
+    id: usize,
+    changes: Vec<FieldChange>,
+    task: TaskInfo,
+}
+
+impl Edit {
+    /// Run the edit command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        let mut store = Store::load_default(config)?;
+        let task = store
+            .get(self.id)
+            .filter(|task| !task.is_deleted())
+            .ok_or_else(|| CodedError::not_found(format!("task {} not found", self.id)))?;
+        let before = task.task().clone();
+
+        let path = env::temp_dir().join(format!("rw-edit-{}.txt", task.uuid()));
+        let result = edit_until_valid(&path, editable::render(self.id, task), &before);
+        let _ = fs::remove_file(&path);
+        if let Some(edited) = result? {
+            let task = store.get_mut(self.id).expect("task was found above");
+            if edited.apply(task.task_mut()) {
+                store.save_default(config)?;
+            }
+        }
+
+        let task = store.get(self.id).expect("task was found above");
+        let edited = Edited {
+            id: self.id,
+            changes: diff(&before, task),
+            task: TaskInfo::from(task),
+        };
+        output.print(&edited, || print_changes(edited.id, &edited.changes))
+    }
+}
+
+/// Open the editor until the text parses, or it is closed without changes
+fn edit_until_valid(
+    path: &Path,
+    mut text: String,
+    task: &Task,
+) -> anyhow::Result<Option<editable::Edited>> {
+    loop {
+        fs::write(path, &text).context("failed to write the file to edit")?;
+        run_editor(path)?;
+        let edited = fs::read_to_string(path).context("failed to read the edited file")?;
+        if edited == text {
+            return Ok(None);
+        }
+
+        match editable::parse(&edited, task.extra()) {
+            Ok(task) => return Ok(Some(task)),
+            Err(errors) => {
+                eprintln!("The task has errors. Reopening the editor");
+                text = editable::annotate(&edited, &errors);
+            }
+        }
+    }
+}
+
+fn run_editor(path: &Path) -> anyhow::Result<()> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut words = editor.split_whitespace();
+    let Some(program) = words.next() else {
+        bail!(CodedError::usage("$EDITOR is empty"));
+    };
+
+    let status = Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .with_context(|| format!("failed to run editor {program:?}"))?;
+    if !status.success() {
+        bail!("editor {program:?} exited with {status}");
+    }
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use std::{fs, io::Write, path::PathBuf};
+
+use anyhow::{Context, bail};
+use clap::Parser;
+use rustwarrior_core::{
+    Config, OpenTask, Store,
+    format::csv::{self, Column},
+};
+
+use crate::{error::CodedError, format::Format, store_ext::StoreExt};
+
+/// Export tasks for use in another tool
+#[derive(Debug, Parser)]
+pub struct Export {
+    /// The file to write. Writes to stdout if omitted or `-`
+    file: Option<PathBuf>,
+    /// The format to write
+    #[clap(long, short, value_enum, default_value_t)]
+    format: Format,
+    /// The columns to write, in order, when exporting CSV
+    ///
+    /// One or more of uuid, description, priority, project, tags, created,
+    /// modified, start, end, completed and extra. Defaults to all of them.
+    #[clap(long, short, value_delimiter = ',')]
+    columns: Vec<Column>,
+}
+
+impl Export {
+    /// Run the export command
+    pub fn run(self, config: &Config) -> anyhow::Result<()> {
+        let store = Store::load_default(config)?;
+        // Only Taskwarrior has a way to mark tasks as deleted
+        let tasks = store
+            .iter()
+            .filter(|task| self.format == Format::Taskwarrior || !task.is_deleted())
+            .map(OpenTask::task);
+
+        let output = match (self.format, self.columns.is_empty()) {
+            (_, true) => self.format.render(tasks)?,
+            (Format::Csv, false) => csv::render(tasks, &self.columns)?,
+            (_, false) => bail!(CodedError::usage(
+                "--columns can only be used with --format csv"
+            )),
+        };
+
+        match &self.file {
+            Some(path) if path.as_os_str() != "-" => fs::write(path, output)
+                .with_context(|| format!("failed to write {}", path.display()))?,
+            _ => std::io::stdout().write_all(output.as_bytes())?,
+        }
+
+        Ok(())
+    }
+}
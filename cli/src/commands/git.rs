@@ -0,0 +1,208 @@
+use std::{
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{Context, bail};
+use clap::Parser;
+use rustwarrior_core::{
+    Config,
+    git::{self, MERGE_DRIVER},
+    paths,
+    store::merge_files,
+};
+
+use crate::{
+    error::CodedError,
+    output::{DataDirInfo, Output},
+    store_ext,
+};
+
+/// Files in the data directory which are local to each replica
+const IGNORED: &[&str] = &["sync_state.json", "backups/"];
+
+/// Keep the data directory under git version control
+///
+/// Once initialised, every change made with `rw` is committed automatically.
+/// Any other git command is run in the data directory, e.g. `rw git push`.
+#[derive(Debug, Parser)]
+pub struct Git {
+    #[clap(subcommand)]
+    command: GitCommand,
+}
+
+#[derive(Debug, Parser)]
+enum GitCommand {
+    /// Turn the data directory into a git repository
+    ///
+    /// Also installs the merge driver for the tasks file. Run this again
+    /// after cloning the repository by hand.
+    Init,
+    /// Clone an existing task repository into an empty data directory
+    Clone {
+        /// The repository to clone
+        url: String,
+    },
+    /// Merge three versions of the tasks file by task UUID (invoked by git)
+    #[clap(hide = true)]
+    MergeDriver {
+        /// The common ancestor
+        base: PathBuf,
+        /// Our version, which receives the result
+        ours: PathBuf,
+        /// Their version
+        theirs: PathBuf,
+    },
+    #[clap(external_subcommand)]
+    External(Vec<OsString>),
+}
+
+impl Git {
+    /// Run the git command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        match self.command {
+            GitCommand::Init => init(output, config),
+            GitCommand::Clone { url } => clone(&url, output, config),
+            GitCommand::MergeDriver { base, ours, theirs } => {
+                merge_files(base, ours, theirs)?;
+                Ok(())
+            }
+            GitCommand::External(args) => external(&args, config),
+        }
+    }
+}
+
+fn init(output: Output, config: &Config) -> anyhow::Result<()> {
+    let data_dir = paths::get_configured_data_dir(config)?;
+    if !git::is_repo(&data_dir) {
+        git::run(&data_dir, ["init", "--quiet"])?;
+    }
+    configure(&data_dir)?;
+    // The repository is usable even if the first commit fails
+    if let Err(warning) = store_ext::auto_commit(&data_dir) {
+        eprintln!("warning: {warning}");
+    }
+
+    output.print(
+        &DataDirInfo {
+            data_dir: &data_dir,
+        },
+        || {
+            println!("Initialised git repository in {}", data_dir.display());
+        },
+    )
+}
+
+fn clone(url: &str, output: Output, config: &Config) -> anyhow::Result<()> {
+    let data_dir = paths::get_configured_data_dir(config)?;
+    let tasks_file = paths::get_tasks_file(Some(&data_dir))?;
+
+    // Loading an empty store creates an empty tasks file, which is safe to
+    // replace
+    if fs::metadata(&tasks_file).is_ok_and(|meta| meta.len() == 0) {
+        fs::remove_file(&tasks_file)?;
+    }
+    if fs::read_dir(&data_dir)?.next().is_some() {
+        bail!(CodedError::usage(format!(
+            "data directory {} is not empty. Use `rw git init` to version existing tasks",
+            data_dir.display()
+        )));
+    }
+
+    git::run(&data_dir, ["clone", "--quiet", url, "."])?;
+    configure(&data_dir)?;
+
+    output.print(
+        &DataDirInfo {
+            data_dir: &data_dir,
+        },
+        || {
+            println!("Cloned {url} into {}", data_dir.display());
+        },
+    )
+}
+
+/// Install the merge driver and ignore replica-local files
+fn configure(data_dir: &Path) -> anyhow::Result<()> {
+    let tasks_file = paths::get_tasks_file(Some(data_dir))?;
+    let tasks_file_name = tasks_file
+        .file_name()
+        .context("tasks file has no name")?
+        .to_string_lossy();
+
+    append_missing_line(
+        &data_dir.join(".gitattributes"),
+        &format!("{tasks_file_name} merge={MERGE_DRIVER}"),
+    )?;
+    for ignored in IGNORED {
+        append_missing_line(&data_dir.join(".gitignore"), ignored)?;
+    }
+
+    let exe = std::env::current_exe().context("unable to locate the rw executable")?;
+    let exe = exe
+        .to_str()
+        .context("the path to the rw executable isn't valid UTF-8")?;
+    let driver = format!(
+        "{} git merge-driver %O %A %B",
+        shlex::try_quote(exe).context("the path to the rw executable can't be quoted")?
+    );
+    git::run(
+        data_dir,
+        [
+            "config",
+            &format!("merge.{MERGE_DRIVER}.name"),
+            "RustWarrior task merge by UUID",
+        ],
+    )?;
+    git::run(
+        data_dir,
+        ["config", &format!("merge.{MERGE_DRIVER}.driver"), &driver],
+    )?;
+
+    Ok(())
+}
+
+fn append_missing_line(path: &Path, line: &str) -> anyhow::Result<()> {
+    let mut contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    if contents.lines().any(|existing| existing.trim() == line) {
+        return Ok(());
+    }
+
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push_str(line);
+    contents.push('\n');
+    fs::write(path, contents)?;
+
+    Ok(())
+}
+
+fn external(args: &[OsString], config: &Config) -> anyhow::Result<()> {
+    let data_dir = paths::get_configured_data_dir(config)?;
+    if !git::is_repo(&data_dir) {
+        bail!(CodedError::usage(
+            "data directory is not a git repository. Run `rw git init` first"
+        ));
+    }
+
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(&data_dir)
+        .args(args)
+        .status()
+        .context("failed to run git. Is it installed?")?;
+
+    if !status.success() {
+        bail!("git exited with {status}");
+    }
+
+    Ok(())
+}
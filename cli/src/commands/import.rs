@@ -0,0 +1,129 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+use anyhow::{Context, bail};
+use clap::Parser;
+use rustwarrior_core::{
+    Config, Store,
+    format::csv::{self, Column, Mapping},
+};
+use serde::Serialize;
+
+use crate::{error::CodedError, format::Format, output::Output, store_ext::StoreExt};
+
+/// Import tasks from another tool
+///
+/// Tasks which already exist (matched by UUID) are updated in place, so
+/// importing the same file twice is safe. Where a task was changed both here
+/// and in the file, the newer change wins. Edits to an exported file are
+/// imported even if the file doesn't record when they were made.
+#[derive(Debug, Parser)]
+pub struct Import {
+    /// The file to import. Reads from stdin if omitted or `-`
+    file: Option<PathBuf>,
+    /// The format of the file
+    #[clap(long, short, value_enum, default_value_t)]
+    format: Format,
+    /// Read a CSV header into a task field, e.g. `--map Title=description`
+    ///
+    /// Headers which aren't mapped are matched against the field names.
+    #[clap(long = "map", value_name = "HEADER=FIELD", value_parser = parse_mapping)]
+    mappings: Vec<(String, Column)>,
+}
+
+impl Import {
+    /// Run the import command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        let input = match &self.file {
+            Some(path) if path.as_os_str() != "-" => fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?,
+            _ => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                input
+            }
+        };
+
+        if self.format != Format::Csv && !self.mappings.is_empty() {
+            bail!(CodedError::usage(
+                "--map can only be used with --format csv"
+            ));
+        }
+
+        let mapping = self
+            .mappings
+            .into_iter()
+            .fold(Mapping::new(), |mapping, (header, column)| {
+                mapping.with(header, column)
+            });
+
+        let mut store = Store::load_default(config)?;
+        let report = Report::from(self.format.import(&mut store, &input, &mapping)?);
+        store.save_default(config)?;
+
+        output.print(&report, || report.print())
+    }
+}
+
+/// The outcome of an import
+#[derive(Debug, Default, Serialize)]
+struct Report {
+    /// Tasks which were not previously in the store
+    added: usize,
+    /// Existing tasks which were changed
+    updated: usize,
+    /// Existing tasks which were already up to date
+    unchanged: usize,
+    /// CSV headers which weren't mapped to any field
+    ignored_columns: Vec<String>,
+    /// CSV rows which couldn't be imported
+    errors: Vec<RowError>,
+}
+
+#[derive(Debug, Serialize)]
+struct RowError {
+    line: usize,
+    message: String,
+}
+
+impl From<csv::Report> for Report {
+    fn from(report: csv::Report) -> Self {
+        Self {
+            added: report.summary.added,
+            updated: report.summary.updated,
+            unchanged: report.summary.unchanged,
+            ignored_columns: report.ignored,
+            errors: report
+                .errors
+                .into_iter()
+                .map(|error| RowError {
+                    line: error.line,
+                    message: error.message,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Report {
+    fn print(&self) {
+        println!(
+            "Imported {} new, {} updated, {} unchanged",
+            self.added, self.updated, self.unchanged
+        );
+        if !self.ignored_columns.is_empty() {
+            println!("Ignored columns: {}", self.ignored_columns.join(", "));
+        }
+        for error in &self.errors {
+            eprintln!("Skipped line {}: {}", error.line, error.message);
+        }
+    }
+}
+
+fn parse_mapping(input: &str) -> anyhow::Result<(String, Column)> {
+    let (header, column) = input.rsplit_once('=').context("expected HEADER=FIELD")?;
+    Ok((header.to_string(), column.parse()?))
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Local, Utc};
+use clap::Parser;
+use clap_complete::ArgValueCandidates;
+use comfy_table::{Attribute, Cell, ContentArrangement, Table, presets::UTF8_HORIZONTAL_ONLY};
+use rustwarrior_core::{Config, OpenTask, Store};
+
+use crate::{
+    completion, duration,
+    error::CodedError,
+    output::{Output, TaskInfo},
+    store_ext::StoreExt,
+};
+
+/// Show all the details of a task
+#[derive(Debug, Parser)]
+pub struct Info {
+    /// The ID of the task
+    #[clap(add = ArgValueCandidates::new(completion::ids))]
+    id: usize,
+}
+
+impl Info {
+    /// Run the info command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        let store = Store::load_default(config)?;
+        let task = store
+            .get(self.id)
+            .ok_or_else(|| CodedError::not_found(format!("task {} not found", self.id)))?;
+
+        output.print(&TaskInfo::from(task), || print_table(task))
+    }
+}
+
+fn print_table(task: &OpenTask) {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_HORIZONTAL_ONLY)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new("Name").add_attribute(Attribute::Bold),
+            Cell::new("Value").add_attribute(Attribute::Bold),
+        ]);
+    for (name, value) in details(task) {
+        table.add_row(vec![Cell::new(name), Cell::new(value)]);
+    }
+
+    println!("{table}");
+}
+
+/// The names and values of a task's attributes which are set
+pub fn details(task: &OpenTask) -> Vec<(&'static str, String)> {
+    let mut rows = vec![
+        ("ID", task.id().to_string()),
+        ("UUID", task.uuid().to_string()),
+        ("Description", task.description().clone()),
+        (
+            "Status",
+            if task.is_deleted() {
+                "Deleted"
+            } else if task.is_completed() {
+                "Completed"
+            } else if task.is_started() {
+                "Started"
+            } else {
+                "Pending"
+            }
+            .to_string(),
+        ),
+    ];
+    if let Some(priority) = task.priority() {
+        rows.push(("Priority", priority.to_string()));
+    }
+    if let Some(project) = task.project() {
+        rows.push(("Project", project.to_string()));
+    }
+    if !task.tags().is_empty() {
+        let tags: Vec<&str> = task.tags().iter().map(String::as_str).collect();
+        rows.push(("Tags", tags.join(" ")));
+    }
+    rows.push(("Created", format_timestamp(task.created())));
+    rows.push(("Modified", format_timestamp(task.modified())));
+    if let Some(start) = task.start() {
+        rows.push(("Start", format_timestamp(start)));
+    }
+    if task.is_started() || !task.intervals().is_empty() {
+        rows.push(("Tracked", duration::format(task.tracked(Utc::now()))));
+    }
+    if let Some(end) = task.end() {
+        rows.push(("End", format_timestamp(end)));
+    }
+    if let Some(deleted) = task.deleted() {
+        rows.push(("Deleted", format_timestamp(deleted)));
+    }
+    rows
+}
+
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp
+        .with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
@@ -1,54 +1,94 @@
+use chrono::{Local, Utc};
 use clap::Parser;
-use comfy_table::{Attribute, Cell, ContentArrangement, Table, presets::UTF8_HORIZONTAL_ONLY};
-use rustwarrior_core::Store;
+use clap_complete::ArgValueCandidates;
+use rustwarrior_core::{Config, Filter, OpenTask, Store, sort::Sort};
 
-use crate::store_ext::StoreExt;
+use crate::{
+    columns::{self, Column},
+    completion, duration,
+    error::CodedError,
+    output::{Output, TaskInfo},
+    store_ext::StoreExt,
+};
 
-/// List all tasks
+/// List tasks, optionally matching a filter
+///
+/// Deleted tasks are left out, unless the filter includes `status:deleted`.
+/// The active context's filter also applies (see `rw context`). For example:
+///
+/// rw list +work and priority.above:3
+///
+/// rw list "(project:home or +errand) and not status:completed"
+///
+/// rw list --sort priority-,created+ --columns id,priority,description,age
 #[derive(Debug, Default, Parser)]
-pub struct List;
+pub struct List {
+    /// Sort by fields, most significant first, each followed by `+` for
+    /// ascending or `-` for descending order. Tasks are listed by ID
+    /// otherwise.
+    #[clap(long, value_name = "FIELDS")]
+    sort: Option<Sort>,
+    /// The columns of the table, separated by commas. Columns with no values
+    /// are hidden.
+    #[clap(long, value_enum, value_delimiter = ',', value_name = "COLUMNS")]
+    columns: Vec<Column>,
+    /// A filter expression, like `+work`, `project:home or /^Call/` or
+    /// `created.after:2024-01-01`. Options must come before it.
+    #[clap(allow_hyphen_values = true, add = ArgValueCandidates::new(completion::filters))]
+    filter: Vec<String>,
+}
 
 impl List {
     /// Run the list command
-    pub fn run() -> anyhow::Result<()> {
-        let store = Store::load_default()?;
-        if store.is_empty() {
-            println!("no tasks to display");
-            return Ok(());
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        let mut filter =
+            Filter::parse(&self.filter.join(" ")).map_err(|e| CodedError::usage(e.to_string()))?;
+        if let Some(context) = config.context()? {
+            filter = filter.and(context.filter().clone());
         }
-        let mut table = Table::new();
-
-        let has_priority = store.into_iter().any(|task| task.priority().is_some());
-
-        let mut header = vec![Cell::new("ID").add_attribute(Attribute::Bold)];
-        if has_priority {
-            header.push(Cell::new("Priority").add_attribute(Attribute::Bold));
+        let store = Store::load_default(config)?;
+        let mut tasks: Vec<&OpenTask> = store.matching(&filter).collect();
+        if let Some(sort) = &self.sort {
+            sort.sort(&mut tasks);
         }
-        header.push(Cell::new("Description").add_attribute(Attribute::Bold));
-
-        table
-            .load_preset(UTF8_HORIZONTAL_ONLY)
-            .set_content_arrangement(ContentArrangement::Dynamic)
-            .set_header(header);
+        print_tasks(output, &tasks, &self.columns, config)
+    }
+}
 
-        for task in &store {
-            if has_priority {
-                table.add_row(vec![
-                    Cell::new(task.id()),
-                    Cell::new(
-                        task.priority()
-                            .map(|priority| priority.to_string())
-                            .unwrap_or_default(),
-                    ),
-                    Cell::new(task.description()),
-                ]);
-            } else {
-                table.add_row(vec![Cell::new(task.id()), Cell::new(task.description())]);
-            }
-        }
+/// Print tasks as a table with the given columns, or the default columns if
+/// there are none, and the tasks in progress
+pub fn print_tasks(
+    output: Output,
+    tasks: &[&OpenTask],
+    columns: &[Column],
+    config: &Config,
+) -> anyhow::Result<()> {
+    let columns = if columns.is_empty() {
+        &Column::DEFAULT[..]
+    } else {
+        columns
+    };
+    let infos: Vec<TaskInfo> = tasks.iter().copied().map(TaskInfo::from).collect();
+    output.print_list(&infos, || print_table(tasks, columns, config))
+}
 
-        println!("{table}");
+fn print_table(tasks: &[&OpenTask], columns: &[Column], config: &Config) {
+    if tasks.is_empty() {
+        println!("no tasks to display");
+        return;
+    }
+    let now = Utc::now();
+    println!("{}", columns::table(tasks, columns, now, config));
 
-        Ok(())
+    for task in tasks.iter().filter(|task| task.is_started()) {
+        if let Some(start) = task.start() {
+            println!(
+                "Active: task {} '{}', started {} ({} ago)",
+                task.id(),
+                task.description(),
+                start.with_timezone(&Local).format("%Y-%m-%d %H:%M"),
+                duration::format(now - start),
+            );
+        }
     }
 }
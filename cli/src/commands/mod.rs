@@ -1,2 +1,24 @@
 pub mod add;
+pub mod backup;
+pub mod completions;
+pub mod config;
+pub mod context;
+pub mod delete;
+pub mod done;
+pub mod edit;
+pub mod export;
+pub mod git;
+pub mod import;
+pub mod info;
 pub mod list;
+pub mod modify;
+pub mod purge;
+pub mod reopen;
+pub mod report;
+pub mod reports;
+pub mod restore;
+pub mod start;
+pub mod stop;
+pub mod sync;
+pub mod timesheet;
+pub mod tui;
@@ -0,0 +1,131 @@
+use anyhow::bail;
+use clap::Parser;
+use clap_complete::ArgValueCandidates;
+use rustwarrior_core::{Config, Store, task::modification::Modification};
+use serde::Serialize;
+
+use crate::{
+    completion,
+    diff::{FieldChange, diff, print_changes},
+    error::CodedError,
+    output::{Output, TaskInfo},
+    prompt,
+    selector::Selector,
+    store_ext::StoreExt,
+};
+
+/// Change the description or attributes of one or more tasks
+///
+/// Plain words replace the description. `priority:N`, `project:NAME` and
+/// `name:value` set attributes, and an empty value (`project:`) clears them.
+/// `+tag` and `-tag` add and remove tags. For example:
+///
+/// rw modify 3 priority:1 +urgent
+///
+/// rw modify +work -work +office
+#[derive(Debug, Parser)]
+pub struct Modify {
+    /// The tasks to change: IDs like `1,3,5-8`, or a filter like `+work` or
+    /// `project:home`. Quote filters with more than one term.
+    #[clap(add = ArgValueCandidates::new(completion::selectors))]
+    selector: Selector,
+    /// The changes to make. Options must come before these.
+    #[clap(required = true, allow_hyphen_values = true)]
+    changes: Vec<String>,
+    /// Change the tasks without asking for confirmation
+    #[clap(long, short)]
+    yes: bool,
+    /// Ask for confirmation before changing more than this many tasks,
+    /// instead of the `bulk` setting (default 3)
+    #[clap(long, value_name = "COUNT")]
+    bulk: Option<usize>,
+}
+
+/// The changes made to one task, as printed in JSON mode
+#[derive(Debug, Serialize)]
+struct Modified {
+    id: usize,
+    changes: Vec<FieldChange>,
+    task: TaskInfo,
+}
+
+impl Modify {
+    /// Run the modify command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        // Every word after the first change is taken as a change
+        if let Some(option) = self.changes.iter().find(|word| word.starts_with("--")) {
+            bail!(CodedError::usage(format!(
+                "{option} must come before the changes"
+            )));
+        }
+        let modification =
+            Modification::parse(&self.changes).map_err(|e| CodedError::usage(e.to_string()))?;
+        let mut store = Store::load_default(config)?;
+        let selection = self.selector.select(&store);
+        if selection.ids.is_empty() && selection.missing.is_empty() {
+            bail!(CodedError::not_found("no tasks match the filter"));
+        }
+
+        let mut updated = Vec::new();
+        let mut results = Vec::new();
+        for id in selection.ids {
+            let Some(task) = store.get(id) else { continue };
+            let mut after = task.task().clone();
+            let changed = modification.apply(&mut after);
+            results.push((id, diff(task, &after)));
+            if changed {
+                updated.push((id, after));
+            }
+        }
+
+        let previewed = if updated.len() > self.bulk.unwrap_or_else(|| config.bulk()) && !self.yes {
+            if output == Output::Table {
+                for (id, changes) in &results {
+                    print_changes(*id, changes);
+                }
+            }
+            let question = format!("Modify {} tasks?", updated.len());
+            if !prompt::confirm(&question, "--yes")? {
+                return output.print_list::<Modified>(&[], || println!("Nothing modified"));
+            }
+            true
+        } else {
+            false
+        };
+
+        let count = updated.len();
+        for (id, after) in updated {
+            if let Some(task) = store.get_mut(id) {
+                *task.task_mut() = after;
+            }
+        }
+        if count > 0 {
+            store.save_default(config)?;
+        }
+
+        let modified: Vec<Modified> = results
+            .into_iter()
+            .filter_map(|(id, changes)| {
+                store.get(id).map(|task| Modified {
+                    id,
+                    changes,
+                    task: TaskInfo::from(task),
+                })
+            })
+            .collect();
+        output.print_list(&modified, || {
+            if previewed {
+                println!("Modified {count} tasks");
+            } else {
+                for modified in &modified {
+                    print_changes(modified.id, &modified.changes);
+                }
+            }
+        })?;
+
+        if !selection.missing.is_empty() {
+            bail!(CodedError::tasks_not_found(&selection.missing));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use chrono::{Duration, Utc};
+use clap::Parser;
+use rustwarrior_core::{Config, Store};
+
+use crate::{
+    output::{Output, TaskInfo},
+    store_ext::StoreExt,
+};
+
+/// Permanently remove tasks deleted a while ago
+#[derive(Debug, Parser)]
+pub struct Purge {
+    /// Remove tasks deleted more than this many days ago
+    #[clap(long, default_value_t = 30, value_name = "DAYS")]
+    older_than: u32,
+}
+
+impl Purge {
+    /// Run the purge command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        let mut store = Store::load_default(config)?;
+        let cutoff = Utc::now() - Duration::days(i64::from(self.older_than));
+        let purged = store.purge(cutoff);
+        if !purged.is_empty() {
+            store.save_default(config)?;
+        }
+
+        let purged: Vec<TaskInfo> = purged.iter().map(TaskInfo::from).collect();
+        output.print_list(&purged, || match purged.len() {
+            0 => println!("No tasks to purge"),
+            1 => println!("Purged 1 task"),
+            count => println!("Purged {count} tasks"),
+        })
+    }
+}
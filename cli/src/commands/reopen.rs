@@ -0,0 +1,42 @@
+use clap::Parser;
+use clap_complete::ArgValueCandidates;
+use rustwarrior_core::Config;
+
+use super::done::{Action, update_each};
+use crate::{completion, ids::IdRange, output::Output};
+
+/// Mark completed tasks as pending again
+///
+/// IDs can be listed separately or as ranges, e.g. `rw reopen 1 3 5-8`.
+#[derive(Debug, Parser)]
+pub struct Reopen {
+    /// The IDs of the tasks, or ranges like `5-8`
+    #[clap(
+        required = true,
+        value_delimiter = ',',
+        add = ArgValueCandidates::new(completion::completed_ids)
+    )]
+    ids: Vec<IdRange>,
+}
+
+impl Reopen {
+    /// Run the reopen command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        update_each(
+            &self.ids,
+            output,
+            config,
+            &Action {
+                changed: "Reopened",
+                unchanged: "isn't completed",
+                apply: |task| {
+                    if !task.is_completed() {
+                        return false;
+                    }
+                    task.mark_active();
+                    true
+                },
+            },
+        )
+    }
+}
@@ -0,0 +1,52 @@
+use rustwarrior_core::{Config, Filter, Store};
+
+use crate::{
+    columns::Column, commands::list::print_tasks, error::CodedError, output::Output,
+    store_ext::StoreExt,
+};
+
+/// Run a named report, like `rw next` or `rw overdue +work`
+///
+/// Anything after the name is a filter expression, combined with the
+/// report's own filter and the active context's. See `rw reports` for the
+/// reports.
+#[derive(Debug)]
+pub struct Report {
+    name: String,
+    filter: Vec<String>,
+}
+
+impl Report {
+    /// A report from the words of an unrecognised command: its name, then a
+    /// filter
+    pub fn new(mut words: Vec<String>) -> Self {
+        let filter = words.split_off(1.min(words.len()));
+        Self {
+            name: words.pop().unwrap_or_default(),
+            filter,
+        }
+    }
+
+    /// Run the report
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        let reports = config
+            .reports()
+            .map_err(|e| CodedError::usage(e.to_string()))?;
+        let report = reports.get(&self.name).ok_or_else(|| {
+            CodedError::usage(format!(
+                "unrecognized command or report '{}'. See `rw --help` for the commands and `rw \
+                 reports` for the reports",
+                self.name
+            ))
+        })?;
+        let mut filter =
+            Filter::parse(&self.filter.join(" ")).map_err(|e| CodedError::usage(e.to_string()))?;
+        if let Some(context) = config.context()? {
+            filter = filter.and(context.filter().clone());
+        }
+        let columns = Column::of_report(report)?;
+
+        let store = Store::load_default(config)?;
+        print_tasks(output, &report.run(&store, &filter), &columns, config)
+    }
+}
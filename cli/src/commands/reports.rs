@@ -0,0 +1,72 @@
+use clap::Parser;
+use comfy_table::{Attribute, Cell, ContentArrangement, Table, presets::UTF8_HORIZONTAL_ONLY};
+use rustwarrior_core::{Config, report::Report};
+use serde::Serialize;
+
+use crate::{error::CodedError, output::Output};
+
+/// List the reports which can be run as commands, like `rw next`
+///
+/// The built-in reports are next, overdue, completed and all. Reports are
+/// defined, or the built-in ones overridden, in the `[reports]` table of the
+/// config file.
+#[derive(Debug, Parser)]
+pub struct Reports;
+
+#[derive(Debug, Serialize)]
+struct ReportInfo<'a> {
+    name: &'a str,
+    description: &'a str,
+    filter: &'a str,
+    sort: String,
+    columns: &'a [String],
+    limit: Option<usize>,
+}
+
+impl<'a> From<&'a Report> for ReportInfo<'a> {
+    fn from(report: &'a Report) -> Self {
+        Self {
+            name: report.name(),
+            description: report.description(),
+            filter: report.filter(),
+            sort: report.sort().to_string(),
+            columns: report.columns(),
+            limit: report.limit(),
+        }
+    }
+}
+
+impl Reports {
+    /// Run the reports command
+    pub fn run(output: Output, config: &Config) -> anyhow::Result<()> {
+        let reports = config
+            .reports()
+            .map_err(|e| CodedError::usage(e.to_string()))?;
+        let infos: Vec<ReportInfo> = reports.iter().map(ReportInfo::from).collect();
+        output.print_list(&infos, || print_table(&infos))
+    }
+}
+
+fn print_table(reports: &[ReportInfo]) {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_HORIZONTAL_ONLY)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(
+            ["Name", "Description", "Filter", "Sort", "Limit"]
+                .map(|heading| Cell::new(heading).add_attribute(Attribute::Bold)),
+        );
+    for report in reports {
+        table.add_row([
+            report.name.to_string(),
+            report.description.to_string(),
+            report.filter.to_string(),
+            report.sort.clone(),
+            report
+                .limit
+                .map(|limit| limit.to_string())
+                .unwrap_or_default(),
+        ]);
+    }
+    println!("{table}");
+}
@@ -0,0 +1,115 @@
+use clap::Parser;
+use rustwarrior_core::{
+    Config, Store, Task, paths,
+    store::backup::{self, Changes},
+};
+use serde::Serialize;
+
+use crate::{output::Output, prompt, store_ext::StoreExt};
+
+/// Replace the tasks with a snapshot taken by `rw backup`
+///
+/// Shows the tasks which would be added, removed or changed, and asks before
+/// restoring. The current tasks are snapshotted first, so a restore can be
+/// undone in the same way.
+#[derive(Debug, Parser)]
+pub struct Restore {
+    /// The snapshot ID from `rw backup list`, or a unique prefix of it
+    snapshot: String,
+    /// Only show what would change
+    #[clap(long, conflicts_with = "yes")]
+    dry_run: bool,
+    /// Restore without asking for confirmation
+    #[clap(long, short)]
+    yes: bool,
+}
+
+/// The outcome of `rw restore`, as printed in JSON mode
+#[derive(Debug, Serialize)]
+struct Report {
+    snapshot: String,
+    restored: bool,
+    added: Vec<TaskSummary>,
+    removed: Vec<TaskSummary>,
+    changed: Vec<TaskSummary>,
+}
+
+#[derive(Debug, Serialize)]
+struct TaskSummary {
+    uuid: String,
+    description: String,
+}
+
+impl From<&Task> for TaskSummary {
+    fn from(task: &Task) -> Self {
+        Self {
+            uuid: task.uuid().to_string(),
+            description: task.description().clone(),
+        }
+    }
+}
+
+impl Restore {
+    /// Run the restore command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        let snapshot = backup::find(
+            &paths::get_backups_dir(Some(&paths::get_configured_data_dir(config)?))?,
+            &self.snapshot,
+        )?;
+        let restored = snapshot.load()?;
+        let changes = Changes::between(&Store::load_default(config)?, &restored);
+
+        let summaries = |tasks: &[Task]| tasks.iter().map(TaskSummary::from).collect();
+        let mut report = Report {
+            snapshot: snapshot.id().to_string(),
+            restored: false,
+            added: summaries(&changes.added),
+            removed: summaries(&changes.removed),
+            changed: summaries(&changes.changed),
+        };
+
+        if output == Output::Table {
+            print_preview(&report);
+        }
+        if !changes.is_empty() && !self.dry_run {
+            let question = format!("Restore snapshot {}?", report.snapshot);
+            if self.yes || prompt::confirm(&question, "--yes")? {
+                restored.save_default(config)?;
+                report.restored = true;
+            }
+        }
+
+        output.print(&report, || {
+            if report.restored {
+                println!("Restored snapshot {}", report.snapshot);
+            } else if !changes.is_empty() && !self.dry_run {
+                println!("Nothing restored");
+            }
+        })
+    }
+}
+
+fn print_preview(report: &Report) {
+    if report.added.is_empty() && report.removed.is_empty() && report.changed.is_empty() {
+        println!(
+            "Snapshot {} is identical to the current tasks",
+            report.snapshot
+        );
+        return;
+    }
+
+    println!("Restoring snapshot {} would:", report.snapshot);
+    for (sign, verb, tasks) in [
+        ('+', "add", &report.added),
+        ('-', "remove", &report.removed),
+        ('~', "change", &report.changed),
+    ] {
+        if tasks.is_empty() {
+            continue;
+        }
+        println!("  {verb} {} task(s)", tasks.len());
+        for task in tasks {
+            println!("    {sign} {}", task.description);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::bail;
+use clap::Parser;
+use clap_complete::ArgValueCandidates;
+use rustwarrior_core::{Config, Store};
+use serde::Serialize;
+
+use super::stop::print_stopped;
+use crate::{
+    completion,
+    error::CodedError,
+    output::{Output, TaskInfo},
+    store_ext::StoreExt,
+};
+
+/// Start working on a task
+///
+/// The time until `rw stop` is added to the time tracked for the task.
+#[derive(Debug, Parser)]
+pub struct Start {
+    /// The ID of the task
+    #[clap(add = ArgValueCandidates::new(completion::open_ids))]
+    id: usize,
+    /// Stop any other task in progress, so only one is active at a time.
+    /// The `exclusive` setting makes this the default
+    #[clap(long)]
+    exclusive: bool,
+}
+
+/// The result of `rw start`, as printed in JSON mode
+#[derive(Debug, Serialize)]
+struct Started {
+    task: TaskInfo,
+    stopped: Vec<TaskInfo>,
+}
+
+impl Start {
+    /// Run the start command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        let mut store = Store::load_default(config)?;
+        let task = store
+            .get_mut(self.id)
+            .filter(|task| !task.is_deleted())
+            .ok_or_else(|| CodedError::tasks_not_found(&[self.id.into()]))?;
+        if task.is_completed() {
+            bail!(CodedError::conflict(format!(
+                "task {} is completed. Reopen it with `rw reopen {}` first",
+                self.id, self.id
+            )));
+        }
+
+        let already_started = task.is_started();
+        task.task_mut().mark_started();
+        let stopped = if self.exclusive || config.exclusive() {
+            store.stop_others(self.id)
+        } else {
+            Vec::new()
+        };
+        if !already_started || !stopped.is_empty() {
+            store.save_default(config)?;
+        }
+
+        let started = Started {
+            task: TaskInfo::from(store.get(self.id).expect("task was found above")),
+            stopped: stopped
+                .iter()
+                .filter_map(|id| store.get(*id))
+                .map(TaskInfo::from)
+                .collect(),
+        };
+        output.print(&started, || {
+            print_stopped(&store, &stopped);
+            if already_started {
+                println!("Task {} is already started", self.id);
+            } else {
+                println!(
+                    "Started task {} '{}'",
+                    started.task.id, started.task.description
+                );
+            }
+        })
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::bail;
+use clap::Parser;
+use clap_complete::ArgValueCandidates;
+use rustwarrior_core::{Config, OpenTask, Store};
+
+use crate::{
+    completion, duration,
+    error::CodedError,
+    output::{Output, TaskInfo},
+    store_ext::StoreExt,
+};
+
+/// Stop working on a task
+///
+/// Without an ID, every task in progress is stopped.
+#[derive(Debug, Parser)]
+pub struct Stop {
+    /// The ID of the task
+    #[clap(add = ArgValueCandidates::new(completion::started_ids))]
+    id: Option<usize>,
+}
+
+impl Stop {
+    /// Run the stop command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        let mut store = Store::load_default(config)?;
+        let ids: Vec<usize> = if let Some(id) = self.id {
+            let task = store
+                .get(id)
+                .filter(|task| !task.is_deleted())
+                .ok_or_else(|| CodedError::tasks_not_found(&[id.into()]))?;
+            if !task.is_started() {
+                bail!(CodedError::conflict(format!("task {id} isn't started")));
+            }
+            vec![id]
+        } else {
+            store
+                .iter()
+                .filter(|task| task.is_started())
+                .map(OpenTask::id)
+                .collect()
+        };
+
+        for id in &ids {
+            if let Some(task) = store.get_mut(*id) {
+                task.task_mut().mark_stopped();
+            }
+        }
+        if !ids.is_empty() {
+            store.save_default(config)?;
+        }
+
+        let stopped: Vec<TaskInfo> = ids
+            .iter()
+            .filter_map(|id| store.get(*id))
+            .map(TaskInfo::from)
+            .collect();
+        output.print_list(&stopped, || {
+            if ids.is_empty() {
+                println!("No task is started");
+            }
+            print_stopped(&store, &ids);
+        })
+    }
+}
+
+/// Print how long each of the stopped tasks was worked on
+pub fn print_stopped(store: &Store, ids: &[usize]) {
+    for task in ids.iter().filter_map(|id| store.get(*id)) {
+        let session = task
+            .intervals()
+            .last()
+            .map(|interval| duration::format(interval.duration()))
+            .unwrap_or_default();
+        println!(
+            "Stopped task {} '{}' after {session} ({} in total)",
+            task.id(),
+            task.description(),
+            duration::format(task.tracked(chrono::Utc::now())),
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use anyhow::{Context, bail};
+use clap::Parser;
+use rustwarrior_core::{
+    Config, Store, paths,
+    sync::{Snapshot, SyncState},
+};
+use serde::Serialize;
+
+use crate::{error::CodedError, output::Output, store_ext::StoreExt};
+
+/// How many times to retry when another replica pushes mid-sync
+const MAX_ATTEMPTS: usize = 5;
+
+/// Synchronise tasks with a sync server
+#[derive(Debug, Parser)]
+pub struct Sync {
+    /// URL of the sync server, e.g. `http://localhost:8765`.
+    ///
+    /// Remembered for subsequent syncs. Switching to a different server
+    /// discards the shared history with the previous one.
+    #[clap(long)]
+    server: Option<String>,
+}
+
+impl Sync {
+    /// Run the sync command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        let state_file =
+            paths::get_sync_state_file(Some(&paths::get_configured_data_dir(config)?))?;
+        let previous = SyncState::load_from_path(&state_file)?;
+
+        let state = match (self.server, previous) {
+            (Some(server), Some(state)) if server.trim_end_matches('/') == state.server => state,
+            (Some(server), _) => SyncState::new(server.trim_end_matches('/').to_string()),
+            (None, Some(state)) => state,
+            (None, None) => bail!(CodedError::usage(
+                "no sync server configured. Pass one with `--server <URL>`"
+            )),
+        };
+
+        let client = Client::new(&state.server);
+        let local = Store::load_default(config)?;
+
+        for _ in 0..MAX_ATTEMPTS {
+            let remote = client.pull()?;
+
+            let mut merged = local.clone();
+            let summary = merged.merge(&state.base.tasks, &remote.tasks);
+
+            let push = Snapshot {
+                version: remote.version,
+                tasks: merged.clone().into_tasks(),
+            };
+
+            if let Some(base) = client.push(&push)? {
+                merged.save_default(config)?;
+                let report = Report {
+                    server: &state.server,
+                    version: base.version,
+                    added: summary.added,
+                    updated: summary.updated,
+                    removed: summary.removed,
+                };
+                output.print(&report, || report.print())?;
+                let server = state.server.clone();
+                SyncState { server, base }.save_to_path(&state_file)?;
+                return Ok(());
+            }
+        }
+
+        bail!(CodedError::conflict(format!(
+            "sync server kept changing during sync, giving up after {MAX_ATTEMPTS} attempts"
+        )))
+    }
+}
+
+/// The outcome of a sync
+#[derive(Debug, Serialize)]
+struct Report<'a> {
+    /// The sync server
+    server: &'a str,
+    /// The server's version of the tasks after the sync
+    version: u64,
+    /// Tasks added by other replicas
+    added: usize,
+    /// Tasks changed by other replicas
+    updated: usize,
+    /// Tasks removed by other replicas
+    removed: usize,
+}
+
+impl Report<'_> {
+    fn print(&self) {
+        let url = self.server;
+        if self.added + self.updated + self.removed == 0 {
+            println!("Synced with {url}, no incoming changes");
+        } else {
+            println!(
+                "Synced with {url}: {} added, {} updated, {} removed",
+                self.added, self.updated, self.removed
+            );
+        }
+    }
+}
+
+struct Client<'a> {
+    agent: ureq::Agent,
+    server: &'a str,
+    endpoint: String,
+}
+
+impl<'a> Client<'a> {
+    fn new(server: &'a str) -> Self {
+        Self {
+            agent: ureq::Agent::new(),
+            server,
+            endpoint: format!("{server}/tasks"),
+        }
+    }
+
+    fn pull(&self) -> anyhow::Result<Snapshot> {
+        self.agent
+            .get(&self.endpoint)
+            .call()
+            .with_context(|| format!("failed to fetch tasks from {}", self.server))?
+            .into_json()
+            .context("sync server returned an invalid response")
+    }
+
+    /// Push the merged tasks, returning the new server state.
+    ///
+    /// Returns `None` if the server has moved on since the pull.
+    fn push(&self, snapshot: &Snapshot) -> anyhow::Result<Option<Snapshot>> {
+        match self.agent.put(&self.endpoint).send_json(snapshot) {
+            Ok(response) => Ok(Some(
+                response
+                    .into_json()
+                    .context("sync server returned an invalid response")?,
+            )),
+            Err(ureq::Error::Status(409, _)) => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("failed to push tasks to {}", self.server)),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::io::Write;
+
+use anyhow::bail;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use clap::Parser;
+use comfy_table::{Attribute, Cell, ContentArrangement, Table, presets::UTF8_HORIZONTAL_ONLY};
+use rustwarrior_core::{
+    Config, OpenTask, Store,
+    timesheet::{GroupBy, Timesheet as Sheet},
+};
+use serde::Serialize;
+
+use crate::{duration, error::CodedError, output::Output, store_ext::StoreExt};
+
+/// Show the time worked on tasks, for invoicing
+///
+/// Adds up the work recorded with `rw start` and `rw stop`, including work in
+/// progress, by day, project or tag.
+#[derive(Debug, Parser)]
+pub struct Timesheet {
+    /// The first day to include, as YYYY-MM-DD. Defaults to the first work
+    /// recorded
+    #[clap(long, value_name = "DATE")]
+    from: Option<NaiveDate>,
+    /// The last day to include, as YYYY-MM-DD. Defaults to today
+    #[clap(long, value_name = "DATE")]
+    to: Option<NaiveDate>,
+    /// How to group the time: day, project or tag
+    ///
+    /// Time on a task with several tags counts towards each of them.
+    #[clap(long, default_value_t = GroupBy::Day)]
+    group_by: GroupBy,
+    /// Round the time of each group up to a multiple of this many minutes,
+    /// instead of the `timesheet_round` setting (default 0, not rounding)
+    #[clap(long, value_name = "MINUTES")]
+    round: Option<u32>,
+    /// Print comma-separated values, with the time in decimal hours
+    #[clap(long)]
+    csv: bool,
+}
+
+/// A timesheet, as printed in JSON mode
+#[derive(Debug, Serialize)]
+struct Report {
+    from: Option<String>,
+    to: String,
+    group_by: String,
+    rows: Vec<Row>,
+    total_seconds: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct Row {
+    group: Option<String>,
+    seconds: i64,
+    tasks: usize,
+}
+
+impl Timesheet {
+    /// Run the timesheet command
+    pub fn run(self, output: Output, config: &Config) -> anyhow::Result<()> {
+        let now = Utc::now();
+        let from = self.from.map(local_midnight);
+        let to = self
+            .to
+            .and_then(|to| to.succ_opt())
+            .map_or(now, local_midnight)
+            .min(now);
+        let start = from.unwrap_or(DateTime::<Utc>::MIN_UTC);
+        if start >= to {
+            bail!(CodedError::usage("--from must be before --to and now"));
+        }
+
+        let store = Store::load_default(config)?;
+        let tasks = store
+            .iter()
+            .filter(|task| !task.is_deleted())
+            .map(OpenTask::task);
+        let mut sheet = Sheet::build(tasks, start..to, self.group_by, &Local);
+        let round = self.round.unwrap_or_else(|| config.timesheet_round());
+        sheet.round_up(Duration::minutes(i64::from(round)));
+
+        if self.csv {
+            std::io::stdout().write_all(sheet.render_csv()?.as_bytes())?;
+            return Ok(());
+        }
+
+        let report = Report {
+            from: from.map(|from| from.to_rfc3339()),
+            to: to.to_rfc3339(),
+            group_by: self.group_by.to_string(),
+            rows: sheet
+                .rows()
+                .iter()
+                .map(|row| Row {
+                    group: row.group().map(ToString::to_string),
+                    seconds: row.duration().num_seconds(),
+                    tasks: row.tasks(),
+                })
+                .collect(),
+            total_seconds: sheet.total().num_seconds(),
+        };
+        output.print(&report, || print_table(&sheet))
+    }
+}
+
+fn local_midnight(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::MIN);
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map_or_else(|| midnight.and_utc(), |midnight| midnight.to_utc())
+}
+
+fn print_table(sheet: &Sheet) {
+    if sheet.rows().is_empty() {
+        println!("No time tracked");
+        return;
+    }
+
+    let (heading, none) = match sheet.group_by() {
+        GroupBy::Day => ("Day", ""),
+        GroupBy::Project => ("Project", "(no project)"),
+        GroupBy::Tag => ("Tag", "(no tag)"),
+    };
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_HORIZONTAL_ONLY)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(vec![
+            Cell::new(heading).add_attribute(Attribute::Bold),
+            Cell::new("Time").add_attribute(Attribute::Bold),
+            Cell::new("Tasks").add_attribute(Attribute::Bold),
+        ]);
+    for row in sheet.rows() {
+        table.add_row(vec![
+            Cell::new(row.group().unwrap_or(none)),
+            Cell::new(duration::format(row.duration())),
+            Cell::new(row.tasks()),
+        ]);
+    }
+    // Tasks with several tags would be counted more than once
+    if sheet.group_by() != GroupBy::Tag {
+        table.add_row(vec![
+            Cell::new("Total").add_attribute(Attribute::Bold),
+            Cell::new(duration::format(sheet.total())).add_attribute(Attribute::Bold),
+            Cell::new(""),
+        ]);
+    }
+
+    println!("{table}");
+}
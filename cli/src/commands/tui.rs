@@ -0,0 +1,21 @@
+use clap::Parser;
+use rustwarrior_core::Config;
+
+use crate::tui;
+
+/// Browse and change tasks in a full-screen interface
+///
+/// The list shows open tasks in the active context, and the pane beside it
+/// the details of the selected task. Keys add (a), complete (d), reprioritise
+/// (1-4, or 0 to clear), delete (x) and filter (/) tasks; q quits. Changes are
+/// saved at once, and changes made by other programs are shown as they
+/// happen.
+#[derive(Debug, Parser)]
+pub struct Tui;
+
+impl Tui {
+    /// Run the tui command
+    pub fn run(config: &Config) -> anyhow::Result<()> {
+        tui::run(config)
+    }
+}
@@ -0,0 +1,104 @@
+//! Shell completion of task IDs, tags, projects and contexts, read as the
+//! shell asks for them
+//!
+//! The shell calls `rw` with `COMPLETE` set (see `rw completions`), and
+//! clap asks the functions here for the values an argument can take. Each
+//! ID comes with its description, tags and project, so that `rw done <TAB>`
+//! shows what is about to be completed.
+
+use std::collections::BTreeSet;
+
+use clap_complete::CompletionCandidate;
+use rustwarrior_core::{Config, OpenTask, Store, context, paths};
+
+use crate::store_ext::StoreExt;
+
+/// The environment variable the shell sets to ask for completions
+pub const VAR: &str = "COMPLETE";
+
+/// The IDs of tasks which haven't been deleted
+pub fn ids() -> Vec<CompletionCandidate> {
+    id_candidates(|_| true)
+}
+
+/// The IDs of tasks which are pending or started
+pub fn open_ids() -> Vec<CompletionCandidate> {
+    id_candidates(|task| !task.is_completed())
+}
+
+/// The IDs of completed tasks
+pub fn completed_ids() -> Vec<CompletionCandidate> {
+    id_candidates(|task| task.is_completed())
+}
+
+/// The IDs of tasks in progress
+pub fn started_ids() -> Vec<CompletionCandidate> {
+    id_candidates(|task| task.is_started())
+}
+
+/// Filter terms for the tags and projects in use, like `+work` and
+/// `project:home`
+pub fn filters() -> Vec<CompletionCandidate> {
+    let Some(store) = load_store() else {
+        return Vec::new();
+    };
+    let live = || store.iter().filter(|task| !task.is_deleted());
+    let tags: BTreeSet<&String> = live().flat_map(|task| task.tags()).collect();
+    let projects: BTreeSet<&str> = live().filter_map(|task| task.project()).collect();
+    tags.into_iter()
+        .map(|tag| CompletionCandidate::new(format!("+{tag}")).help(Some("tag".into())))
+        .chain(projects.into_iter().map(|project| {
+            CompletionCandidate::new(format!("project:{project}")).help(Some("project".into()))
+        }))
+        .collect()
+}
+
+/// The IDs of tasks which haven't been deleted, then filter terms
+pub fn selectors() -> Vec<CompletionCandidate> {
+    let mut candidates = ids();
+    candidates.extend(filters());
+    candidates
+}
+
+/// The contexts defined in the config file, with their filters, and `none`
+pub fn contexts() -> Vec<CompletionCandidate> {
+    let contexts = paths::get_config_file()
+        .ok()
+        .and_then(|path| Config::load(&path).ok())
+        .map(|config| config.contexts)
+        .unwrap_or_default();
+    contexts
+        .into_iter()
+        .map(|(name, filter)| CompletionCandidate::new(name).help(Some(filter.into())))
+        .chain([CompletionCandidate::new(context::NONE).help(Some("no context".into()))])
+        .collect()
+}
+
+/// Candidates for the IDs of the tasks which match, with what they are
+///
+/// A store which can't be read has no candidates, rather than breaking the
+/// shell's completion with an error.
+fn id_candidates(include: impl Fn(&OpenTask) -> bool) -> Vec<CompletionCandidate> {
+    let Some(store) = load_store() else {
+        return Vec::new();
+    };
+    store
+        .iter()
+        .filter(|task| !task.is_deleted() && include(task))
+        .map(|task| CompletionCandidate::new(task.id().to_string()).help(Some(help(task).into())))
+        .collect()
+}
+
+/// The tasks, or `None` if the config or the store can't be read
+fn load_store() -> Option<Store> {
+    let config = Config::load_default().ok()?;
+    Store::load_default(&config).ok()
+}
+
+/// The description of a task, then its tags and project
+fn help(task: &OpenTask) -> String {
+    let mut words = vec![task.description().clone()];
+    words.extend(task.tags().iter().map(|tag| format!("+{tag}")));
+    words.extend(task.project().map(|project| format!("project:{project}")));
+    words.join(" ")
+}
@@ -0,0 +1,135 @@
+//! Describing what changed in a task
+
+use std::collections::BTreeSet;
+
+use rustwarrior_core::Task;
+use serde::Serialize;
+
+use crate::output::status;
+
+/// A field which differs between two versions of a task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldChange {
+    /// The name of the field
+    pub field: String,
+    /// The old value, if it was set
+    pub before: Option<String>,
+    /// The new value, if it is set
+    pub after: Option<String>,
+}
+
+impl std::fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let none = "(none)";
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.field,
+            self.before.as_deref().unwrap_or(none),
+            self.after.as_deref().unwrap_or(none)
+        )
+    }
+}
+
+/// The fields which differ between two versions of a task
+///
+/// Timestamps which only record when the task was changed are left out.
+pub fn diff(before: &Task, after: &Task) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    let mut compare = |field: &str, before: Option<String>, after: Option<String>| {
+        if before != after {
+            changes.push(FieldChange {
+                field: field.to_string(),
+                before,
+                after,
+            });
+        }
+    };
+
+    compare(
+        "description",
+        Some(before.description().clone()),
+        Some(after.description().clone()),
+    );
+    compare(
+        "status",
+        Some(status(before).to_string()),
+        Some(status(after).to_string()),
+    );
+    compare(
+        "priority",
+        before.priority().map(|priority| priority.to_string()),
+        after.priority().map(|priority| priority.to_string()),
+    );
+    compare(
+        "project",
+        before.project().map(ToString::to_string),
+        after.project().map(ToString::to_string),
+    );
+    compare("tags", tags(before), tags(after));
+
+    let names: BTreeSet<&String> = before.extra().keys().chain(after.extra().keys()).collect();
+    for name in names {
+        compare(name, extra(before, name), extra(after, name));
+    }
+
+    changes
+}
+
+/// Print the changes made to a task, one field per line
+pub fn print_changes(id: usize, changes: &[FieldChange]) {
+    if changes.is_empty() {
+        println!("Task {id} unchanged");
+        return;
+    }
+    println!("Task {id}:");
+    for change in changes {
+        println!("  {change}");
+    }
+}
+
+fn extra(task: &Task, name: &str) -> Option<String> {
+    task.extra().get(name).map(|value| {
+        value
+            .as_str()
+            .map_or_else(|| value.to_string(), ToString::to_string)
+    })
+}
+
+fn tags(task: &Task) -> Option<String> {
+    let tags: Vec<&str> = task.tags().iter().map(String::as_str).collect();
+    (!tags.is_empty()).then(|| tags.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use rustwarrior_core::{Priority, Task};
+    use serde_json::json;
+
+    use super::diff;
+
+    #[test]
+    fn changed_fields() {
+        let before = Task::new("old".to_string())
+            .with_priority(Priority::Two)
+            .with_tag("work".to_string());
+        let mut after = before.clone();
+        after.set_priority(None);
+        after.add_tag("urgent".to_string());
+        after.set_extra("estimate".to_string(), json!("2h"));
+
+        let changes: Vec<String> = diff(&before, &after)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                "priority: 2 -> (none)",
+                "tags: work -> urgent work",
+                "estimate: (none) -> 2h"
+            ]
+        );
+        assert!(diff(&before, &before).is_empty());
+    }
+}
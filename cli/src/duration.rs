@@ -0,0 +1,60 @@
+//! Durations for people, like `1h 05m` or `3d`
+
+use chrono::Duration;
+
+/// Format a duration in hours and minutes, or seconds if under a minute
+pub fn format(duration: Duration) -> String {
+    let hours = duration.num_hours();
+    let minutes = duration.num_minutes() % 60;
+    if hours > 0 {
+        format!("{hours}h {minutes:02}m")
+    } else if minutes > 0 {
+        format!("{minutes}m")
+    } else {
+        format!("{}s", duration.num_seconds().max(0))
+    }
+}
+
+/// Format an age roughly, in the largest whole unit, like `3d` or `2mo`
+pub fn age(duration: Duration) -> String {
+    let days = duration.num_days();
+    if days >= 365 {
+        format!("{}y", days / 365)
+    } else if days >= 60 {
+        format!("{}mo", days / 30)
+    } else if days >= 14 {
+        format!("{}w", days / 7)
+    } else if days > 0 {
+        format!("{days}d")
+    } else if duration.num_hours() > 0 {
+        format!("{}h", duration.num_hours())
+    } else {
+        format!("{}m", duration.num_minutes().max(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use test_case::test_case;
+
+    use super::{age, format};
+
+    #[test_case(Duration::seconds(42) => "42s" ; "seconds")]
+    #[test_case(Duration::seconds(25 * 60 + 59) => "25m" ; "minutes")]
+    #[test_case(Duration::minutes(65) => "1h 05m" ; "hours")]
+    #[test_case(Duration::hours(30) => "30h 00m" ; "days")]
+    fn formats(duration: Duration) -> String {
+        format(duration)
+    }
+
+    #[test_case(Duration::seconds(30) => "0m" ; "new")]
+    #[test_case(Duration::hours(5) => "5h" ; "hours")]
+    #[test_case(Duration::days(13) => "13d" ; "days")]
+    #[test_case(Duration::days(20) => "2w" ; "weeks")]
+    #[test_case(Duration::days(100) => "3mo" ; "months")]
+    #[test_case(Duration::days(800) => "2y" ; "years")]
+    fn ages(duration: Duration) -> String {
+        age(duration)
+    }
+}
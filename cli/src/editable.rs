@@ -0,0 +1,367 @@
+//! Tasks as `name: value` text, for editing in `$EDITOR`
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+use chrono::Local;
+use rustwarrior_core::{Priority, Task};
+use serde_json::Value;
+
+use crate::output::status;
+
+/// The marker for errors shown in the text, which is removed before parsing
+const ERROR_PREFIX: &str = "# ERROR: ";
+
+/// Render a task for editing
+pub fn render(id: usize, task: &Task) -> String {
+    let mut text = format!(
+        "# Editing task {id}. Save and close the editor to apply the changes.\n# Close it without \
+         saving to cancel.\n#\n# Lines starting with `#` are ignored, and an empty value clears a \
+         field.\n# status: pending, started or completed\n# priority: 1 (highest) to 4 \
+         (lowest)\n# tags: separated by spaces\n# Any other `name: value` line sets an extra \
+         attribute.\n#\n# UUID: {}\n# Created: {}\n\n",
+        task.uuid(),
+        task.created()
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S"),
+    );
+
+    let tags: Vec<&str> = task.tags().iter().map(String::as_str).collect();
+    let fields = [
+        ("description", task.description().clone()),
+        ("status", status(task).to_string()),
+        (
+            "priority",
+            task.priority()
+                .map(|priority| priority.to_string())
+                .unwrap_or_default(),
+        ),
+        ("project", task.project().unwrap_or_default().to_string()),
+        ("tags", tags.join(" ")),
+    ];
+    for (name, value) in fields {
+        let _ = writeln!(text, "{name}: {value}");
+    }
+    for (name, value) in task.extra() {
+        let value = value
+            .as_str()
+            .map_or_else(|| value.to_string(), ToString::to_string);
+        let _ = writeln!(text, "{name}: {value}");
+    }
+
+    text
+}
+
+/// A task as parsed from the text form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edited {
+    description: String,
+    status: Status,
+    priority: Option<Priority>,
+    project: Option<String>,
+    tags: BTreeSet<String>,
+    extra: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pending,
+    Started,
+    Completed,
+}
+
+/// A problem with one line of the text, or with the whole text if `line` is 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineError {
+    /// The line number, starting at 1
+    pub line: usize,
+    /// What is wrong
+    pub message: String,
+}
+
+/// Parse the text form, reporting every error found
+///
+/// `current` holds the task's extra attributes before editing, which tell how
+/// to read each extra back.
+pub fn parse(text: &str, current: &BTreeMap<String, Value>) -> Result<Edited, Vec<LineError>> {
+    let mut errors = Vec::new();
+    let mut fields = BTreeMap::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let mut error = |message: String| {
+            errors.push(LineError {
+                line: line_number,
+                message,
+            });
+        };
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let Some((name, value)) = trimmed.split_once(':') else {
+            error("expected `name: value`".to_string());
+            continue;
+        };
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            error(format!("invalid field name {name:?}"));
+            continue;
+        }
+        if fields
+            .insert(name.to_string(), (line_number, value.trim().to_string()))
+            .is_some()
+        {
+            error(format!("{name} is given more than once"));
+        }
+    }
+
+    let mut take = |name: &str| fields.remove(name).unwrap_or((0, String::new()));
+    let mut error = |line: usize, message: String| errors.push(LineError { line, message });
+
+    let (line, description) = take("description");
+    if description.is_empty() {
+        error(line, "description can't be empty".to_string());
+    }
+
+    let (line, status) = take("status");
+    let status = match status.as_str() {
+        "" | "pending" => Status::Pending,
+        "started" => Status::Started,
+        "completed" => Status::Completed,
+        other => {
+            error(
+                line,
+                format!("invalid status {other:?}. Expected pending, started or completed"),
+            );
+            Status::Pending
+        }
+    };
+
+    let (line, priority) = take("priority");
+    let priority = if priority.is_empty() {
+        None
+    } else if let Ok(priority) = priority.parse() {
+        Some(priority)
+    } else {
+        error(line, format!("invalid priority {priority:?}. Expected 1-4"));
+        None
+    };
+
+    let (_, project) = take("project");
+    let project = (!project.is_empty()).then_some(project);
+
+    let (_, tags) = take("tags");
+    let tags = tags.split_whitespace().map(ToString::to_string).collect();
+
+    let extra = fields
+        .into_iter()
+        .filter(|(_, (_, value))| !value.is_empty())
+        .map(|(name, (_, value))| {
+            let value = extra_value(value, current.get(&name));
+            (name, value)
+        })
+        .collect();
+
+    if !errors.is_empty() {
+        errors.sort_by_key(|error| error.line);
+        return Err(errors);
+    }
+    Ok(Edited {
+        description,
+        status,
+        priority,
+        project,
+        tags,
+        extra,
+    })
+}
+
+/// Extras which aren't strings are rendered as JSON, so read them back
+///
+/// Strings and new extras stay strings, so that `42` typed over a string is
+/// still a string.
+fn extra_value(value: String, current: Option<&Value>) -> Value {
+    if current.is_none_or(Value::is_string) {
+        return Value::String(value);
+    }
+    match serde_json::from_str(&value) {
+        Ok(Value::String(_)) | Err(_) => Value::String(value),
+        Ok(json) => json,
+    }
+}
+
+/// Show errors in the text, above the lines they refer to
+///
+/// Errors shown by an earlier call are replaced.
+pub fn annotate(text: &str, errors: &[LineError]) -> String {
+    let mut annotated = String::new();
+    for error in errors.iter().filter(|error| error.line == 0) {
+        let _ = writeln!(annotated, "{ERROR_PREFIX}{}", error.message);
+    }
+
+    let lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.starts_with(ERROR_PREFIX));
+    for (index, line) in lines {
+        for error in errors.iter().filter(|error| error.line == index + 1) {
+            let _ = writeln!(annotated, "{ERROR_PREFIX}{}", error.message);
+        }
+        let _ = writeln!(annotated, "{line}");
+    }
+
+    annotated
+}
+
+impl Edited {
+    /// Update a task to match, returning whether anything changed
+    pub fn apply(self, task: &mut Task) -> bool {
+        let before = task.clone();
+
+        if *task.description() != self.description {
+            task.set_description(self.description);
+        }
+        if task.priority() != self.priority {
+            task.set_priority(self.priority);
+        }
+        if task.project() != self.project.as_deref() {
+            task.set_project(self.project);
+        }
+
+        let removed: Vec<String> = task.tags().difference(&self.tags).cloned().collect();
+        for tag in removed {
+            task.remove_tag(&tag);
+        }
+        for tag in self.tags {
+            task.add_tag(tag);
+        }
+
+        let removed: Vec<String> = task
+            .extra()
+            .keys()
+            .filter(|name| !self.extra.contains_key(*name))
+            .cloned()
+            .collect();
+        for name in removed {
+            task.remove_extra(&name);
+        }
+        for (name, value) in self.extra {
+            if task.extra().get(&name) != Some(&value) {
+                task.set_extra(name, value);
+            }
+        }
+
+        match self.status {
+            Status::Pending => {
+                task.mark_active();
+                task.mark_stopped();
+            }
+            Status::Started => {
+                task.mark_active();
+                task.mark_started();
+            }
+            Status::Completed => task.mark_completed(),
+        }
+
+        *task != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rustwarrior_core::{Priority, Task};
+    use serde_json::json;
+    use test_case::test_case;
+
+    use super::{LineError, annotate, parse, render};
+
+    fn task() -> Task {
+        Task::new("Pay rent".to_string())
+            .with_priority(Priority::Two)
+            .with_tag("bills".to_string())
+            .with_extra("estimate".to_string(), json!("10m"))
+            .with_extra("count".to_string(), json!(3))
+            .with_extra("code".to_string(), json!("42"))
+            .with_extra("flag".to_string(), json!("true"))
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut task = task();
+        let unchanged = task.clone();
+
+        let text = render(4, &task);
+        assert!(!parse(&text, task.extra()).unwrap().apply(&mut task));
+        assert_eq!(task, unchanged);
+
+        // Changing another field leaves the extras as they were
+        let text = text.replace("description: Pay rent", "description: Pay the rent");
+        let extra = task.extra().clone();
+        assert!(parse(&text, &extra).unwrap().apply(&mut task));
+        assert_eq!(task.extra(), unchanged.extra());
+    }
+
+    #[test]
+    fn edit() {
+        let mut task = task();
+        let text = render(4, &task)
+            .replace("description: Pay rent", "description: Pay the rent")
+            .replace("priority: 2", "priority:")
+            .replace("tags: bills", "tags: bills home")
+            .replace("estimate: 10m\n", "")
+            .replace("status: pending", "status: completed");
+
+        let extra = task.extra().clone();
+        assert!(parse(&text, &extra).unwrap().apply(&mut task));
+        assert_eq!(task.description(), "Pay the rent");
+        assert_eq!(task.priority(), None);
+        assert!(task.has_tag("home"));
+        assert_eq!(task.extra().get("estimate"), None);
+        assert_eq!(task.extra().get("count"), Some(&json!(3)));
+        assert!(task.is_completed());
+    }
+
+    #[test_case("description: x\npriority: 9" => vec![(2, "invalid priority \"9\". Expected 1-4".to_string())] ; "priority")]
+    #[test_case("priority: 1" => vec![(0, "description can't be empty".to_string())] ; "no description")]
+    #[test_case("description: x\nstatus: done" => vec![(2, "invalid status \"done\". Expected pending, started or completed".to_string())] ; "status")]
+    #[test_case("description: x\nnonsense" => vec![(2, "expected `name: value`".to_string())] ; "no colon")]
+    #[test_case("description: x\ndescription: y" => vec![(2, "description is given more than once".to_string())] ; "duplicate")]
+    fn errors(text: &str) -> Vec<(usize, String)> {
+        parse(text, &BTreeMap::new())
+            .unwrap_err()
+            .into_iter()
+            .map(|error| (error.line, error.message))
+            .collect()
+    }
+
+    #[test]
+    fn annotate_replaces_old_errors() {
+        let errors = [
+            LineError {
+                line: 0,
+                message: "missing".to_string(),
+            },
+            LineError {
+                line: 2,
+                message: "bad".to_string(),
+            },
+        ];
+        let annotated = annotate("a: 1\nb: 2\n", &errors);
+        assert_eq!(annotated, "# ERROR: missing\na: 1\n# ERROR: bad\nb: 2\n");
+
+        let errors = [LineError {
+            line: 4,
+            message: "worse".to_string(),
+        }];
+        assert_eq!(
+            annotate(&annotated, &errors),
+            "a: 1\n# ERROR: worse\nb: 2\n"
+        );
+    }
+}
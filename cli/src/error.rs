@@ -0,0 +1,148 @@
+//! Error codes, so that scripts can tell failures apart
+
+use rustwarrior_core::{
+    StoreError,
+    format::{csv, ics, org, taskwarrior, todotxt},
+    store::backup,
+    timesheet,
+};
+use serde::Serialize;
+
+use crate::ids::IdRange;
+
+/// The kind of failure reported by a command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The command line is invalid
+    Usage,
+    /// A task or other named thing doesn't exist
+    NotFound,
+    /// An input file couldn't be parsed
+    InvalidInput,
+    /// Another change got in the way
+    Conflict,
+    /// A server couldn't be reached, or returned an error
+    Network,
+    /// The task store couldn't be read or written
+    Storage,
+    /// Some other file couldn't be read or written
+    Io,
+    /// Anything else
+    Error,
+}
+
+impl ErrorCode {
+    /// Classify an error by the first cause with a known code
+    pub fn of(error: &anyhow::Error) -> Self {
+        error
+            .chain()
+            .find_map(Self::of_cause)
+            .unwrap_or(Self::Error)
+    }
+
+    fn of_cause(cause: &(dyn std::error::Error + 'static)) -> Option<Self> {
+        if let Some(coded) = cause.downcast_ref::<CodedError>() {
+            return Some(coded.code);
+        }
+
+        let code = if let Some(error) = cause.downcast_ref::<backup::Error>() {
+            match error {
+                backup::Error::Io(_) => Self::Io,
+                backup::Error::Ambiguous(_) => Self::Usage,
+                backup::Error::NotFound(_) => Self::NotFound,
+            }
+        } else if cause.is::<StoreError>() {
+            Self::Storage
+        } else if cause.is::<ureq::Error>() {
+            Self::Network
+        } else if cause.is::<taskwarrior::Error>()
+            || cause.is::<todotxt::Error>()
+            || cause.is::<csv::Error>()
+            || cause.is::<ics::Error>()
+            || cause.is::<org::Error>()
+        {
+            Self::InvalidInput
+        } else if cause.is::<std::io::Error>() || cause.is::<timesheet::Error>() {
+            Self::Io
+        } else {
+            return None;
+        };
+        Some(code)
+    }
+}
+
+/// An error carrying its [`ErrorCode`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodedError {
+    code: ErrorCode,
+    message: String,
+}
+
+impl CodedError {
+    /// The command line is invalid
+    pub fn usage(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Usage, message)
+    }
+
+    /// Something named on the command line doesn't exist
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    /// Some of the task IDs named on the command line don't exist
+    pub fn tasks_not_found(ids: &[IdRange]) -> Self {
+        let message = match ids {
+            [id] if id.is_single() => format!("task {id} not found"),
+            ids => {
+                let ids: Vec<String> = ids.iter().map(ToString::to_string).collect();
+                format!("tasks {} not found", ids.join(", "))
+            }
+        };
+        Self::not_found(message)
+    }
+
+    /// Another change got in the way
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Conflict, message)
+    }
+
+    fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for CodedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CodedError {}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Context;
+    use test_case::test_case;
+
+    use super::{CodedError, ErrorCode};
+
+    #[test_case(&anyhow::anyhow!("boom") => ErrorCode::Error ; "plain")]
+    #[test_case(&CodedError::not_found("task 3 not found").into() => ErrorCode::NotFound ; "coded")]
+    #[test_case(
+        &anyhow::Error::from(std::io::Error::other("disk")).context("failed to read file")
+        => ErrorCode::Io
+        ; "with context"
+    )]
+    #[test_case(
+        &Err::<(), _>(CodedError::usage("bad flag")).context("wrapped").unwrap_err()
+        => ErrorCode::Usage
+        ; "wrapped coded"
+    )]
+    fn classify(error: &anyhow::Error) -> ErrorCode {
+        ErrorCode::of(error)
+    }
+}
@@ -0,0 +1,66 @@
+//! File formats supported by `rw import` and `rw export`
+
+use clap::ValueEnum;
+use rustwarrior_core::{
+    Store, Task,
+    format::{
+        csv::{self, Column, Mapping, Report},
+        ics, markdown, org, taskwarrior, todotxt,
+    },
+};
+
+/// A task file format
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Taskwarrior JSON, as read by `task import` and written by `task export`
+    #[default]
+    Taskwarrior,
+    /// todo.txt, one task per line
+    Todotxt,
+    /// Comma-separated values, one task per row
+    Csv,
+    /// iCalendar to-dos, for calendar and reminder apps
+    Ics,
+    /// Markdown checklists, grouped by priority
+    Markdown,
+    /// Org mode `TODO` and `DONE` headlines
+    Org,
+}
+
+impl Format {
+    /// Import the contents of a file into a store
+    ///
+    /// The mapping of headers to fields is only used for CSV, the only format
+    /// which can report rows it skipped or headers it ignored.
+    pub fn import(
+        self,
+        store: &mut Store,
+        input: &str,
+        mapping: &Mapping,
+    ) -> anyhow::Result<Report> {
+        let summary = match self {
+            Self::Taskwarrior => store.import(taskwarrior::parse(input)?),
+            Self::Todotxt => todotxt::import(store, input)?,
+            Self::Csv => return Ok(csv::import(store, input, mapping)?),
+            Self::Ics => store.import(ics::parse(input)?),
+            Self::Markdown => markdown::import(store, input),
+            Self::Org => org::import(store, input)?,
+        };
+        Ok(Report {
+            summary,
+            ..Report::default()
+        })
+    }
+
+    /// Render tasks to the contents of a file
+    pub fn render<'a>(self, tasks: impl IntoIterator<Item = &'a Task>) -> anyhow::Result<String> {
+        match self {
+            Self::Taskwarrior => Ok(taskwarrior::render(tasks)?),
+            Self::Todotxt => Ok(todotxt::render(tasks)),
+            Self::Csv => Ok(csv::render(tasks, &Column::ALL)?),
+            Self::Ics => Ok(ics::render(tasks)),
+            Self::Markdown => Ok(markdown::render(tasks)),
+            Self::Org => Ok(org::render(tasks)),
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Task IDs given on the command line, as single IDs or ranges
+
+use std::{collections::BTreeSet, fmt, str::FromStr};
+
+use rustwarrior_core::{OpenTask, Store};
+
+/// A single ID like `3`, or an inclusive range like `5-8`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct IdRange {
+    first: usize,
+    last: usize,
+}
+
+impl IdRange {
+    /// The first ID in the range
+    pub const fn first(self) -> usize {
+        self.first
+    }
+
+    /// Whether the range covers just one ID
+    pub const fn is_single(self) -> bool {
+        self.first == self.last
+    }
+}
+
+impl From<usize> for IdRange {
+    fn from(id: usize) -> Self {
+        Self {
+            first: id,
+            last: id,
+        }
+    }
+}
+
+impl fmt::Display for IdRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_single() {
+            write!(f, "{}", self.first)
+        } else {
+            write!(f, "{}-{}", self.first, self.last)
+        }
+    }
+}
+
+impl FromStr for IdRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |id: &str| {
+            id.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("invalid task ID {id:?}"))
+        };
+
+        let (first, last) = s.split_once('-').unwrap_or((s, s));
+        let (first, last) = (parse(first)?, parse(last)?);
+        if first > last {
+            return Err(format!("invalid ID range {s:?}: {first} is after {last}"));
+        }
+
+        Ok(Self { first, last })
+    }
+}
+
+/// The IDs covered by some ID ranges, split by whether they exist
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Expanded {
+    /// The IDs which exist, in ascending order and without repeats
+    pub ids: Vec<usize>,
+    /// The IDs which don't exist, as ranges in ascending order
+    pub missing: Vec<IdRange>,
+}
+
+/// Expand ID ranges into the `existing` IDs they cover
+///
+/// The IDs which don't exist are gathered into ranges, so `0-100000` costs no
+/// more than the store is big.
+pub fn expand(ranges: &[IdRange], existing: &BTreeSet<usize>) -> Expanded {
+    let mut ids = BTreeSet::new();
+    let mut missing = Vec::new();
+    for range in ranges {
+        // The first ID of the range after the IDs seen so far
+        let mut next = Some(range.first);
+        for &id in existing.range(range.first..=range.last) {
+            if let Some(first) = next.filter(|first| *first < id) {
+                missing.push(IdRange {
+                    first,
+                    last: id - 1,
+                });
+            }
+            ids.insert(id);
+            next = id.checked_add(1);
+        }
+        if let Some(first) = next.filter(|first| *first <= range.last) {
+            missing.push(IdRange {
+                first,
+                last: range.last,
+            });
+        }
+    }
+
+    missing.sort_unstable();
+    let missing = missing
+        .into_iter()
+        .fold(Vec::<IdRange>::new(), |mut merged, range| {
+            match merged.last_mut() {
+                Some(last)
+                    if last
+                        .last
+                        .checked_add(1)
+                        .is_none_or(|end| end >= range.first) =>
+                {
+                    last.last = last.last.max(range.last);
+                }
+                _ => merged.push(range),
+            }
+            merged
+        });
+
+    Expanded {
+        ids: ids.into_iter().collect(),
+        missing,
+    }
+}
+
+/// The IDs of the tasks in the store that haven't been deleted
+pub fn live(store: &Store) -> BTreeSet<usize> {
+    store
+        .iter()
+        .filter(|task| !task.is_deleted())
+        .map(OpenTask::id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use test_case::test_case;
+
+    use super::{IdRange, expand};
+
+    #[test_case(&["3"] => (vec![3], vec![]) ; "single")]
+    #[test_case(&["1", "3", "5-8"] => (vec![1, 3, 5, 6, 7, 8], vec![]) ; "list and range")]
+    #[test_case(&["4-4"] => (vec![4], vec![]) ; "one element range")]
+    #[test_case(&["2-3", "1-3"] => (vec![1, 2, 3], vec![]) ; "overlapping")]
+    #[test_case(&["12"] => (vec![], vec!["12".to_string()]) ; "missing single")]
+    #[test_case(&["8-12"] => (vec![8, 9, 10], vec!["11-12".to_string()]) ; "range past the end")]
+    #[test_case(&["11-12", "12-14", "15"] => (vec![], vec!["11-15".to_string()]) ; "missing ranges merge")]
+    #[test_case(
+        &["0-18446744073709551615"]
+        => ((0..=10).collect(), vec!["11-18446744073709551615".to_string()])
+        ; "huge range"
+    )]
+    fn expands(ranges: &[&str]) -> (Vec<usize>, Vec<String>) {
+        let ranges: Vec<IdRange> = ranges.iter().map(|range| range.parse().unwrap()).collect();
+        let existing: BTreeSet<usize> = (0..=10).collect();
+        let expanded = expand(&ranges, &existing);
+        let missing = expanded.missing.iter().map(ToString::to_string).collect();
+        (expanded.ids, missing)
+    }
+
+    #[test]
+    fn gaps_are_missing() {
+        let ranges: Vec<IdRange> = ["0-6", "3"].iter().map(|r| r.parse().unwrap()).collect();
+        let expanded = expand(&ranges, &BTreeSet::from([1, 4]));
+
+        assert_eq!(expanded.ids, [1, 4]);
+        let missing: Vec<String> = expanded.missing.iter().map(ToString::to_string).collect();
+        assert_eq!(missing, ["0", "2-3", "5-6"]);
+    }
+
+    #[test_case("x" ; "not a number")]
+    #[test_case("-3" ; "negative")]
+    #[test_case("8-5" ; "backwards")]
+    #[test_case("1-2-3" ; "too many parts")]
+    fn rejects(range: &str) {
+        assert!(range.parse::<IdRange>().is_err());
+    }
+}
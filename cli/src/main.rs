@@ -10,14 +10,55 @@
 #![allow(clippy::cargo_common_metadata)]
 
 mod cli;
+mod columns;
 mod commands;
+mod completion;
+mod diff;
+mod duration;
+mod editable;
+mod error;
+mod format;
+mod ids;
+mod output;
+mod prompt;
+mod selector;
 mod store_ext;
+mod tui;
 
+use std::process::ExitCode;
+
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
 use cli::Cli;
+use error::ErrorCode;
+use output::Output;
+
+fn main() -> ExitCode {
+    CompleteEnv::with_factory(Cli::command)
+        .var(completion::VAR)
+        .complete();
 
-fn main() -> anyhow::Result<()> {
-    let cli = Cli::default();
-    cli.run()?;
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) if e.use_stderr() => {
+            let output = Output::requested(std::env::args_os());
+            let rendered = e.render().to_string();
+            let message = rendered.lines().next().unwrap_or_default();
+            let message = message.trim_start_matches("error: ");
+            output.print_coded_error(ErrorCode::Usage, message, || {
+                let _ = e.print();
+            });
+            return ExitCode::from(2);
+        }
+        Err(e) => e.exit(),
+    };
 
-    Ok(())
+    let output = cli.output();
+    match cli.run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            output.print_error(&e);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,208 @@
+//! Output for people or for scripts, selected with `--output`
+//!
+//! In `json` mode each command prints a single JSON document, and in `jsonl`
+//! mode lists are printed one JSON object per line. Tasks are described by
+//! [`TaskInfo`]. Errors are printed to stderr as an [`ErrorInfo`] object, and
+//! the exit status is non-zero.
+
+use std::{ffi::OsString, path::Path};
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use rustwarrior_core::{OpenTask, Task};
+use serde::Serialize;
+
+use crate::error::ErrorCode;
+
+/// How command output is printed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Output {
+    /// Tables and messages for people
+    #[default]
+    Table,
+    /// One JSON document
+    Json,
+    /// One JSON object per line
+    Jsonl,
+}
+
+impl Output {
+    /// The `--output` requested on the command line, if it can be found
+    ///
+    /// Used to report errors in the requested format when the command line as
+    /// a whole can't be parsed.
+    pub fn requested(args: impl IntoIterator<Item = OsString>) -> Self {
+        let mut args = args
+            .into_iter()
+            .map(|arg| arg.to_string_lossy().into_owned());
+        while let Some(arg) = args.next() {
+            let value = match arg.strip_prefix("--output") {
+                Some("") => args.next(),
+                Some(value) => value.strip_prefix('=').map(ToString::to_string),
+                None => None,
+            };
+            if let Some(output) = value.and_then(|value| Self::from_str(&value, true).ok()) {
+                return output;
+            }
+        }
+        Self::default()
+    }
+
+    /// Print a value as JSON, or by calling `table` in table mode
+    pub fn print<T: Serialize + ?Sized>(
+        self,
+        value: &T,
+        table: impl FnOnce(),
+    ) -> anyhow::Result<()> {
+        match self {
+            Self::Table => table(),
+            Self::Json => println!("{}", serde_json::to_string_pretty(value)?),
+            Self::Jsonl => println!("{}", serde_json::to_string(value)?),
+        }
+        Ok(())
+    }
+
+    /// Print a list of values as JSON, or by calling `table` in table mode
+    ///
+    /// In `jsonl` mode, each value is printed on its own line.
+    pub fn print_list<T: Serialize>(
+        self,
+        values: &[T],
+        table: impl FnOnce(),
+    ) -> anyhow::Result<()> {
+        if self == Self::Jsonl {
+            for value in values {
+                println!("{}", serde_json::to_string(value)?);
+            }
+            return Ok(());
+        }
+        self.print(values, table)
+    }
+
+    /// Report an error which ended the command
+    pub fn print_error(self, error: &anyhow::Error) {
+        self.print_coded_error(ErrorCode::of(error), &format!("{error:#}"), || {
+            eprintln!("Error: {error:?}");
+        });
+    }
+
+    /// Report an error with a known code
+    pub fn print_coded_error(self, code: ErrorCode, message: &str, table: impl FnOnce()) {
+        let info = ErrorInfo {
+            error: ErrorBody { code, message },
+        };
+        match (self, serde_json::to_string(&info)) {
+            (Self::Json | Self::Jsonl, Ok(json)) => eprintln!("{json}"),
+            _ => table(),
+        }
+    }
+}
+
+/// A task, as printed by commands in JSON mode
+///
+/// Timestamps are RFC 3339 strings in UTC. Optional fields are left out when
+/// they have no value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TaskInfo {
+    /// The short ID used on the command line
+    pub id: usize,
+    /// The permanent identifier of the task
+    pub uuid: String,
+    /// What the task is
+    pub description: String,
+    /// `pending`, `started`, `completed` or `deleted`
+    pub status: &'static str,
+    /// 1 (highest) to 4 (lowest)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+    /// The project the task belongs to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// The tags attached to the task, in sorted order
+    pub tags: Vec<String>,
+    /// When the task was created
+    pub created: String,
+    /// When the task was last changed
+    pub modified: String,
+    /// When work on the task started, while it is in progress
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    /// When the task was closed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    /// The total time worked on the task in seconds, including work in
+    /// progress, if any has been tracked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracked_seconds: Option<i64>,
+    /// When the task was completed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed: Option<String>,
+    /// When the task was deleted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted: Option<String>,
+    /// Whether the task is completed
+    pub is_completed: bool,
+}
+
+impl From<&OpenTask> for TaskInfo {
+    fn from(task: &OpenTask) -> Self {
+        Self {
+            id: task.id(),
+            uuid: task.uuid().to_string(),
+            description: task.description().clone(),
+            status: status(task),
+            priority: task.priority().map(u8::from),
+            project: task.project().map(ToString::to_string),
+            tags: task.tags().iter().cloned().collect(),
+            created: timestamp(task.created()),
+            modified: timestamp(task.modified()),
+            start: task.start().map(timestamp),
+            end: task.end().map(timestamp),
+            tracked_seconds: (task.is_started() || !task.intervals().is_empty())
+                .then(|| task.tracked(Utc::now()).num_seconds()),
+            completed: task.completed().map(timestamp),
+            deleted: task.deleted().map(timestamp),
+            is_completed: task.is_completed(),
+        }
+    }
+}
+
+/// The status of a task: `pending`, `started`, `completed` or `deleted`
+pub const fn status(task: &Task) -> &'static str {
+    if task.is_deleted() {
+        "deleted"
+    } else if task.is_completed() {
+        "completed"
+    } else if task.is_started() {
+        "started"
+    } else {
+        "pending"
+    }
+}
+
+/// The data directory a command acted on
+#[derive(Debug, Serialize)]
+pub struct DataDirInfo<'a> {
+    /// The data directory
+    pub data_dir: &'a Path,
+}
+
+/// An error, as printed in JSON mode
+#[derive(Debug, Serialize)]
+pub struct ErrorInfo<'a> {
+    /// What went wrong
+    pub error: ErrorBody<'a>,
+}
+
+/// The details of an [`ErrorInfo`]
+#[derive(Debug, Serialize)]
+pub struct ErrorBody<'a> {
+    /// The kind of error, for scripts to match on
+    pub code: ErrorCode,
+    /// A description of the error, for people
+    pub message: &'a str,
+}
+
+fn timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339()
+}
@@ -0,0 +1,26 @@
+//! Asking for confirmation before destructive changes
+
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use anyhow::bail;
+
+use crate::error::CodedError;
+
+/// Ask a yes/no question on the terminal, defaulting to no
+///
+/// Fails if stdin isn't a terminal, since nobody is there to answer. `flag`
+/// names the option which skips the question, for the error message.
+pub fn confirm(question: &str, flag: &str) -> anyhow::Result<bool> {
+    if !io::stdin().is_terminal() {
+        bail!(CodedError::usage(format!(
+            "confirmation required. Pass {flag} to proceed without asking"
+        )));
+    }
+
+    eprint!("{question} [y/N] ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
@@ -0,0 +1,111 @@
+//! Choosing tasks on the command line, by ID or with a filter
+
+use std::str::FromStr;
+
+use rustwarrior_core::{Filter, OpenTask, Store};
+
+use crate::ids::{self, IdRange};
+
+/// The tasks a command acts on
+///
+/// Either IDs and ranges like `1,3,5-8`, or a filter expression like
+/// `+work and priority.above:3` (see [`rustwarrior_core::filter`]). Deleted
+/// tasks are left out, unless the filter asks for them with
+/// `status:deleted`.
+#[derive(Debug, Clone)]
+pub enum Selector {
+    /// Tasks chosen by ID
+    Ids(Vec<IdRange>),
+    /// Tasks matching a filter
+    Filter(Filter),
+}
+
+/// The result of [`Selector::select`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Selection {
+    /// The IDs of the selected tasks
+    pub ids: Vec<usize>,
+    /// IDs which were asked for but don't exist
+    pub missing: Vec<IdRange>,
+}
+
+impl Selector {
+    /// Find the selected tasks in the store
+    pub fn select(&self, store: &Store) -> Selection {
+        match self {
+            Self::Ids(ranges) => {
+                let expanded = ids::expand(ranges, &ids::live(store));
+                Selection {
+                    ids: expanded.ids,
+                    missing: expanded.missing,
+                }
+            }
+            Self::Filter(filter) => Selection {
+                ids: store.matching(filter).map(OpenTask::id).collect(),
+                missing: Vec::new(),
+            },
+        }
+    }
+}
+
+impl FromStr for Selector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(ranges) = s.split(',').map(str::parse).collect() {
+            return Ok(Self::Ids(ranges));
+        }
+
+        let filter = Filter::parse(s).map_err(|e| e.to_string())?;
+        if filter.is_empty() {
+            return Err("no tasks selected".to_string());
+        }
+        Ok(Self::Filter(filter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use rustwarrior_core::{Priority, Store, Task};
+    use test_case::test_case;
+
+    use super::Selector;
+
+    fn store() -> Store {
+        let mut store = Store::new();
+        store.push(
+            Task::new("Write report".to_string())
+                .with_tag("work".to_string())
+                .with_priority(Priority::One),
+        );
+        store.push(Task::new("Water plants".to_string()).with_project("home".to_string()));
+        store.push(
+            Task::new("Book flights".to_string())
+                .with_tag("work".to_string())
+                .with_project("travel".to_string()),
+        );
+        store.push(Task::new("Book more flights".to_string()).with_deleted(Utc::now()));
+        store
+    }
+
+    #[test_case("1" => (vec![1], vec![]) ; "id")]
+    #[test_case("0,2-4" => (vec![0, 2], vec!["3-4".to_string()]) ; "ids and ranges")]
+    #[test_case("1-100000,4" => (vec![1, 2], vec!["3-100000".to_string()]) ; "range past the store")]
+    #[test_case("+work" => (vec![0, 2], vec![]) ; "tag")]
+    #[test_case("+work project:" => (vec![0], vec![]) ; "unset attribute")]
+    #[test_case("project:home" => (vec![1], vec![]) ; "project")]
+    #[test_case("priority:1" => (vec![0], vec![]) ; "priority")]
+    #[test_case("-work status:pending" => (vec![1], vec![]) ; "not tag and status")]
+    #[test_case("water" => (vec![1], vec![]) ; "word")]
+    #[test_case("+home" => (vec![], vec![]) ; "no match")]
+    #[test_case("3" => (vec![], vec!["3".to_string()]) ; "deleted id")]
+    #[test_case("flights" => (vec![2], vec![]) ; "deleted left out")]
+    #[test_case("status:deleted" => (vec![3], vec![]) ; "deleted")]
+    #[test_case("+work and (priority.above:2 or project:travel)" => (vec![0, 2], vec![]) ; "expression")]
+    fn select(selector: &str) -> (Vec<usize>, Vec<String>) {
+        let selection = selector.parse::<Selector>().unwrap().select(&store());
+        let missing = selection.missing.iter().map(ToString::to_string).collect();
+        (selection.ids, missing)
+    }
+}
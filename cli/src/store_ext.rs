@@ -1,32 +1,64 @@
 //! CLI-specific convenience methods for Store
 
-use std::fs;
+use std::{fs, path::Path};
 
-use rustwarrior_core::Store;
+use rustwarrior_core::{
+    Config, Store, git,
+    store::{backup, paths},
+};
 
 /// Extension trait for Store providing default path convenience methods
 pub trait StoreExt {
-    /// Load tasks from the default location
-    fn load_default() -> anyhow::Result<Self>
+    /// Load tasks from the data directory of the resolved config
+    fn load_default(config: &Config) -> anyhow::Result<Self>
     where
         Self: Sized;
 
-    /// Save tasks to the default location
-    fn save_default(&self) -> anyhow::Result<()>;
+    /// Save tasks to the data directory of the resolved config
+    ///
+    /// The previous contents are first snapshotted into the backups directory.
+    /// If the data directory is a git repository, the change is committed.
+    fn save_default(&self, config: &Config) -> anyhow::Result<()>;
+
+    /// Save tasks like [`save_default`], returning
+    /// a failure to commit the change instead of printing it
+    ///
+    /// [`save_default`]: StoreExt::save_default
+    fn save_default_quietly(&self, config: &Config) -> anyhow::Result<Option<String>>;
 }
 
 impl StoreExt for Store {
-    fn load_default() -> anyhow::Result<Self> {
-        let data_dir = rustwarrior_core::store::paths::get_data_dir()?;
-        let tasks_file = rustwarrior_core::store::paths::get_tasks_file(Some(&data_dir))?;
+    fn load_default(config: &Config) -> anyhow::Result<Self> {
+        let data_dir = paths::get_configured_data_dir(config)?;
+        let tasks_file = paths::get_tasks_file(Some(&data_dir))?;
         Self::load_from_path(&tasks_file).map_err(|e| anyhow::anyhow!(e))
     }
 
-    fn save_default(&self) -> anyhow::Result<()> {
-        let data_dir = rustwarrior_core::store::paths::get_data_dir()?;
-        let tasks_file = rustwarrior_core::store::paths::get_tasks_file(Some(&data_dir))?;
+    fn save_default(&self, config: &Config) -> anyhow::Result<()> {
+        if let Some(warning) = self.save_default_quietly(config)? {
+            eprintln!("warning: {warning}");
+        }
+        Ok(())
+    }
+
+    fn save_default_quietly(&self, config: &Config) -> anyhow::Result<Option<String>> {
+        let data_dir = paths::get_configured_data_dir(config)?;
+        let tasks_file = paths::get_tasks_file(Some(&data_dir))?;
         fs::create_dir_all(&data_dir)?;
+        let backups_dir = paths::get_backups_dir(Some(&data_dir))?;
+        backup::rotate(&tasks_file, &backups_dir, &config.retention())?;
         self.save_to_path(&tasks_file)
-            .map_err(|e| anyhow::anyhow!(e))
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(auto_commit(&data_dir).err())
     }
 }
+
+/// Commit any changes in the data directory, returning a failure as a warning
+///
+/// The commit message records the command line that made the change.
+pub fn auto_commit(data_dir: &Path) -> Result<(), String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    git::auto_commit(data_dir, &format!("rw {}", args.join(" ")))
+        .map_err(|e| format!("failed to commit changes: {e}"))
+}
@@ -0,0 +1,496 @@
+//! The full-screen interface of `rw tui`
+//!
+//! [`App`] holds what is on screen and turns key presses into [`Change`]s.
+//! Each change is saved the way a command saves it: the tasks are loaded,
+//! changed and saved with [`StoreExt`], so backups and git commits happen as
+//! usual. Changes name tasks by UUID, as the IDs on screen may be out of date
+//! by then. Between key presses the tasks file is checked, and the list is
+//! reloaded when another process has changed it.
+
+mod view;
+
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{anyhow, bail};
+use ratatui::{
+    DefaultTerminal,
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    widgets::TableState,
+};
+use rustwarrior_core::{
+    Color, Config, Filter, OpenTask, Priority, Store, Task, context::Context, paths,
+};
+use uuid::Uuid;
+
+use crate::{commands::add::new_task, store_ext::StoreExt};
+
+/// How often the tasks file is checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The filter the list starts with
+const DEFAULT_FILTER: &str = "status:open";
+
+/// Run the interface until it is quit
+pub fn run(config: &Config) -> anyhow::Result<()> {
+    let mut app = App::new(Store::load_default(config)?, config)?;
+    let mut file = TasksFile::new(config)?;
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app, config, &mut file);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    config: &Config,
+    file: &mut TasksFile,
+) -> anyhow::Result<()> {
+    while !app.quit {
+        terminal.draw(|frame| view::draw(frame, app))?;
+
+        if event::poll(POLL_INTERVAL)? {
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if let Some(change) = app.handle_key(key) {
+                match save(&change, config) {
+                    Ok((store, message)) => {
+                        app.load(store);
+                        app.message = Some(message);
+                    }
+                    Err(e) => app.message = Some(format!("{e:#}")),
+                }
+                file.changed();
+            }
+        } else if file.changed() {
+            match Store::load_default(config) {
+                Ok(store) => {
+                    app.load(store);
+                    app.message = Some("Reloaded tasks changed by another program".to_string());
+                }
+                Err(e) => app.message = Some(format!("{e:#}")),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Make a change to the tasks as they are on disk, and save them
+///
+/// A failure to commit the change is added to the message, as printing it
+/// would garble the screen.
+fn save(change: &Change, config: &Config) -> anyhow::Result<(Store, String)> {
+    let mut store = Store::load_default(config)?;
+    let message = change.apply(&mut store, config)?;
+    let message = match store.save_default_quietly(config)? {
+        Some(warning) => format!("{message} (warning: {warning})"),
+        None => message,
+    };
+    Ok((store, message))
+}
+
+/// The tasks file, and when it was last seen to change
+struct TasksFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl TasksFile {
+    fn new(config: &Config) -> anyhow::Result<Self> {
+        let path = paths::get_tasks_file(Some(&paths::get_configured_data_dir(config)?))?;
+        let mut file = Self {
+            path,
+            modified: None,
+        };
+        file.changed();
+        Ok(file)
+    }
+
+    /// Whether the file has changed since the last call
+    fn changed(&mut self) -> bool {
+        let modified = fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let changed = modified != self.modified;
+        self.modified = modified;
+        changed
+    }
+}
+
+/// A change to the tasks, asked for with a key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Add a task with a description
+    Add(String),
+    /// Mark a task completed
+    Complete(Uuid),
+    /// Set or clear the priority of a task
+    Priority(Uuid, Option<Priority>),
+    /// Delete a task
+    Delete(Uuid),
+}
+
+impl Change {
+    /// Make the change, returning what was done
+    fn apply(&self, store: &mut Store, config: &Config) -> anyhow::Result<String> {
+        match self {
+            Self::Add(description) => {
+                let id = store.push(new_task(description.clone(), None, config)?);
+                Ok(format!("Added task {id}"))
+            }
+            Self::Complete(uuid) => update(store, *uuid, |id, task| {
+                if task.is_completed() {
+                    bail!("Task {id} is already completed");
+                }
+                task.mark_completed();
+                Ok(format!("Completed task {id} '{}'", task.description()))
+            }),
+            Self::Priority(uuid, Some(priority)) => update(store, *uuid, |id, task| {
+                task.set_priority(Some(*priority));
+                Ok(format!("Set the priority of task {id} to {priority}"))
+            }),
+            Self::Priority(uuid, None) => update(store, *uuid, |id, task| {
+                task.set_priority(None);
+                Ok(format!("Cleared the priority of task {id}"))
+            }),
+            Self::Delete(uuid) => update(store, *uuid, |id, task| {
+                task.mark_deleted();
+                Ok(format!("Deleted task {id} '{}'", task.description()))
+            }),
+        }
+    }
+}
+
+/// Change a task which hasn't been deleted, given its current ID
+fn update(
+    store: &mut Store,
+    uuid: Uuid,
+    change: impl FnOnce(usize, &mut Task) -> anyhow::Result<String>,
+) -> anyhow::Result<String> {
+    let id = store
+        .get_by_uuid(uuid)
+        .filter(|task| !task.is_deleted())
+        .map(OpenTask::id)
+        .ok_or_else(|| anyhow!("The task is no longer there"))?;
+    let task = store.get_mut(id).expect("task was found above");
+    change(id, task.task_mut())
+}
+
+/// What keys do
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Mode {
+    /// Moving around the list and changing tasks
+    Normal,
+    /// Typing the description of a new task
+    Add(String),
+    /// Typing a filter expression
+    Filter(String),
+    /// Asking whether to delete a task
+    Delete(Uuid),
+}
+
+/// The state of the interface
+#[derive(Debug)]
+pub struct App {
+    store: Store,
+    context: Option<Context>,
+    filter_text: String,
+    filter: Filter,
+    /// The IDs of the listed tasks, in order
+    ids: Vec<usize>,
+    table: TableState,
+    mode: Mode,
+    /// The outcome of the last change, or an error
+    message: Option<String>,
+    styled: bool,
+    quit: bool,
+}
+
+impl App {
+    /// The interface for some tasks, listing open tasks in the active context
+    fn new(store: Store, config: &Config) -> anyhow::Result<Self> {
+        let mut app = Self {
+            store,
+            context: config.context()?,
+            filter_text: DEFAULT_FILTER.to_string(),
+            filter: Filter::parse(DEFAULT_FILTER)?,
+            ids: Vec::new(),
+            table: TableState::default().with_selected(Some(0)),
+            mode: Mode::Normal,
+            message: None,
+            styled: config.color() != Color::Never,
+            quit: false,
+        };
+        app.refresh();
+        Ok(app)
+    }
+
+    /// Show other tasks, keeping the selected task selected if it is still
+    /// listed
+    fn load(&mut self, store: Store) {
+        self.store = store;
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        let selected = self.selected().map(OpenTask::id);
+        let filter = match &self.context {
+            Some(context) => self.filter.clone().and(context.filter().clone()),
+            None => self.filter.clone(),
+        };
+        self.ids = self.store.matching(&filter).map(OpenTask::id).collect();
+
+        let index = selected
+            .and_then(|id| self.ids.iter().position(|listed| *listed == id))
+            .or_else(|| self.table.selected())
+            .map(|index| index.min(self.ids.len().saturating_sub(1)));
+        self.table.select(index);
+    }
+
+    /// The listed tasks
+    fn tasks(&self) -> impl Iterator<Item = &OpenTask> {
+        self.ids.iter().filter_map(|id| self.store.get(*id))
+    }
+
+    /// The task under the cursor
+    fn selected(&self) -> Option<&OpenTask> {
+        self.store.get(*self.ids.get(self.table.selected()?)?)
+    }
+
+    /// Act on a key, returning the change it asks for, if any
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Change> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return None;
+        }
+        match &mut self.mode {
+            Mode::Normal => self.handle_normal_key(key.code),
+            Mode::Add(text) | Mode::Filter(text) => match key.code {
+                KeyCode::Char(c) => {
+                    text.push(c);
+                    None
+                }
+                KeyCode::Backspace => {
+                    text.pop();
+                    None
+                }
+                KeyCode::Esc => {
+                    self.mode = Mode::Normal;
+                    None
+                }
+                KeyCode::Enter => self.submit(),
+                _ => None,
+            },
+            Mode::Delete(uuid) => {
+                let uuid = *uuid;
+                self.mode = Mode::Normal;
+                if key.code == KeyCode::Char('y') {
+                    Some(Change::Delete(uuid))
+                } else {
+                    self.message = Some("Nothing deleted".to_string());
+                    None
+                }
+            }
+        }
+    }
+
+    fn handle_normal_key(&mut self, code: KeyCode) -> Option<Change> {
+        self.message = None;
+        let selected = self.selected().map(|task| task.uuid());
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('j') | KeyCode::Down => self.table.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.table.select_previous(),
+            KeyCode::Char('g') | KeyCode::Home => self.table.select_first(),
+            KeyCode::Char('G') | KeyCode::End => self.table.select_last(),
+            KeyCode::Char('a') => self.mode = Mode::Add(String::new()),
+            KeyCode::Char('/') => self.mode = Mode::Filter(self.filter_text.clone()),
+            KeyCode::Char('d') => return selected.map(Change::Complete),
+            KeyCode::Char('x') | KeyCode::Delete => {
+                if let Some(uuid) = selected {
+                    self.mode = Mode::Delete(uuid);
+                }
+            }
+            KeyCode::Char('0') => return selected.map(|uuid| Change::Priority(uuid, None)),
+            KeyCode::Char(c @ '1'..='4') => {
+                let priority = Priority::try_from(c as u8 - b'0').ok();
+                return selected.map(|uuid| Change::Priority(uuid, priority));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Finish typing a description or filter
+    fn submit(&mut self) -> Option<Change> {
+        match std::mem::replace(&mut self.mode, Mode::Normal) {
+            Mode::Add(description) if !description.trim().is_empty() => {
+                Some(Change::Add(description.trim().to_string()))
+            }
+            Mode::Filter(text) => {
+                match Filter::parse(&text) {
+                    Ok(filter) => {
+                        self.filter = filter;
+                        self.filter_text = text;
+                        self.table.select(Some(0));
+                        self.refresh();
+                    }
+                    Err(e) => {
+                        self.message = Some(format!("Invalid filter: {e}"));
+                        self.mode = Mode::Filter(text);
+                    }
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::{KeyCode, KeyEvent};
+    use rustwarrior_core::{Config, Priority, Store, Task};
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    use super::{App, Change, Mode, TasksFile};
+
+    pub(super) fn app() -> App {
+        let mut store = Store::new();
+        store.push(Task::new("Write report".to_string()).with_priority(Priority::One));
+        store.push(Task::new("Call mum".to_string()));
+        store.push(Task::new("Pay rent".to_string()).with_completed(chrono::Utc::now()));
+        App::new(store, &Config::default()).unwrap()
+    }
+
+    fn press(app: &mut App, keys: &str) -> Option<Change> {
+        keys.chars()
+            .map(|c| {
+                let code = match c {
+                    '\n' => KeyCode::Enter,
+                    '\x1b' => KeyCode::Esc,
+                    c => KeyCode::Char(c),
+                };
+                app.handle_key(KeyEvent::from(code))
+            })
+            .last()
+            .flatten()
+    }
+
+    #[test]
+    fn lists_open_tasks() {
+        let app = app();
+        assert_eq!(app.ids, [0, 1]);
+        assert_eq!(app.selected().unwrap().id(), 0);
+    }
+
+    fn uuid(app: &App, id: usize) -> Uuid {
+        app.store.get(id).unwrap().uuid()
+    }
+
+    #[test]
+    fn keys_ask_for_changes() {
+        let mut app = app();
+        let (report, call) = (uuid(&app, 0), uuid(&app, 1));
+        assert_eq!(press(&mut app, "d"), Some(Change::Complete(report)));
+        assert_eq!(
+            press(&mut app, "j3"),
+            Some(Change::Priority(call, Some(Priority::Three)))
+        );
+        assert_eq!(press(&mut app, "0"), Some(Change::Priority(call, None)));
+        assert_eq!(press(&mut app, "xn"), None);
+        assert_eq!(press(&mut app, "xy"), Some(Change::Delete(call)));
+        assert_eq!(
+            press(&mut app, "aBuy milk\n"),
+            Some(Change::Add("Buy milk".to_string()))
+        );
+        assert_eq!(press(&mut app, "aNever mind\x1b"), None);
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn filter() {
+        let mut app = app();
+        press(&mut app, "/");
+        assert_eq!(app.mode, Mode::Filter("status:open".to_string()));
+        press(&mut app, " /mum/\n");
+        assert_eq!(app.ids, [1]);
+
+        press(&mut app, "/ (\n");
+        assert!(
+            app.message
+                .as_deref()
+                .unwrap()
+                .starts_with("Invalid filter")
+        );
+        assert!(matches!(app.mode, Mode::Filter(_)));
+        assert_eq!(app.ids, [1]);
+    }
+
+    #[test]
+    fn changes_apply_to_the_store() {
+        let mut app = app();
+        let config = Config::default();
+        let (report, call) = (uuid(&app, 0), uuid(&app, 1));
+        for change in [
+            Change::Add("Buy milk".to_string()),
+            Change::Complete(report),
+            Change::Priority(call, Some(Priority::Two)),
+        ] {
+            change.apply(&mut app.store, &config).unwrap();
+        }
+        assert!(
+            Change::Complete(report)
+                .apply(&mut app.store, &config)
+                .is_err()
+        );
+        assert!(
+            Change::Delete(Uuid::new_v4())
+                .apply(&mut app.store, &config)
+                .is_err()
+        );
+        app.refresh();
+
+        assert_eq!(app.ids, [1, 3]);
+        assert_eq!(app.store.get(1).unwrap().priority(), Some(Priority::Two));
+    }
+
+    #[test]
+    fn changes_follow_the_task_not_the_id() {
+        let mut app = app();
+        let config = Config::default();
+        let change = Change::Complete(uuid(&app, 0));
+
+        // Another program removes the task, and a new one takes its ID
+        app.store.delete(0);
+        app.store.push(Task::new("Water plants".to_string()));
+
+        assert!(change.apply(&mut app.store, &config).is_err());
+        assert!(!app.store.get(0).unwrap().is_completed());
+    }
+
+    #[test]
+    fn watches_the_configured_tasks_file() {
+        let dir = TempDir::new().unwrap();
+        let config = Config {
+            data_dir: Some(dir.path().to_path_buf()),
+            ..Config::default()
+        };
+        let mut file = TasksFile::new(&config).unwrap();
+        assert_eq!(file.path, dir.path().join("open_tasks.jsonl"));
+
+        Store::new().save_to_path(&file.path).unwrap();
+        assert!(file.changed());
+        assert!(!file.changed());
+    }
+}
@@ -0,0 +1,170 @@
+//! Drawing the task list, the detail pane and the status lines
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Row, Table, Wrap},
+};
+use rustwarrior_core::{OpenTask, Priority};
+
+use super::{App, Mode};
+use crate::commands::info::details;
+
+/// What the keys do, shown at the bottom of the screen
+const KEYS: &str =
+    "a add  d done  1-4 priority  0 clear priority  x delete  / filter  j/k move  q quit";
+
+/// Draw the whole screen
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let [main, status, keys] = Layout::vertical([
+        Constraint::Min(0),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [list, detail] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(main);
+
+    let table = task_table(app);
+    frame.render_stateful_widget(table, list, &mut app.table);
+    frame.render_widget(detail_pane(app), detail);
+    frame.render_widget(status_line(app), status);
+    frame.render_widget(Line::from(KEYS).style(modifier(app, Modifier::DIM)), keys);
+}
+
+fn task_table(app: &App) -> Table<'static> {
+    let rows: Vec<Row> = app
+        .tasks()
+        .map(|task| {
+            let tags: Vec<String> = task.tags().iter().map(|tag| format!("+{tag}")).collect();
+            Row::new([
+                task.id().to_string(),
+                task.priority()
+                    .map(|priority| priority.to_string())
+                    .unwrap_or_default(),
+                task.project().unwrap_or_default().to_string(),
+                tags.join(" "),
+                task.description().clone(),
+            ])
+            .style(row_style(app, task))
+        })
+        .collect();
+
+    let context = app
+        .context
+        .as_ref()
+        .map(|context| format!("· context {} ", context.name()))
+        .unwrap_or_default();
+    let title = format!(" Tasks ({}) · {} {context}", app.ids.len(), app.filter_text);
+    Table::new(
+        rows,
+        [
+            Constraint::Length(4),
+            Constraint::Length(3),
+            Constraint::Max(12),
+            Constraint::Max(16),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(
+        Row::new(["ID", "Pri", "Project", "Tags", "Description"])
+            .style(modifier(app, Modifier::BOLD)),
+    )
+    .block(Block::bordered().title(title))
+    .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+    .highlight_symbol("> ")
+}
+
+/// Important and started tasks stand out, as in `rw list`
+fn row_style(app: &App, task: &OpenTask) -> Style {
+    if !app.styled {
+        return Style::new();
+    }
+    if task.is_started() {
+        return Style::new().fg(Color::Green);
+    }
+    match task.priority() {
+        Some(Priority::One) => Style::new().fg(Color::Red),
+        Some(Priority::Two) => Style::new().fg(Color::Yellow),
+        _ => Style::new(),
+    }
+}
+
+fn detail_pane(app: &App) -> Paragraph<'static> {
+    let lines: Vec<Line> = app.selected().map_or_else(
+        || vec![Line::from("No task selected")],
+        |task| {
+            details(task)
+                .into_iter()
+                .map(|(name, value)| {
+                    Line::from(vec![
+                        Span::styled(format!("{name}: "), modifier(app, Modifier::DIM)),
+                        Span::raw(value),
+                    ])
+                })
+                .collect()
+        },
+    );
+    Paragraph::new(lines)
+        .block(Block::bordered().title(" Details "))
+        .wrap(Wrap { trim: false })
+}
+
+/// What is being typed or asked, or the outcome of the last change
+fn status_line(app: &App) -> Line<'static> {
+    match &app.mode {
+        Mode::Add(text) => Line::from(format!("Add: {text}█")),
+        Mode::Filter(text) => Line::from(format!(
+            "Filter: {text}█  {}",
+            app.message.as_deref().unwrap_or_default()
+        )),
+        Mode::Delete(uuid) => {
+            let question = app.store.get_by_uuid(*uuid).map_or_else(
+                || "Delete the task? (y/n)".to_string(),
+                |task| format!("Delete task {} '{}'? (y/n)", task.id(), task.description()),
+            );
+            Line::from(question)
+        }
+        Mode::Normal => Line::from(app.message.clone().unwrap_or_default()),
+    }
+}
+
+/// Text in bold or dimmed, unless colour is turned off
+const fn modifier(app: &App, modifier: Modifier) -> Style {
+    if app.styled {
+        Style::new().add_modifier(modifier)
+    } else {
+        Style::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use super::draw;
+    use crate::tui::tests::app;
+
+    #[test]
+    fn shows_list_and_details() {
+        let mut app = app();
+        let mut terminal = Terminal::new(TestBackend::new(100, 12)).unwrap();
+        terminal.draw(|frame| draw(frame, &mut app)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(ratatui::buffer::Cell::symbol)
+            .collect();
+        assert!(screen.contains("Tasks (2) · status:open"));
+        assert!(screen.contains("> 0"));
+        assert!(screen.contains("Call mum"));
+        assert!(!screen.contains("Pay rent"));
+        assert!(screen.contains("Description: Write report"));
+        assert!(screen.contains("a add"));
+    }
+}
@@ -0,0 +1,93 @@
+//! Adding tasks, one at a time or a line each from a file
+
+mod common;
+
+use std::{
+    fs,
+    io::Write,
+    process::{Output, Stdio},
+};
+
+use serde_json::Value;
+use tempfile::TempDir;
+
+fn rw(dir: &TempDir, args: &[&str], stdin: &str) -> Output {
+    let mut child = common::command(dir)
+        .args(args)
+        .env("RUSTWARRIOR_BACKUP_COUNT", "none")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run rw");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().expect("failed to run rw")
+}
+
+fn backups(dir: &TempDir) -> usize {
+    fs::read_dir(dir.path().join("backups")).map_or(0, Iterator::count)
+}
+
+#[test]
+fn lines_from_stdin_are_added_in_one_save() {
+    let dir = TempDir::new().unwrap();
+    rw(&dir, &["add", "Existing"], "");
+    let before = backups(&dir);
+
+    let output = rw(
+        &dir,
+        &["--output", "json", "add", "-", "-p", "3"],
+        "Buy milk\n\n  Pay rent !1  \npriority:2 Water plants\n",
+    );
+    assert!(output.status.success());
+    let added: Value = serde_json::from_slice(&output.stdout).expect("output is not valid JSON");
+    let added: Vec<(u64, &str, u64)> = added
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| {
+            (
+                task["id"].as_u64().unwrap(),
+                task["description"].as_str().unwrap(),
+                task["priority"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        added,
+        [
+            (1, "Buy milk", 3),
+            (2, "Pay rent", 1),
+            (3, "Water plants", 2)
+        ]
+    );
+    assert_eq!(backups(&dir), before + 1);
+}
+
+#[test]
+fn invalid_line_adds_nothing() {
+    let dir = TempDir::new().unwrap();
+    let notes = dir.path().join("notes.txt");
+    fs::write(&notes, "Buy milk\nFix bug priority:9\n").unwrap();
+
+    let output = rw(
+        &dir,
+        &["--output", "json", "add", "--file", notes.to_str().unwrap()],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let error: Value = serde_json::from_slice(&output.stderr).expect("error is not valid JSON");
+    assert_eq!(error["error"]["code"], "usage");
+    assert!(
+        error["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("line 2:")
+    );
+    assert!(!dir.path().join("open_tasks.jsonl").exists());
+}
@@ -0,0 +1,53 @@
+//! Snapshots with `rw backup list` and `rw restore`
+
+mod common;
+
+use common::{json, rw};
+use serde_json::{Value, json};
+use tempfile::TempDir;
+
+#[test]
+fn restore_snapshot() {
+    let dir = TempDir::new().unwrap();
+    rw(&dir, &["add", "keep me"]);
+    rw(&dir, &["add", "bad edit"]);
+
+    let snapshots = json(&rw(&dir, &["backup", "list", "--output", "json"]));
+    assert_eq!(snapshots.as_array().unwrap().len(), 1);
+    assert_eq!(snapshots[0]["tasks"], json!(1));
+    let id = snapshots[0]["id"].as_str().unwrap();
+
+    let preview = json(&rw(&dir, &["restore", id, "--dry-run", "--output", "json"]));
+    assert_eq!(preview["restored"], json!(false));
+    assert_eq!(preview["added"], json!([]));
+    assert_eq!(preview["removed"][0]["description"], json!("bad edit"));
+    assert_eq!(
+        json(&rw(&dir, &["list", "--output", "json"]))
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let unconfirmed = rw(&dir, &["restore", id]);
+    assert!(!unconfirmed.status.success());
+
+    let restored = json(&rw(
+        &dir,
+        &["restore", &id[..8], "--yes", "--output", "json"],
+    ));
+    assert_eq!(restored["restored"], json!(true));
+    let tasks = json(&rw(&dir, &["list", "--output", "json"]));
+    assert_eq!(tasks.as_array().unwrap().len(), 1);
+    assert_eq!(tasks[0]["description"], json!("keep me"));
+}
+
+#[test]
+fn unknown_snapshot() {
+    let dir = TempDir::new().unwrap();
+
+    let missing = rw(&dir, &["--output", "json", "restore", "1999"]);
+    assert!(!missing.status.success());
+    let error: Value = serde_json::from_slice(&missing.stderr).unwrap();
+    assert_eq!(error["error"]["code"], json!("not_found"));
+}
@@ -0,0 +1,57 @@
+//! Helpers shared by the integration tests
+//!
+//! Each test file only uses some of them.
+#![allow(dead_code)]
+
+use std::{
+    ffi::OsStr,
+    process::{Command, Output, Stdio},
+};
+
+use rustwarrior_core::config::Setting;
+use serde_json::Value;
+use tempfile::TempDir;
+
+/// A command running `rw` with its data directory and config file in `dir`
+///
+/// Settings from the environment running the tests are removed, and times are
+/// in UTC.
+pub fn command(dir: &TempDir) -> Command {
+    command_at(env!("CARGO_BIN_EXE_rw"), dir)
+}
+
+/// Like [`command`], running a copy of `rw` elsewhere
+pub fn command_at(exe: impl AsRef<OsStr>, dir: &TempDir) -> Command {
+    let mut command = Command::new(exe);
+    for setting in Setting::ALL {
+        command.env_remove(setting.env());
+    }
+    command
+        .env("RUSTWARRIOR_DATA_DIR", dir.path())
+        .env("RUSTWARRIOR_CONFIG", dir.path().join("config.toml"))
+        .env("TZ", "UTC")
+        .stdin(Stdio::null());
+    command
+}
+
+/// Run `rw` in `dir`
+pub fn rw(dir: &TempDir, args: &[&str]) -> Output {
+    command(dir).args(args).output().expect("failed to run rw")
+}
+
+/// The JSON printed by a command, which must have succeeded
+pub fn json(output: &Output) -> Value {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).expect("output is not valid JSON")
+}
+
+/// The JSON error printed by a command run with `--output json`, which must
+/// have failed
+pub fn error(output: &Output) -> Value {
+    assert!(!output.status.success(), "the command succeeded");
+    serde_json::from_slice(&output.stderr).expect("error is not valid JSON")
+}
@@ -0,0 +1,64 @@
+//! Shell completion scripts, and the task IDs they complete
+
+mod common;
+
+use std::process::Output;
+
+use tempfile::TempDir;
+
+fn rw(dir: &TempDir, args: &[&str], shell: Option<&str>) -> Output {
+    let mut command = common::command(dir);
+    command.args(args).env_remove("COMPLETE");
+    if let Some(shell) = shell {
+        command.env("COMPLETE", shell);
+    }
+    command.output().expect("failed to run rw")
+}
+
+/// The candidates fish is offered for the last word, with their help
+fn complete(dir: &TempDir, words: &[&str]) -> Vec<String> {
+    let output = rw(dir, &[&["--", "rw"], words].concat(), Some("fish"));
+    assert!(output.status.success());
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .filter(|line| !line.starts_with("--"))
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn scripts_call_back_into_rw() {
+    let dir = TempDir::new().unwrap();
+    for shell in ["bash", "zsh", "fish"] {
+        let output = rw(&dir, &["completions", shell], None);
+        assert!(output.status.success());
+        let script = String::from_utf8(output.stdout).unwrap();
+        let script = script.replace('"', "");
+        assert!(script.contains(&format!("COMPLETE={shell}")), "{script}");
+    }
+    assert_eq!(
+        rw(&dir, &["completions", "tcsh"], None).status.code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn ids_are_completed_with_descriptions() {
+    let dir = TempDir::new().unwrap();
+    rw(&dir, &["add", "Write report"], None);
+    rw(&dir, &["add", "Call mum"], None);
+    rw(&dir, &["modify", "0", "+work", "project:office"], None);
+    rw(&dir, &["done", "1"], None);
+
+    assert_eq!(
+        complete(&dir, &["done", ""]),
+        ["0\tWrite report +work project:office"]
+    );
+    assert_eq!(complete(&dir, &["reopen", ""]), ["1\tCall mum"]);
+    assert!(complete(&dir, &["stop", ""]).is_empty());
+    assert_eq!(
+        complete(&dir, &["list", ""]),
+        ["+work\ttag", "project:office\tproject"]
+    );
+}
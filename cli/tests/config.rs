@@ -0,0 +1,137 @@
+//! Reading and changing settings with `rw config`
+
+mod common;
+
+use std::{
+    fs,
+    process::{Command, Output},
+};
+
+use serde_json::Value;
+use tempfile::TempDir;
+
+fn rw(dir: &TempDir, args: &[&str]) -> Output {
+    // The data directory comes from the config file here
+    common::command(dir)
+        .args(args)
+        .env_remove("RUSTWARRIOR_DATA_DIR")
+        .output()
+        .expect("failed to run rw")
+}
+
+fn get(dir: &TempDir, key: &str) -> Value {
+    let output = rw(dir, &["--output", "json", "config", "get", key]);
+    serde_json::from_slice(&output.stdout).expect("output is not valid JSON")
+}
+
+#[test]
+fn set_and_unset_keep_comments() {
+    let dir = TempDir::new().unwrap();
+    let config = dir.path().join("config.toml");
+    fs::write(&config, "# Where my tasks live\ncolor = \"never\"\n").unwrap();
+
+    let data_dir = dir.path().join("tasks");
+    let data_dir = data_dir.to_str().unwrap();
+    assert!(
+        rw(&dir, &["config", "set", "data_dir", data_dir])
+            .status
+            .success()
+    );
+    assert!(
+        rw(&dir, &["config", "set", "default_priority", "2"])
+            .status
+            .success()
+    );
+    assert!(
+        rw(&dir, &["config", "set", "reports.next.limit", "1"])
+            .status
+            .success()
+    );
+
+    let text = fs::read_to_string(&config).unwrap();
+    assert!(text.starts_with("# Where my tasks live\ncolor = \"never\"\n"));
+    assert!(text.contains("[reports.next]\nlimit = 1"), "{text}");
+    assert_eq!(get(&dir, "default_priority")["source"], "file");
+    assert_eq!(
+        get(&dir, "reports.next.sort")["value"],
+        "priority-,created+"
+    );
+
+    // New tasks go to the configured data dir, with the default priority
+    rw(&dir, &["add", "Water plants"]);
+    rw(&dir, &["add", "Pay rent", "-p", "1"]);
+    let tasks = fs::read_to_string(dir.path().join("tasks/open_tasks.jsonl")).unwrap();
+    let priorities: Vec<Value> = tasks
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap()["priority"].clone())
+        .collect();
+    assert_eq!(priorities, [2, 1]);
+
+    assert!(
+        rw(&dir, &["config", "unset", "default_priority"])
+            .status
+            .success()
+    );
+    assert_eq!(get(&dir, "date_format")["source"], "default");
+    assert!(
+        !fs::read_to_string(&config)
+            .unwrap()
+            .contains("default_priority")
+    );
+
+    let missing = rw(&dir, &["config", "unset", "default_priority"]);
+    assert_eq!(missing.status.code(), Some(1));
+}
+
+#[test]
+fn set_keeps_comments_on_the_key() {
+    let dir = TempDir::new().unwrap();
+    let config = dir.path().join("config.toml");
+    fs::write(
+        &config,
+        "# How dates are shown\ndate_format = \"%Y-%m-%d\"  # ISO dates\ncolor = \"never\"\n",
+    )
+    .unwrap();
+
+    assert!(
+        rw(&dir, &["config", "set", "date_format", "%d/%m/%Y"])
+            .status
+            .success()
+    );
+
+    assert_eq!(
+        fs::read_to_string(&config).unwrap(),
+        "# How dates are shown\ndate_format = \"%d/%m/%Y\"  # ISO dates\ncolor = \"never\"\n"
+    );
+}
+
+#[test]
+fn invalid_values_are_rejected() {
+    let dir = TempDir::new().unwrap();
+    for args in [
+        ["config", "set", "default_priority", "7"],
+        ["config", "set", "colour", "never"],
+        ["config", "set", "reports.next.sort", "urgency-"],
+        ["config", "set", "reports.next.columns", "id,bogus"],
+        ["config", "set", "reports.done.filter", "+work"],
+    ] {
+        let output = rw(&dir, &args);
+        assert_eq!(output.status.code(), Some(1), "{args:?}");
+    }
+    assert!(!dir.path().join("config.toml").exists());
+}
+
+#[test]
+fn environment_overrides_file() {
+    let dir = TempDir::new().unwrap();
+    rw(&dir, &["config", "set", "date_format", "%d/%m/%Y"]);
+    let output = Command::new(env!("CARGO_BIN_EXE_rw"))
+        .args(["--output", "json", "config", "get", "date_format"])
+        .env("RUSTWARRIOR_CONFIG", dir.path().join("config.toml"))
+        .env("RUSTWARRIOR_DATE_FORMAT", "%Y")
+        .output()
+        .unwrap();
+    let setting: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(setting["value"], "%Y");
+    assert_eq!(setting["source"], "environment");
+}
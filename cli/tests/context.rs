@@ -0,0 +1,67 @@
+//! Switching contexts with `rw context`, and the filter and attributes they
+//! give listings, reports and new tasks
+
+mod common;
+
+use std::fs;
+
+use common::{error, json, rw};
+use serde_json::Value;
+use tempfile::TempDir;
+
+fn descriptions(dir: &TempDir, args: &[&str]) -> Vec<String> {
+    json(&rw(dir, &[&["--output", "json"], args].concat()))
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| task["description"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn context_filters_listings_and_tags_new_tasks() {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("config.toml"),
+        "[contexts]\nwork = \"+work priority:2\"\nhome = \"project:home\"\n",
+    )
+    .unwrap();
+    rw(&dir, &["add", "Water plants"]);
+
+    let switched = json(&rw(&dir, &["--output", "json", "context", "work"]));
+    assert_eq!(switched["name"], "work");
+    rw(&dir, &["add", "Write report"]);
+
+    assert_eq!(descriptions(&dir, &["list"]), ["Write report"]);
+    assert_eq!(descriptions(&dir, &["next"]), ["Write report"]);
+    assert_eq!(
+        descriptions(&dir, &["--context", "none", "list"]),
+        ["Water plants", "Write report"]
+    );
+    let task = &json(&rw(&dir, &["--output", "json", "info", "1"]));
+    assert_eq!(task["tags"], serde_json::json!(["work"]));
+    assert_eq!(task["priority"], 2);
+
+    let contexts = json(&rw(&dir, &["--output", "json", "context", "list"]));
+    assert_eq!(contexts[0]["name"], "home");
+    assert_eq!(contexts[0]["active"], false);
+    assert_eq!(contexts[1]["active"], true);
+
+    json(&rw(&dir, &["--output", "json", "context", "none"]));
+    assert_eq!(
+        json(&rw(&dir, &["--output", "json", "context"])),
+        Value::Null
+    );
+    assert_eq!(descriptions(&dir, &["list"]).len(), 2);
+    let config = fs::read_to_string(dir.path().join("config.toml")).unwrap();
+    assert!(!config.contains("context ="));
+}
+
+#[test]
+fn unknown_context_is_rejected() {
+    let dir = TempDir::new().unwrap();
+    let output = rw(&dir, &["--output", "json", "context", "work"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(error(&output)["error"]["code"], "not_found");
+    assert!(!dir.path().join("config.toml").exists());
+}
@@ -0,0 +1,48 @@
+//! Deleting tasks with `rw delete`, and removing them for good with `rw purge`
+
+mod common;
+
+use common::{json, rw};
+use serde_json::{Value, json};
+use tempfile::TempDir;
+
+fn descriptions(dir: &TempDir) -> Vec<Value> {
+    json(&rw(dir, &["list", "--output", "json"]))
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| task["description"].clone())
+        .collect()
+}
+
+#[test]
+fn delete_and_purge() {
+    let dir = TempDir::new().unwrap();
+    for description in ["keep", "drop", "also drop"] {
+        rw(&dir, &["add", description]);
+    }
+
+    let unconfirmed = rw(&dir, &["delete", "1"]);
+    assert!(!unconfirmed.status.success());
+    assert_eq!(descriptions(&dir).len(), 3);
+
+    let deleted = rw(&dir, &["--output", "json", "delete", "--yes", "drop"]);
+    assert!(deleted.status.success());
+    let deleted = json(&deleted);
+    assert_eq!(deleted.as_array().unwrap().len(), 2);
+    assert_eq!(deleted[0]["status"], json!("deleted"));
+    assert_eq!(descriptions(&dir), [json!("keep")]);
+
+    let info = json(&rw(&dir, &["info", "1", "--output", "json"]));
+    assert_eq!(info["status"], json!("deleted"));
+
+    let recent = json(&rw(&dir, &["purge", "--output", "json"]));
+    assert_eq!(recent, json!([]));
+
+    let purged = json(&rw(
+        &dir,
+        &["purge", "--older-than", "0", "--output", "json"],
+    ));
+    assert_eq!(purged.as_array().unwrap().len(), 2);
+    assert!(!rw(&dir, &["info", "1"]).status.success());
+}
@@ -0,0 +1,86 @@
+//! Completing and reopening tasks by ID with `rw done` and `rw reopen`
+
+mod common;
+
+use common::rw;
+use serde_json::{Value, json};
+use tempfile::TempDir;
+
+fn statuses(dir: &TempDir) -> Vec<Value> {
+    let listed: Value = serde_json::from_slice(&rw(dir, &["list", "--output", "json"]).stdout)
+        .expect("output is not valid JSON");
+    listed
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| task["status"].clone())
+        .collect()
+}
+
+#[test]
+fn done_and_reopen() {
+    let dir = TempDir::new().unwrap();
+    for description in ["zero", "one", "two", "three"] {
+        rw(&dir, &["add", description]);
+    }
+
+    let done = rw(&dir, &["--output", "json", "done", "0", "2-3", "9"]);
+    assert!(!done.status.success());
+    let results: Value = serde_json::from_slice(&done.stdout).unwrap();
+    let outcomes: Vec<&Value> = results
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| &result["outcome"])
+        .collect();
+    assert_eq!(
+        outcomes,
+        [
+            &json!("changed"),
+            &json!("changed"),
+            &json!("changed"),
+            &json!("not_found")
+        ]
+    );
+    let error: Value = serde_json::from_slice(&done.stderr).unwrap();
+    assert_eq!(error["error"]["code"], json!("not_found"));
+    assert_eq!(
+        statuses(&dir),
+        [
+            json!("completed"),
+            json!("pending"),
+            json!("completed"),
+            json!("completed")
+        ]
+    );
+
+    let reopen = rw(&dir, &["reopen", "2,3"]);
+    assert!(reopen.status.success());
+    assert_eq!(
+        statuses(&dir),
+        [
+            json!("completed"),
+            json!("pending"),
+            json!("pending"),
+            json!("pending")
+        ]
+    );
+}
+
+#[test]
+fn missing_ranges_fail() {
+    let dir = TempDir::new().unwrap();
+    for description in ["zero", "one"] {
+        rw(&dir, &["add", description]);
+    }
+
+    let done = rw(&dir, &["--output", "json", "done", "1-3", "50-60"]);
+    assert!(!done.status.success());
+    let error: Value = serde_json::from_slice(&done.stderr).unwrap();
+    assert_eq!(error["error"]["code"], json!("not_found"));
+    assert_eq!(
+        error["error"]["message"],
+        json!("tasks 2-3, 50-60 not found")
+    );
+    assert_eq!(statuses(&dir), [json!("pending"), json!("completed")]);
+}
@@ -0,0 +1,52 @@
+//! Editing tasks in `$EDITOR` with `rw edit`
+#![cfg(unix)]
+
+mod common;
+
+use std::process::Output;
+
+use serde_json::{Value, json};
+use tempfile::TempDir;
+
+fn rw(dir: &TempDir, editor: &str, args: &[&str]) -> Output {
+    common::command(dir)
+        .args(args)
+        .env_remove("VISUAL")
+        .env("EDITOR", editor)
+        .output()
+        .expect("failed to run rw")
+}
+
+#[test]
+fn edit_with_sed() {
+    let dir = TempDir::new().unwrap();
+    rw(&dir, "true", &["add", "pay rent", "-p", "2"]);
+
+    let edited = rw(
+        &dir,
+        "sed -i -e s/^priority:.*$/priority:1/ -e s/^tags:.*$/tags:bills/",
+        &["--output", "json", "edit", "0"],
+    );
+    assert!(edited.status.success());
+    let edited: Value = serde_json::from_slice(&edited.stdout).unwrap();
+    assert_eq!(
+        edited["changes"],
+        json!([
+            {"field": "priority", "before": "2", "after": "1"},
+            {"field": "tags", "before": null, "after": "bills"},
+        ])
+    );
+    assert_eq!(edited["task"]["priority"], json!(1));
+
+    // An invalid edit reopens the editor, and making no further changes cancels it
+    let invalid = rw(
+        &dir,
+        "sed -i s/^priority:.*$/priority:9/",
+        &["--output", "json", "edit", "0"],
+    );
+    assert!(invalid.status.success());
+    assert!(String::from_utf8_lossy(&invalid.stderr).contains("Reopening the editor"));
+    let invalid: Value = serde_json::from_slice(&invalid.stdout).unwrap();
+    assert_eq!(invalid["changes"], json!([]));
+    assert_eq!(invalid["task"]["priority"], json!(1));
+}
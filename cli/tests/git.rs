@@ -0,0 +1,90 @@
+//! Versioning the data directory with `rw git`
+
+mod common;
+
+use std::{
+    fs,
+    path::Path,
+    process::{Command, Output},
+};
+
+use tempfile::TempDir;
+
+fn rw(exe: &Path, dir: &TempDir, args: &[&str]) -> Output {
+    let output = common::command_at(exe, dir)
+        .args(args)
+        .env("GIT_AUTHOR_NAME", "Test")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "Test")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .output()
+        .expect("failed to run rw");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+fn git(dir: &TempDir, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir.path())
+        .args(args)
+        .output()
+        .expect("failed to run git");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn init_and_commit_on_save() {
+    // A quote in the path must survive the shell git runs the driver with
+    let bin = TempDir::new().unwrap();
+    let exe = bin.path().join("it's").join("rw");
+    fs::create_dir_all(exe.parent().unwrap()).unwrap();
+    fs::copy(env!("CARGO_BIN_EXE_rw"), &exe).unwrap();
+
+    let dir = TempDir::new().unwrap();
+    rw(&exe, &dir, &["git", "init"]);
+    assert!(dir.path().join(".git").exists());
+
+    let driver = git(&dir, &["config", "merge.rustwarrior.driver"]);
+    let words = shlex::split(driver.trim()).unwrap();
+    assert_eq!(Path::new(&words[0]), exe);
+    assert_eq!(words[1..], ["git", "merge-driver", "%O", "%A", "%B"]);
+
+    rw(&exe, &dir, &["add", "Water plants"]);
+    let log = git(&dir, &["log", "--format=%s"]);
+    assert_eq!(log.lines().next(), Some("rw add Water plants"));
+    assert!(git(&dir, &["status", "--porcelain"]).is_empty());
+}
+
+#[test]
+fn failed_commit_is_a_warning() {
+    let exe = Path::new(env!("CARGO_BIN_EXE_rw"));
+    let dir = TempDir::new().unwrap();
+    // git refuses to commit with an empty author name
+    let rw = |args: &[&str]| {
+        common::command_at(exe, &dir)
+            .args(args)
+            .env("GIT_AUTHOR_NAME", "")
+            .env("GIT_COMMITTER_NAME", "")
+            .output()
+            .expect("failed to run rw")
+    };
+
+    for args in [&["git", "init"][..], &["add", "Water plants"]] {
+        let output = rw(args);
+        assert!(output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(
+            stderr.contains("warning: failed to commit changes"),
+            "{stderr}"
+        );
+    }
+
+    let tasks = fs::read_to_string(dir.path().join("open_tasks.jsonl")).unwrap();
+    assert!(tasks.contains("Water plants"));
+    assert!(git(&dir, &["log"]).is_empty());
+}
@@ -0,0 +1,80 @@
+//! Listing tasks matching a filter with `rw list <filter>`
+
+mod common;
+
+use common::rw;
+use serde_json::Value;
+use tempfile::TempDir;
+
+fn listed(dir: &TempDir, filter: &[&str]) -> Vec<String> {
+    let output = rw(dir, &[&["--output", "json", "list"], filter].concat());
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let tasks: Value = serde_json::from_slice(&output.stdout).expect("output is not valid JSON");
+    tasks
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| task["description"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn filter_expressions() {
+    let dir = TempDir::new().unwrap();
+    rw(&dir, &["add", "Write report", "-p", "1"]);
+    rw(&dir, &["add", "Call mum"]);
+    rw(&dir, &["add", "Pay rent", "-p", "3"]);
+    rw(&dir, &["modify", "0,2", "+work"]);
+    rw(&dir, &["modify", "1", "project:home"]);
+    rw(&dir, &["done", "2"]);
+
+    assert_eq!(listed(&dir, &["-work"]), ["Call mum"]);
+    assert_eq!(
+        listed(&dir, &["+work", "and", "status:open"]),
+        ["Write report"]
+    );
+    assert_eq!(
+        listed(&dir, &["(priority.above:3 or project:home) and not /rent/"]),
+        ["Write report", "Call mum"]
+    );
+    assert_eq!(listed(&dir, &["status:completed"]), ["Pay rent"]);
+
+    let invalid = rw(&dir, &["--output", "json", "list", "priority.soon:1"]);
+    assert!(!invalid.status.success());
+    let error: Value = serde_json::from_slice(&invalid.stderr).expect("error is not valid JSON");
+    assert_eq!(error["error"]["code"], "usage");
+}
+
+#[test]
+fn sort_and_columns() {
+    let dir = TempDir::new().unwrap();
+    rw(&dir, &["add", "Write report", "-p", "2"]);
+    rw(&dir, &["add", "Call mum"]);
+    rw(&dir, &["add", "Pay rent", "-p", "1"]);
+    rw(&dir, &["add", "Water plants", "-p", "2"]);
+
+    assert_eq!(
+        listed(&dir, &["--sort", "priority-,created+"]),
+        ["Pay rent", "Write report", "Water plants", "Call mum"]
+    );
+    assert_eq!(
+        listed(&dir, &["--sort", "description-", "priority.any:"]),
+        ["Write report", "Water plants", "Pay rent"]
+    );
+
+    let table = rw(&dir, &["list", "--columns", "id,description,project,age"]);
+    assert!(table.status.success());
+    let table = String::from_utf8(table.stdout).unwrap();
+    assert!(table.contains("Description"));
+    assert!(table.contains("Age"));
+    assert!(!table.contains("Project"), "empty column shown:\n{table}");
+
+    assert_eq!(
+        rw(&dir, &["list", "--sort", "urgency"]).status.code(),
+        Some(2)
+    );
+}
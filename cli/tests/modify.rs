@@ -0,0 +1,84 @@
+//! Changing tasks with `rw modify`
+
+mod common;
+
+use std::fs;
+
+use common::{command, error, json, rw};
+use serde_json::json;
+use tempfile::TempDir;
+
+#[test]
+fn modify_by_id() {
+    let dir = TempDir::new().unwrap();
+    rw(&dir, &["add", "old words", "-p", "4"]);
+
+    let modified = rw(
+        &dir,
+        &[
+            "--output",
+            "json",
+            "modify",
+            "0",
+            "new",
+            "words",
+            "priority:1",
+            "+urgent",
+        ],
+    );
+    assert!(modified.status.success());
+    let modified = json(&modified);
+    assert_eq!(
+        modified[0]["changes"],
+        json!([
+            {"field": "description", "before": "old words", "after": "new words"},
+            {"field": "priority", "before": "4", "after": "1"},
+            {"field": "tags", "before": null, "after": "urgent"},
+        ])
+    );
+    assert_eq!(modified[0]["task"]["tags"], json!(["urgent"]));
+}
+
+#[test]
+fn confirm_bulk_changes() {
+    let dir = TempDir::new().unwrap();
+    for description in ["one", "two", "three"] {
+        rw(&dir, &["add", description]);
+    }
+    let modify = |args: &[&str]| {
+        command(&dir)
+            .env("RUSTWARRIOR_BULK", "2")
+            .args(args)
+            .output()
+            .expect("failed to run rw")
+    };
+
+    let unconfirmed = modify(&["--output", "json", "modify", "0-2", "+work"]);
+    assert_eq!(error(&unconfirmed)["error"]["code"], json!("usage"));
+
+    let small = modify(&["--output", "json", "modify", "0,1", "+work"]);
+    assert_eq!(json(&small).as_array().unwrap().len(), 2);
+
+    let confirmed = modify(&["modify", "--yes", "0-2", "-work", "+office"]);
+    assert!(confirmed.status.success());
+    let listed = json(&rw(&dir, &["list", "--output", "json"]));
+    for task in listed.as_array().unwrap() {
+        assert_eq!(task["tags"], json!(["office"]));
+    }
+}
+
+#[test]
+fn bulk_threshold_from_config_file() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("config.toml"), "bulk = 1\n").unwrap();
+    for description in ["one", "two"] {
+        rw(&dir, &["add", description]);
+    }
+
+    assert!(!rw(&dir, &["modify", "0,1", "+work"]).status.success());
+    assert!(
+        rw(&dir, &["modify", "--bulk", "2", "0,1", "+work"])
+            .status
+            .success()
+    );
+}
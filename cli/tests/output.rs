@@ -0,0 +1,57 @@
+//! Machine-readable output with `--output json|jsonl`
+
+mod common;
+
+use common::{error, json, rw};
+use serde_json::{Value, json};
+use tempfile::TempDir;
+
+#[test]
+fn tasks_as_json() {
+    let dir = TempDir::new().unwrap();
+
+    let added = rw(&dir, &["--output", "json", "add", "first task", "-p", "2"]);
+    assert!(added.status.success());
+    let added = json(&added);
+    assert_eq!(added["id"], json!(0));
+    assert_eq!(added["description"], json!("first task"));
+    assert_eq!(added["priority"], json!(2));
+    assert_eq!(added["status"], json!("pending"));
+    assert_eq!(added["tags"], json!([]));
+
+    rw(&dir, &["add", "second task"]);
+
+    let listed = rw(&dir, &["list", "--output", "json"]);
+    let listed = json(&listed);
+    assert_eq!(listed.as_array().unwrap().len(), 2);
+    assert_eq!(listed[0], added);
+    assert!(listed[1].get("priority").is_none());
+
+    let lines = rw(&dir, &["list", "--output=jsonl"]);
+    let lines: Vec<Value> = String::from_utf8(lines.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines, listed.as_array().unwrap().clone());
+
+    let info = rw(&dir, &["info", "0", "--output", "json"]);
+    assert_eq!(json(&info), added);
+}
+
+#[test]
+fn errors_as_json() {
+    let dir = TempDir::new().unwrap();
+
+    let missing = rw(&dir, &["--output", "json", "info", "7"]);
+    assert!(!missing.status.success());
+    assert!(missing.stdout.is_empty());
+    assert_eq!(
+        error(&missing),
+        json!({"error": {"code": "not_found", "message": "task 7 not found"}})
+    );
+
+    let usage = rw(&dir, &["--output", "jsonl", "info", "not-a-number"]);
+    assert_eq!(usage.status.code(), Some(2));
+    assert_eq!(error(&usage)["error"]["code"], json!("usage"));
+}
@@ -0,0 +1,57 @@
+//! Running named reports, like `rw next`, and defining them in the config file
+
+mod common;
+
+use std::fs;
+
+use common::rw;
+use serde_json::Value;
+use tempfile::TempDir;
+
+fn report(dir: &TempDir, args: &[&str]) -> Vec<String> {
+    let output = rw(dir, &[&["--output", "json"], args].concat());
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let tasks: Value = serde_json::from_slice(&output.stdout).expect("output is not valid JSON");
+    tasks
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| task["description"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn builtin_and_configured_reports() {
+    let dir = TempDir::new().unwrap();
+    rw(&dir, &["add", "Call mum"]);
+    rw(&dir, &["add", "Write report", "-p", "1"]);
+    rw(&dir, &["add", "Pay rent", "-p", "2"]);
+    rw(&dir, &["modify", "1", "+work"]);
+    rw(&dir, &["done", "2"]);
+
+    assert_eq!(report(&dir, &["next"]), ["Write report", "Call mum"]);
+    assert_eq!(report(&dir, &["next", "-work"]), ["Call mum"]);
+    assert_eq!(report(&dir, &["completed"]), ["Pay rent"]);
+    assert_eq!(
+        report(&dir, &["all"]),
+        ["Call mum", "Write report", "Pay rent"]
+    );
+    assert!(report(&dir, &["overdue"]).is_empty());
+
+    fs::write(
+        dir.path().join("config.toml"),
+        "[reports.next]\nlimit = 1\n\n[reports.work]\nfilter = \"+work\"\n",
+    )
+    .unwrap();
+    assert_eq!(report(&dir, &["next"]), ["Write report"]);
+    assert_eq!(report(&dir, &["work"]), ["Write report"]);
+
+    let unknown = rw(&dir, &["--output", "json", "nonsense"]);
+    assert_eq!(unknown.status.code(), Some(1));
+    let error: Value = serde_json::from_slice(&unknown.stderr).expect("error is not valid JSON");
+    assert_eq!(error["error"]["code"], "usage");
+}
@@ -0,0 +1,168 @@
+//! Two replicas syncing through a local sync server
+
+mod common;
+
+use std::{
+    path::{Path, PathBuf},
+    process::Output,
+    thread,
+};
+
+use rustwarrior_core::{OpenTask, Priority, Store, paths};
+use rustwarrior_sync_server::Server;
+use tempfile::TempDir;
+
+struct Replica {
+    dir: TempDir,
+}
+
+impl Replica {
+    fn new() -> Self {
+        Self {
+            dir: TempDir::new().expect("unable to create temporary directory"),
+        }
+    }
+
+    fn command(&self, args: &[&str]) -> Output {
+        common::command(&self.dir)
+            .args(args)
+            .output()
+            .expect("failed to run rw")
+    }
+
+    fn rw(&self, args: &[&str]) -> String {
+        let output = self.command(args);
+        assert!(
+            output.status.success(),
+            "rw {args:?} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    fn tasks_file(&self) -> PathBuf {
+        paths::get_tasks_file(Some(self.dir.path())).unwrap()
+    }
+
+    fn store(&self) -> Store {
+        Store::load_from_path(self.tasks_file()).unwrap()
+    }
+
+    fn edit(&self, f: impl FnOnce(&mut Store)) {
+        let mut store = self.store();
+        f(&mut store);
+        store.save_to_path(self.tasks_file()).unwrap();
+    }
+}
+
+fn start_server(dir: &Path) -> String {
+    start_server_at(&dir.join("server.json"))
+}
+
+fn start_server_at(state_file: &Path) -> String {
+    let server = Server::bind("127.0.0.1:0", state_file).unwrap();
+    let addr = server.local_addr().unwrap();
+    thread::spawn(move || server.run());
+    format!("http://{addr}")
+}
+
+#[test]
+fn two_replicas_converge() {
+    let server_dir = TempDir::new().unwrap();
+    let url = start_server(server_dir.path());
+
+    let laptop = Replica::new();
+    let desktop = Replica::new();
+
+    laptop.rw(&["add", "write report"]);
+    laptop.rw(&["sync", "--server", &url]);
+
+    let output = desktop.rw(&["sync", "--server", &url]);
+    assert!(output.contains("1 added"), "{output}");
+    assert_eq!(
+        desktop.store().get(0).unwrap().description(),
+        "write report"
+    );
+
+    // Concurrent edits to different fields of the same task, plus an
+    // addition on each side
+    laptop.edit(|store| store.get_mut(0).unwrap().task_mut().mark_completed());
+    laptop.rw(&["add", "laptop task"]);
+    desktop.edit(|store| {
+        store
+            .get_mut(0)
+            .unwrap()
+            .task_mut()
+            .set_priority(Some(Priority::One));
+    });
+    desktop.rw(&["add", "desktop task"]);
+
+    laptop.rw(&["sync"]);
+    desktop.rw(&["sync"]);
+    laptop.rw(&["sync"]);
+
+    let laptop_store = laptop.store();
+    let desktop_store = desktop.store();
+
+    assert_eq!(laptop_store.len(), 3);
+    assert_eq!(desktop_store.len(), 3);
+
+    for store in [&laptop_store, &desktop_store] {
+        let report = store
+            .iter()
+            .find(|task| task.description() == "write report")
+            .unwrap();
+        assert!(report.is_completed());
+        assert_eq!(report.priority(), Some(Priority::One));
+    }
+
+    let mut laptop_tasks: Vec<_> = laptop_store.iter().map(OpenTask::task).collect();
+    let mut desktop_tasks: Vec<_> = desktop_store.iter().map(OpenTask::task).collect();
+    laptop_tasks.sort_by_key(|task| task.uuid());
+    desktop_tasks.sort_by_key(|task| task.uuid());
+    assert_eq!(laptop_tasks, desktop_tasks);
+}
+
+#[test]
+fn deletions_propagate() {
+    let server_dir = TempDir::new().unwrap();
+    let url = start_server(server_dir.path());
+
+    let laptop = Replica::new();
+    let desktop = Replica::new();
+
+    laptop.rw(&["add", "short-lived task"]);
+    laptop.rw(&["add", "long-lived task"]);
+    laptop.rw(&["sync", "--server", &url]);
+    desktop.rw(&["sync", "--server", &url]);
+
+    desktop.edit(|store| {
+        store.delete(0);
+    });
+    desktop.rw(&["sync"]);
+
+    let output = laptop.rw(&["sync"]);
+    assert!(output.contains("1 removed"), "{output}");
+
+    let store = laptop.store();
+    assert_eq!(store.len(), 1);
+    assert_eq!(store.get(1).unwrap().description(), "long-lived task");
+}
+
+#[test]
+fn server_survives_failed_save() {
+    let server_dir = TempDir::new().unwrap();
+    // The state file can't be written, as its directory doesn't exist
+    let url = start_server_at(&server_dir.path().join("missing").join("server.json"));
+
+    let laptop = Replica::new();
+    laptop.rw(&["add", "write report"]);
+
+    // The second push reaching the server shows it is still running
+    for _ in 0..2 {
+        let output = laptop.command(&["sync", "--server", &url]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("status code 500"), "{stderr}");
+    }
+}
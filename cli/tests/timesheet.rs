@@ -0,0 +1,44 @@
+//! Reporting tracked time with `rw timesheet`
+
+mod common;
+
+use common::rw;
+use tempfile::TempDir;
+
+const TASKS: &str = r#"{"id":0,"uuid":"ee00fce2-f349-42b1-969e-17d4c6c612f5","created":"2024-01-01T08:00:00Z","description":"invoice","project":"acme","intervals":[{"start":"2024-01-01T09:00:00Z","end":"2024-01-01T10:10:00Z"}]}
+{"id":1,"uuid":"0b8e1a52-3c1d-4f0e-9d7a-5a4f0b6d2c11","created":"2024-01-01T08:00:00Z","description":"deploy","project":"acme","intervals":[{"start":"2024-01-03T09:00:00Z","end":"2024-01-03T09:25:00Z"}]}
+{"id":2,"uuid":"6f1c2d3e-4b5a-4c6d-8e7f-9a0b1c2d3e4f","created":"2024-01-01T08:00:00Z","description":"read","intervals":[{"start":"2024-02-01T09:00:00Z","end":"2024-02-01T11:00:00Z"}]}
+"#;
+
+#[test]
+fn csv_by_project() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(dir.path().join("open_tasks.jsonl"), TASKS).unwrap();
+
+    let output = rw(
+        &dir,
+        &[
+            "timesheet",
+            "--from",
+            "2024-01-01",
+            "--to",
+            "2024-01-31",
+            "--group-by",
+            "project",
+            "--round",
+            "15",
+            "--csv",
+        ],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "project,hours,tasks\nacme,1.75,2\n"
+    );
+
+    let output = rw(&dir, &["timesheet", "--from", "2024-02-01"]);
+    let table = String::from_utf8(output.stdout).unwrap();
+    assert!(table.contains("2024-02-01"));
+    assert!(table.contains("2h 00m"));
+    assert!(!table.contains("2024-01-01"));
+}
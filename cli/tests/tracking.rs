@@ -0,0 +1,38 @@
+//! Tracking work on tasks with `rw start` and `rw stop`
+
+mod common;
+
+use common::{json, rw};
+use tempfile::TempDir;
+
+#[test]
+fn start_and_stop() {
+    let dir = TempDir::new().unwrap();
+    for description in ["write report", "review code"] {
+        rw(&dir, &["add", description]);
+    }
+
+    let started = json(&rw(&dir, &["--output", "json", "start", "0"]));
+    assert_eq!(started["task"]["status"], "started");
+    json(&rw(&dir, &["--output", "json", "start", "1"]));
+
+    let list = String::from_utf8(rw(&dir, &["list"]).stdout).unwrap();
+    assert!(list.contains("Tracked"));
+    assert!(list.contains("Active: task 0 'write report'"));
+    assert!(list.contains("Active: task 1 'review code'"));
+
+    let started = json(&rw(
+        &dir,
+        &["--output", "json", "start", "0", "--exclusive"],
+    ));
+    assert_eq!(started["stopped"][0]["id"], 1);
+    assert!(started["stopped"][0]["tracked_seconds"].is_number());
+
+    assert!(!rw(&dir, &["stop", "1"]).status.success());
+    let stopped = json(&rw(&dir, &["--output", "json", "stop"]));
+    assert_eq!(stopped.as_array().unwrap().len(), 1);
+    assert_eq!(stopped[0]["status"], "pending");
+
+    rw(&dir, &["done", "1"]);
+    assert!(!rw(&dir, &["start", "1"]).status.success());
+}
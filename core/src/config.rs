@@ -0,0 +1,694 @@
+//! The config file, `rustwarrior/config.toml` in the platform config
+//! directory
+//!
+//! The `RUSTWARRIOR_CONFIG` environment variable names another file. A
+//! missing file is the same as an empty one.
+//!
+//! ```toml
+//! data_dir = "~/Documents/tasks"
+//! default_priority = 3
+//! date_format = "%d/%m/%Y"
+//! color = "never"
+//! context = "work"
+//! backup_count = 50
+//! backup_days = "none"
+//! bulk = 10
+//! exclusive = true
+//! timesheet_round = 15
+//!
+//! [contexts]
+//! work = "+work or project:office"
+//!
+//! [reports.next]
+//! limit = 5
+//!
+//! [reports.work]
+//! description = "Work in progress"
+//! filter = "+work status:open"
+//! sort = "priority-"
+//! columns = ["id", "priority", "description"]
+//! ```
+//!
+//! Settings are layered: the defaults, then the file, then environment
+//! variables (see [`Setting::env`]). Command-line flags override them all.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use chrono::{
+    Duration,
+    format::{Item, StrftimeItems},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Priority,
+    context::{self, Context},
+    paths,
+    report::{self, Definition, Reports},
+    store::backup::Retention,
+};
+
+/// The settings read from the config file and the environment
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Where tasks are stored, instead of the platform data directory. A
+    /// leading `~` is the home directory
+    #[serde(default)]
+    pub data_dir: Option<PathBuf>,
+    /// The priority of new tasks which aren't given one
+    #[serde(default)]
+    pub default_priority: Option<Priority>,
+    /// How dates are shown in tables, as a `strftime` format
+    #[serde(default)]
+    pub date_format: Option<String>,
+    /// When to colour tables
+    #[serde(default)]
+    pub color: Option<Color>,
+    /// The name of the active context, if any
+    #[serde(default)]
+    pub context: Option<String>,
+    /// How many backups of the tasks file to keep
+    #[serde(default)]
+    pub backup_count: Option<Limit>,
+    /// How many days to keep backups of the tasks file for
+    #[serde(default)]
+    pub backup_days: Option<Limit>,
+    /// How many tasks `rw modify` changes before asking for confirmation
+    #[serde(default)]
+    pub bulk: Option<usize>,
+    /// Whether starting a task stops any other task in progress
+    #[serde(default)]
+    pub exclusive: Option<bool>,
+    /// The number of minutes timesheet groups are rounded up to a multiple
+    /// of, or 0 not to round
+    #[serde(default)]
+    pub timesheet_round: Option<u32>,
+    /// Context filters, by name. See [`context`](crate::context)
+    #[serde(default)]
+    pub contexts: BTreeMap<String, String>,
+    /// Report definitions, by name. See [`report`]
+    #[serde(default)]
+    pub reports: BTreeMap<String, Definition>,
+}
+
+impl Config {
+    /// The number of tasks `rw modify` changes without asking, if not
+    /// configured
+    pub const DEFAULT_BULK: usize = 3;
+    /// The date format used if none is configured
+    pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+    /// Read the config file at the default location, and apply the
+    /// environment
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or parsed, or an
+    /// environment variable is invalid.
+    pub fn load_default() -> Result<Self, Error> {
+        Self::load(&paths::get_config_file()?)?.with_env()
+    }
+
+    /// Read a config file, which may not exist
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        text.parse().map_err(|source| Error::File {
+            path: path.to_path_buf(),
+            source: Box::new(source),
+        })
+    }
+
+    /// Override settings with the environment variables which are set
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a variable has an invalid value.
+    pub fn with_env(mut self) -> Result<Self, Error> {
+        for setting in Setting::ALL {
+            let Some(value) = std::env::var(setting.env())
+                .ok()
+                .filter(|value| !value.is_empty())
+            else {
+                continue;
+            };
+            let invalid = |expected| Error::Env {
+                name: setting.env(),
+                value: value.clone(),
+                expected,
+            };
+            match setting {
+                Setting::DataDir => self.data_dir = Some(PathBuf::from(&value)),
+                Setting::DefaultPriority => {
+                    self.default_priority =
+                        Some(value.parse().map_err(|_| invalid("Expected 1-4"))?);
+                }
+                Setting::DateFormat => {
+                    check_date_format(&value)?;
+                    self.date_format = Some(value);
+                }
+                Setting::Color => {
+                    self.color = Some(
+                        value
+                            .parse()
+                            .map_err(|_| invalid("Expected auto, always or never"))?,
+                    );
+                }
+                Setting::Context => {
+                    self.context = Some(value);
+                }
+                Setting::BackupCount | Setting::BackupDays => {
+                    let limit = value
+                        .parse()
+                        .map_err(|_| invalid("Expected a whole number or none"))?;
+                    if setting == Setting::BackupCount {
+                        self.backup_count = Some(limit);
+                    } else {
+                        self.backup_days = Some(limit);
+                    }
+                }
+                Setting::Bulk => {
+                    self.bulk = Some(
+                        value
+                            .parse()
+                            .map_err(|_| invalid("Expected a whole number"))?,
+                    );
+                }
+                Setting::Exclusive => {
+                    self.exclusive =
+                        Some(parse_bool(&value).ok_or_else(|| invalid("Expected true or false"))?);
+                }
+                Setting::TimesheetRound => {
+                    self.timesheet_round = Some(
+                        value
+                            .parse()
+                            .map_err(|_| invalid("Expected a whole number of minutes"))?,
+                    );
+                }
+            }
+        }
+        self.context()?;
+        Ok(self)
+    }
+
+    /// Where tasks are stored, if configured, with `~` expanded
+    #[must_use]
+    pub fn data_dir(&self) -> Option<PathBuf> {
+        let dir = self.data_dir.as_ref()?;
+        match (dir.strip_prefix("~"), dirs::home_dir()) {
+            (Ok(rest), Some(home)) => Some(home.join(rest)),
+            _ => Some(dir.clone()),
+        }
+    }
+
+    /// How dates are shown in tables
+    #[must_use]
+    pub fn date_format(&self) -> &str {
+        self.date_format
+            .as_deref()
+            .unwrap_or(Self::DEFAULT_DATE_FORMAT)
+    }
+
+    /// When to colour tables
+    #[must_use]
+    pub fn color(&self) -> Color {
+        self.color.unwrap_or_default()
+    }
+
+    /// Which backups of the tasks file to keep
+    #[must_use]
+    pub fn retention(&self) -> Retention {
+        let defaults = Retention::default();
+        Retention {
+            count: match self.backup_count {
+                None => defaults.count,
+                Some(Limit::Unlimited) => None,
+                Some(Limit::At(count)) => Some(count as usize),
+            },
+            age: match self.backup_days {
+                None => defaults.age,
+                Some(Limit::Unlimited) => None,
+                Some(Limit::At(days)) => Some(Duration::days(i64::from(days))),
+            },
+        }
+    }
+
+    /// How many tasks `rw modify` changes before asking for confirmation
+    #[must_use]
+    pub fn bulk(&self) -> usize {
+        self.bulk.unwrap_or(Self::DEFAULT_BULK)
+    }
+
+    /// Whether starting a task stops any other task in progress
+    #[must_use]
+    pub fn exclusive(&self) -> bool {
+        self.exclusive.unwrap_or_default()
+    }
+
+    /// The number of minutes timesheet groups are rounded up to a multiple
+    /// of, or 0 not to round
+    #[must_use]
+    pub fn timesheet_round(&self) -> u32 {
+        self.timesheet_round.unwrap_or_default()
+    }
+
+    /// The value of a setting, if it is set
+    #[must_use]
+    pub fn value(&self, setting: Setting) -> Option<String> {
+        match setting {
+            Setting::DataDir => self.data_dir.as_ref().map(|dir| dir.display().to_string()),
+            Setting::DefaultPriority => self.default_priority.map(|priority| priority.to_string()),
+            Setting::DateFormat => self.date_format.clone(),
+            Setting::Color => self.color.map(|color| color.to_string()),
+            Setting::Context => self.context.clone(),
+            Setting::BackupCount => self.backup_count.map(|limit| limit.to_string()),
+            Setting::BackupDays => self.backup_days.map(|limit| limit.to_string()),
+            Setting::Bulk => self.bulk.map(|bulk| bulk.to_string()),
+            Setting::Exclusive => self.exclusive.map(|exclusive| exclusive.to_string()),
+            Setting::TimesheetRound => self.timesheet_round.map(|round| round.to_string()),
+        }
+    }
+
+    /// The active context, unless there is none or it is `none`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the context isn't defined or its filter is
+    /// invalid.
+    pub fn context(&self) -> Result<Option<Context>, Error> {
+        match self.context.as_deref() {
+            None | Some(context::NONE) => Ok(None),
+            Some(name) => self.find_context(name).map(Some),
+        }
+    }
+
+    /// The context with a name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the context isn't defined or its filter is
+    /// invalid.
+    pub fn find_context(&self, name: &str) -> Result<Context, Error> {
+        let filter = self
+            .contexts
+            .get(name)
+            .ok_or_else(|| Error::UnknownContext(name.to_string()))?;
+        Ok(Context::new(name, filter)?)
+    }
+
+    /// Every context, in order of name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a context's filter is invalid.
+    pub fn contexts(&self) -> Result<Vec<Context>, Error> {
+        self.contexts
+            .iter()
+            .map(|(name, filter)| Ok(Context::new(name, filter)?))
+            .collect()
+    }
+
+    /// The built-in reports, with the reports defined in the file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a report has an invalid filter or sort order.
+    pub fn reports(&self) -> Result<Reports, report::Error> {
+        Reports::new(&self.reports)
+    }
+}
+
+impl FromStr for Config {
+    type Err = Error;
+
+    /// Parse and check the contents of a config file
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let config: Self = toml::from_str(s)?;
+        if let Some(format) = &config.date_format {
+            check_date_format(format)?;
+        }
+        config.reports()?;
+        if let Some(name) = config
+            .contexts
+            .keys()
+            .find(|name| context::RESERVED.contains(&name.as_str()))
+        {
+            return Err(Error::ReservedContext(name.clone()));
+        }
+        config.contexts()?;
+        config.context()?;
+        Ok(config)
+    }
+}
+
+/// Read a yes or no from an environment variable
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+fn check_date_format(format: &str) -> Result<(), Error> {
+    if StrftimeItems::new(format).any(|item| item == Item::Error) {
+        return Err(Error::DateFormat(format.to_string()));
+    }
+    Ok(())
+}
+
+/// A setting other than the reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    /// [`Config::data_dir`]
+    DataDir,
+    /// [`Config::default_priority`]
+    DefaultPriority,
+    /// [`Config::date_format`]
+    DateFormat,
+    /// [`Config::color`]
+    Color,
+    /// [`Config::context`]
+    Context,
+    /// [`Config::backup_count`]
+    BackupCount,
+    /// [`Config::backup_days`]
+    BackupDays,
+    /// [`Config::bulk`]
+    Bulk,
+    /// [`Config::exclusive`]
+    Exclusive,
+    /// [`Config::timesheet_round`]
+    TimesheetRound,
+}
+
+impl Setting {
+    /// Every setting, in the order they are shown
+    pub const ALL: [Self; 10] = [
+        Self::DataDir,
+        Self::DefaultPriority,
+        Self::DateFormat,
+        Self::Color,
+        Self::Context,
+        Self::BackupCount,
+        Self::BackupDays,
+        Self::Bulk,
+        Self::Exclusive,
+        Self::TimesheetRound,
+    ];
+
+    /// The key of the setting in the config file
+    #[must_use]
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::DataDir => "data_dir",
+            Self::DefaultPriority => "default_priority",
+            Self::DateFormat => "date_format",
+            Self::Color => "color",
+            Self::Context => "context",
+            Self::BackupCount => "backup_count",
+            Self::BackupDays => "backup_days",
+            Self::Bulk => "bulk",
+            Self::Exclusive => "exclusive",
+            Self::TimesheetRound => "timesheet_round",
+        }
+    }
+
+    /// The environment variable which overrides the setting
+    #[must_use]
+    pub const fn env(self) -> &'static str {
+        match self {
+            Self::DataDir => "RUSTWARRIOR_DATA_DIR",
+            Self::DefaultPriority => "RUSTWARRIOR_DEFAULT_PRIORITY",
+            Self::DateFormat => "RUSTWARRIOR_DATE_FORMAT",
+            Self::Color => "RUSTWARRIOR_COLOR",
+            Self::Context => "RUSTWARRIOR_CONTEXT",
+            Self::BackupCount => "RUSTWARRIOR_BACKUP_COUNT",
+            Self::BackupDays => "RUSTWARRIOR_BACKUP_DAYS",
+            Self::Bulk => "RUSTWARRIOR_BULK",
+            Self::Exclusive => "RUSTWARRIOR_EXCLUSIVE",
+            Self::TimesheetRound => "RUSTWARRIOR_TIMESHEET_ROUND",
+        }
+    }
+}
+
+impl FromStr for Setting {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|setting| setting.key() == s)
+            .ok_or(())
+    }
+}
+
+/// A limit which can be lifted, written as a whole number or `none`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "LimitValue")]
+pub enum Limit {
+    /// No limit
+    Unlimited,
+    /// At most this many
+    At(u32),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LimitValue {
+    Number(u32),
+    Text(String),
+}
+
+impl TryFrom<LimitValue> for Limit {
+    type Error = Error;
+
+    fn try_from(value: LimitValue) -> Result<Self, Self::Error> {
+        match value {
+            LimitValue::Number(limit) => Ok(Self::At(limit)),
+            LimitValue::Text(text) => text.parse(),
+        }
+    }
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unlimited => f.write_str("none"),
+            Self::At(limit) => write!(f, "{limit}"),
+        }
+    }
+}
+
+impl FromStr for Limit {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().eq_ignore_ascii_case("none") {
+            return Ok(Self::Unlimited);
+        }
+        s.trim()
+            .parse()
+            .map(Self::At)
+            .map_err(|_| Error::Limit(s.to_string()))
+    }
+}
+
+/// When to colour tables
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Color {
+    /// When printing to a terminal, unless `NO_COLOR` is set
+    #[default]
+    Auto,
+    /// Even when printing to a file or a pipe
+    Always,
+    /// Never
+    Never,
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Auto => "auto",
+            Self::Always => "always",
+            Self::Never => "never",
+        })
+    }
+}
+
+impl FromStr for Color {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            _ => Err(Error::Color(s.to_string())),
+        }
+    }
+}
+
+/// Error type for reading the [`Config`]
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The file couldn't be read
+    #[error("failed to read the config file: {0}")]
+    Io(#[from] io::Error),
+    /// The file isn't valid
+    #[error("invalid config file {}: {source}", path.display())]
+    File {
+        /// The config file
+        path: PathBuf,
+        /// What is wrong with it
+        source: Box<Self>,
+    },
+    /// The text isn't valid TOML, or has unknown or mistyped settings
+    #[error("{0}")]
+    Syntax(#[from] toml::de::Error),
+    /// A date format has an invalid specifier
+    #[error("invalid date format '{0}'")]
+    DateFormat(String),
+    /// A colour choice isn't recognised
+    #[error("invalid color '{0}'. Expected auto, always or never")]
+    Color(String),
+    /// A limit isn't a whole number or `none`
+    #[error("invalid limit '{0}'. Expected a whole number or none")]
+    Limit(String),
+    /// A report is invalid
+    #[error(transparent)]
+    Report(#[from] report::Error),
+    /// A context is invalid
+    #[error(transparent)]
+    Context(#[from] context::Error),
+    /// The active context isn't defined
+    #[error("unknown context '{0}'")]
+    UnknownContext(String),
+    /// A context has a name which has another meaning
+    #[error("a context can't be called '{0}'")]
+    ReservedContext(String),
+    /// An environment variable has an invalid value
+    #[error("invalid {name} '{value}'. {expected}")]
+    Env {
+        /// The environment variable
+        name: &'static str,
+        /// Its value
+        value: String,
+        /// What was expected
+        expected: &'static str,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use chrono::Duration;
+    use test_case::test_case;
+
+    use super::{Color, Config, Error, Setting};
+    use crate::{Priority, store::backup::Retention};
+
+    #[test]
+    fn load() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        assert_eq!(Config::load(&path).unwrap(), Config::default());
+
+        fs::write(
+            &path,
+            "# My settings\ndefault_priority = 2\ncolor = \"never\"\n\n[reports.work]\nfilter = \
+             \"+work\"\nlimit = 3\n",
+        )
+        .unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.default_priority, Some(Priority::Two));
+        assert_eq!(config.color(), Color::Never);
+        assert_eq!(config.date_format(), Config::DEFAULT_DATE_FORMAT);
+        assert_eq!(config.reports["work"].filter.as_deref(), Some("+work"));
+        assert_eq!(
+            config.reports().unwrap().get("work").unwrap().limit(),
+            Some(3)
+        );
+
+        fs::write(&path, "[reports.work]\nfilters = \"+work\"\n").unwrap();
+        assert!(matches!(Config::load(&path), Err(Error::File { .. })));
+    }
+
+    #[test_case("default_priority = 5" ; "priority")]
+    #[test_case("colour = \"always\"" ; "unknown setting")]
+    #[test_case("color = \"sometimes\"" ; "color")]
+    #[test_case("date_format = \"%Q\"" ; "date format")]
+    #[test_case("[reports.next]\nsort = \"urgency\"" ; "report")]
+    #[test_case("context = \"work\"" ; "unknown context")]
+    #[test_case("[contexts]\nwork = \"(+work\"" ; "context filter")]
+    #[test_case("[contexts]\nnone = \"+work\"" ; "reserved context")]
+    #[test_case("backup_count = \"all\"" ; "backup count")]
+    #[test_case("backup_days = -1" ; "backup days")]
+    #[test_case("exclusive = \"yes\"" ; "exclusive")]
+    fn invalid(text: &str) {
+        assert!(text.parse::<Config>().is_err());
+    }
+
+    #[test]
+    fn context() {
+        let config: Config = "context = \"work\"\n[contexts]\nwork = \"+work\"\nhome = \"+home\""
+            .parse()
+            .unwrap();
+        assert_eq!(config.context().unwrap().unwrap().filter_text(), "+work");
+        assert_eq!(config.contexts().unwrap().len(), 2);
+
+        let off = Config {
+            context: Some("none".to_string()),
+            ..config
+        };
+        assert!(off.context().unwrap().is_none());
+    }
+
+    #[test]
+    fn data_dir() {
+        let config: Config = "data_dir = \"~/tasks\"".parse().unwrap();
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(config.data_dir(), Some(home.join("tasks")));
+    }
+
+    #[test]
+    fn thresholds() {
+        let config = Config::default();
+        assert_eq!(config.retention(), Retention::default());
+        assert_eq!(config.bulk(), Config::DEFAULT_BULK);
+        assert!(!config.exclusive());
+        assert_eq!(config.timesheet_round(), 0);
+
+        let config: Config = "backup_count = \"none\"\nbackup_days = 7\nbulk = 10\nexclusive = \
+                              true\ntimesheet_round = 15"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            config.retention(),
+            Retention {
+                count: None,
+                age: Some(Duration::days(7)),
+            }
+        );
+        assert_eq!(config.bulk(), 10);
+        assert!(config.exclusive());
+        assert_eq!(config.timesheet_round(), 15);
+        assert_eq!(config.value(Setting::BackupCount).as_deref(), Some("none"));
+        assert_eq!(config.value(Setting::BackupDays).as_deref(), Some("7"));
+    }
+}
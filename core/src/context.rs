@@ -0,0 +1,84 @@
+//! Contexts: named filters applied to every listing, like `work`
+//!
+//! Contexts are defined in the `[contexts]` table of the
+//! [config file](crate::config), and the active one is its `context` setting:
+//!
+//! ```toml
+//! context = "work"
+//!
+//! [contexts]
+//! work = "+work or project:office"
+//! home = "project:home"
+//! ```
+//!
+//! The active context's filter is combined with the filter of every listing
+//! and report. New tasks get the attributes it asks for, like its tags (see
+//! [`Filter::apply_to`]).
+
+use crate::{Filter, Task, filter};
+
+/// The name which turns contexts off
+pub const NONE: &str = "none";
+
+/// Names which can't be given to a context: [`NONE`], and `list`, which
+/// lists contexts
+pub const RESERVED: [&str; 2] = [NONE, "list"];
+
+/// A named filter
+#[derive(Debug, Clone)]
+pub struct Context {
+    name: String,
+    filter_text: String,
+    filter: Filter,
+}
+
+impl Context {
+    /// A context with a filter expression
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the filter is invalid.
+    pub fn new(name: &str, filter: &str) -> Result<Self, Error> {
+        Ok(Self {
+            name: name.to_string(),
+            filter_text: filter.to_string(),
+            filter: Filter::parse(filter).map_err(|source| Error {
+                context: name.to_string(),
+                source,
+            })?,
+        })
+    }
+
+    /// The name the context is chosen by
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The filter expression, as written
+    #[must_use]
+    pub fn filter_text(&self) -> &str {
+        &self.filter_text
+    }
+
+    /// The filter
+    #[must_use]
+    pub const fn filter(&self) -> &Filter {
+        &self.filter
+    }
+
+    /// Give a new task the attributes the context asks for
+    pub fn apply_to(&self, task: &mut Task) {
+        self.filter.apply_to(task);
+    }
+}
+
+/// A context has an invalid filter
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("context '{context}' has an invalid filter: {source}")]
+pub struct Error {
+    /// The name of the context
+    pub context: String,
+    /// Why the filter is invalid
+    pub source: filter::Error,
+}
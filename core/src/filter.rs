@@ -0,0 +1,587 @@
+//! Expressions which choose tasks, like `+work and (priority.above:3 or
+//! due:)`
+//!
+//! A filter is made of terms:
+//!
+//! - `+tag` matches tasks with the tag, and `-tag` tasks without it
+//! - `name:value` compares an attribute, and `name.modifier:value` compares it
+//!   another way (see below). An empty value matches tasks where the attribute
+//!   is unset
+//! - `/pattern/` matches the description against a regular expression
+//! - any other word matches descriptions containing it, ignoring case
+//!
+//! Terms are combined with `and`, `or` and `not`, and grouped with
+//! parentheses. Terms next to each other must both match, so `+work urgent`
+//! is the same as `+work and urgent`. `not` binds tightest, then `and`, then
+//! `or`. Values containing spaces can be quoted, like `project:"big move"`.
+//!
+//! The attributes are `description`, `status`, `priority`, `project`, the
+//! dates `created`, `modified`, `start`, `end`, `completed` and `deleted`,
+//! and any other name for an extra attribute (see [`Task::extra`]).
+//!
+//! `status` is `pending`, `started`, `completed`, `deleted`, or `open` for
+//! tasks which are pending or started. Dates are `YYYY-MM-DD` (midnight
+//! UTC) or RFC 3339 timestamps, and a plain date matches the whole day.
+//! `today` is the current day, and `now` the current moment. Extra attributes
+//! holding dates, like `due`, can be compared with `after` and `before`.
+//!
+//! The modifiers are:
+//!
+//! - `is` (the default) and `not`
+//! - `above` and `below`, or `after` and `before` for dates. A priority is
+//!   above another when it is more important, so `priority.above:3` matches
+//!   priorities 1 and 2
+//! - `has`, `startswith` and `endswith`, ignoring case
+//! - `regex`, a regular expression
+//! - `any` and `none`, for attributes which are set or unset
+//!
+//! `description:word` matches descriptions containing the word, like a plain
+//! word does.
+
+use std::{cmp::Ordering, str::FromStr};
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use regex::Regex;
+
+use crate::{Priority, Task};
+
+mod parser;
+
+/// A parsed filter expression
+///
+/// The default filter matches every task.
+#[derive(Debug, Default, Clone)]
+pub struct Filter {
+    expr: Option<Expr>,
+}
+
+/// A node of a [`Filter`]
+#[derive(Debug, Clone)]
+enum Expr {
+    Condition(Condition),
+    Not(Box<Self>),
+    And(Vec<Self>),
+    Or(Vec<Self>),
+}
+
+/// A test of a single attribute
+#[derive(Debug, Clone)]
+enum Condition {
+    Tag(String),
+    Word(String),
+    Status(Status),
+    Set(Field),
+    Text(Field, TextTest),
+    Priority(Ordering, Priority),
+    Date(Field, Ordering, Moment),
+}
+
+/// A task attribute which can be filtered on
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Field {
+    Description,
+    Status,
+    Priority,
+    Project,
+    Created,
+    Modified,
+    Start,
+    End,
+    Completed,
+    Deleted,
+    Extra(String),
+}
+
+#[derive(Debug, Clone)]
+enum TextTest {
+    Equals(String),
+    Contains(String),
+    StartsWith(String),
+    EndsWith(String),
+    Regex(Regex),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pending,
+    Started,
+    Completed,
+    Deleted,
+    Open,
+}
+
+/// A date, or a whole day
+#[derive(Debug, Clone, Copy)]
+struct Moment {
+    start: DateTime<Utc>,
+    day: bool,
+}
+
+impl Filter {
+    /// Parse a filter expression
+    ///
+    /// An empty expression matches every task.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression is malformed, or a value is
+    /// invalid for its attribute.
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        parser::parse(input).map(|expr| Self { expr })
+    }
+
+    /// Whether the task matches the filter
+    #[must_use]
+    pub fn matches(&self, task: &Task) -> bool {
+        self.expr.as_ref().is_none_or(|expr| expr.matches(task))
+    }
+
+    /// Whether the filter asks about deleted tasks
+    ///
+    /// Deleted tasks are usually hidden, unless the filter mentions
+    /// `status:deleted` or the `deleted` date.
+    #[must_use]
+    pub fn includes_deleted(&self) -> bool {
+        self.expr.as_ref().is_some_and(Expr::includes_deleted)
+    }
+
+    /// Whether the filter matches every task
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.expr.is_none()
+    }
+
+    /// Give a task the attributes the filter asks for
+    ///
+    /// Only the tags, project and priority of terms which must all match,
+    /// like `+work project:office priority:2`, are set. Other terms are
+    /// ignored, so the task may still not match.
+    pub fn apply_to(&self, task: &mut Task) {
+        if let Some(expr) = &self.expr {
+            expr.apply_to(task);
+        }
+    }
+
+    /// Combine two filters, so that a task must match both
+    #[must_use]
+    pub fn and(self, other: Self) -> Self {
+        let expr = match (self.expr, other.expr) {
+            (Some(left), Some(right)) => Some(Expr::And(vec![left, right])),
+            (left, right) => left.or(right),
+        };
+        Self { expr }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Expr {
+    fn matches(&self, task: &Task) -> bool {
+        match self {
+            Self::Condition(condition) => condition.matches(task),
+            Self::Not(expr) => !expr.matches(task),
+            Self::And(exprs) => exprs.iter().all(|expr| expr.matches(task)),
+            Self::Or(exprs) => exprs.iter().any(|expr| expr.matches(task)),
+        }
+    }
+
+    fn apply_to(&self, task: &mut Task) {
+        match self {
+            Self::Condition(Condition::Tag(tag)) => {
+                task.add_tag(tag.clone());
+            }
+            Self::Condition(Condition::Text(Field::Project, TextTest::Equals(project))) => {
+                task.set_project(Some(project.clone()));
+            }
+            Self::Condition(Condition::Priority(Ordering::Equal, priority)) => {
+                task.set_priority(Some(*priority));
+            }
+            Self::And(exprs) => {
+                for expr in exprs {
+                    expr.apply_to(task);
+                }
+            }
+            Self::Condition(_) | Self::Not(_) | Self::Or(..) => {}
+        }
+    }
+
+    fn includes_deleted(&self) -> bool {
+        match self {
+            Self::Condition(condition) => matches!(
+                condition,
+                Condition::Status(Status::Deleted)
+                    | Condition::Set(Field::Deleted)
+                    | Condition::Date(Field::Deleted, ..)
+            ),
+            Self::Not(expr) => expr.includes_deleted(),
+            Self::And(exprs) | Self::Or(exprs) => exprs.iter().any(Self::includes_deleted),
+        }
+    }
+}
+
+impl Condition {
+    fn matches(&self, task: &Task) -> bool {
+        match self {
+            Self::Tag(tag) => task.has_tag(tag),
+            Self::Word(word) => contains(task.description(), word),
+            Self::Status(status) => status.matches(task),
+            Self::Set(field) => match field {
+                Field::Priority => task.priority().is_some(),
+                Field::Created
+                | Field::Modified
+                | Field::Start
+                | Field::End
+                | Field::Completed
+                | Field::Deleted => field.date(task).is_some(),
+                _ => field.text(task).is_some_and(|text| !text.is_empty()),
+            },
+            Self::Text(field, test) => field.text(task).is_some_and(|text| test.matches(&text)),
+            Self::Priority(ordering, priority) => task
+                .priority()
+                .and_then(|actual| actual.partial_cmp(priority))
+                .is_some_and(|actual| actual == *ordering),
+            Self::Date(field, ordering, moment) => field
+                .date(task)
+                .is_some_and(|date| moment.compare(date) == *ordering),
+        }
+    }
+}
+
+impl Field {
+    fn parse(name: &str) -> Self {
+        match name {
+            "description" => Self::Description,
+            "status" => Self::Status,
+            "priority" => Self::Priority,
+            "project" => Self::Project,
+            "created" => Self::Created,
+            "modified" => Self::Modified,
+            "start" => Self::Start,
+            "end" => Self::End,
+            "completed" => Self::Completed,
+            "deleted" => Self::Deleted,
+            name => Self::Extra(name.to_string()),
+        }
+    }
+
+    const fn is_date(&self) -> bool {
+        matches!(
+            self,
+            Self::Created
+                | Self::Modified
+                | Self::Start
+                | Self::End
+                | Self::Completed
+                | Self::Deleted
+        )
+    }
+
+    fn text(&self, task: &Task) -> Option<String> {
+        match self {
+            Self::Description => Some(task.description().clone()),
+            Self::Project => task.project().map(ToString::to_string),
+            Self::Extra(name) => task.extra().get(name).map(|value| {
+                value
+                    .as_str()
+                    .map_or_else(|| value.to_string(), ToString::to_string)
+            }),
+            _ => None,
+        }
+    }
+
+    fn date(&self, task: &Task) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Created => Some(task.created()),
+            Self::Modified => Some(task.modified()),
+            Self::Start => task.start(),
+            Self::End => task.end(),
+            Self::Completed => task.completed(),
+            Self::Deleted => task.deleted(),
+            Self::Extra(_) => self.text(task).as_deref().and_then(parse_date),
+            _ => None,
+        }
+    }
+}
+
+/// A date (YYYY-MM-DD, at midnight UTC) or an RFC 3339 timestamp
+fn parse_date(text: &str) -> Option<DateTime<Utc>> {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+        .or_else(|_| DateTime::parse_from_rfc3339(text).map(|timestamp| timestamp.to_utc()))
+        .ok()
+}
+
+impl TextTest {
+    fn matches(&self, text: &str) -> bool {
+        match self {
+            Self::Equals(value) => text == value,
+            Self::Contains(value) => contains(text, value),
+            Self::StartsWith(value) => text.to_lowercase().starts_with(&value.to_lowercase()),
+            Self::EndsWith(value) => text.to_lowercase().ends_with(&value.to_lowercase()),
+            Self::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+impl Status {
+    const fn matches(self, task: &Task) -> bool {
+        let open = !task.is_deleted() && !task.is_completed();
+        match self {
+            Self::Pending => open && !task.is_started(),
+            Self::Started => open && task.is_started(),
+            Self::Completed => !task.is_deleted() && task.is_completed(),
+            Self::Deleted => task.is_deleted(),
+            Self::Open => open,
+        }
+    }
+}
+
+impl Moment {
+    /// Where `date` falls relative to this moment
+    fn compare(self, date: DateTime<Utc>) -> Ordering {
+        let end = if self.day {
+            self.start + Duration::days(1)
+        } else {
+            self.start
+        };
+        if date < self.start {
+            Ordering::Less
+        } else if date > end || (self.day && date == end) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+}
+
+fn contains(text: &str, word: &str) -> bool {
+    text.to_lowercase().contains(&word.to_lowercase())
+}
+
+/// Error type for parsing a [`Filter`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    /// The expression ended where a term was expected
+    #[error("the filter ends too early")]
+    UnexpectedEnd,
+    /// Something was found where it doesn't belong, like `and or`
+    #[error("unexpected '{0}' in the filter")]
+    Unexpected(String),
+    /// A `(` has no matching `)`
+    #[error("missing ')' in the filter")]
+    UnclosedParenthesis,
+    /// Parentheses or `not` are nested too deeply
+    #[error("the filter nests more than {0} levels deep")]
+    TooDeep(usize),
+    /// A quote or `/pattern/` isn't closed
+    #[error("unclosed {0} in the filter")]
+    Unclosed(&'static str),
+    /// The modifier after a `.` isn't known
+    #[error("unknown modifier '{0}'")]
+    UnknownModifier(String),
+    /// The modifier can't be used with the attribute
+    #[error("'{modifier}' can't be used with {attribute}")]
+    UnsupportedModifier {
+        /// The attribute
+        attribute: String,
+        /// The modifier
+        modifier: String,
+    },
+    /// The value isn't valid for the attribute
+    #[error("invalid {attribute} '{value}'. {expected}")]
+    InvalidValue {
+        /// The attribute
+        attribute: String,
+        /// The value given
+        value: String,
+        /// What would be valid
+        expected: &'static str,
+    },
+    /// A regular expression can't be compiled
+    #[error("invalid regular expression: {0}")]
+    InvalidRegex(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use test_case::test_case;
+
+    use super::{Error, Filter};
+    use crate::{Priority, Task};
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        timestamp.parse().unwrap()
+    }
+
+    fn tasks() -> Vec<Task> {
+        let mut started = Task::new("Book flights to Oslo".to_string())
+            .with_tag("travel".to_string())
+            .with_project("big move".to_string())
+            .with_priority(Priority::Three)
+            .with_created(at("2024-03-01T12:00:00Z"));
+        started.mark_started();
+
+        vec![
+            Task::new("Write report".to_string())
+                .with_tag("work".to_string())
+                .with_priority(Priority::One)
+                .with_created(at("2024-01-10T09:00:00Z")),
+            Task::new("Water plants".to_string())
+                .with_project("home".to_string())
+                .with_extra("room".to_string(), serde_json::json!("kitchen"))
+                .with_extra("due".to_string(), serde_json::json!("2024-02-10"))
+                .with_created(at("2024-02-01T08:00:00Z")),
+            started,
+            Task::new("Pay invoice 42".to_string())
+                .with_tag("work".to_string())
+                .with_priority(Priority::Two)
+                .with_created(at("2024-02-01T18:00:00Z"))
+                .with_completed(at("2024-02-02T10:00:00Z")),
+            Task::new("Old idea".to_string())
+                .with_created(at("2023-12-01T00:00:00Z"))
+                .with_deleted(at("2024-01-01T00:00:00Z")),
+        ]
+    }
+
+    fn select(filter: &str) -> Vec<usize> {
+        let filter = Filter::parse(filter).unwrap();
+        tasks()
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| filter.matches(task))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    #[test_case("" => vec![0, 1, 2, 3, 4] ; "empty")]
+    #[test_case("+work" => vec![0, 3] ; "tag")]
+    #[test_case("-work" => vec![1, 2, 4] ; "not tag")]
+    #[test_case("water" => vec![1] ; "word")]
+    #[test_case("description:REPORT" => vec![0] ; "description")]
+    #[test_case("/^[WP]a/" => vec![1, 3] ; "regex")]
+    #[test_case("/invoice \\d+$/" => vec![3] ; "regex with spaces")]
+    #[test_case("description.regex:^Old" => vec![4] ; "regex modifier")]
+    #[test_case("priority:1" => vec![0] ; "priority")]
+    #[test_case("priority.above:3" => vec![0, 3] ; "priority above")]
+    #[test_case("priority.below:2" => vec![2] ; "priority below")]
+    #[test_case("priority:" => vec![1, 4] ; "priority unset")]
+    #[test_case("priority.any:" => vec![0, 2, 3] ; "priority set")]
+    #[test_case("project:home" => vec![1] ; "project")]
+    #[test_case("project:\"big move\"" => vec![2] ; "quoted value")]
+    #[test_case("project.not:home" => vec![0, 2, 3, 4] ; "not modifier")]
+    #[test_case("project.startswith:BIG" => vec![2] ; "starts with")]
+    #[test_case("project.none:" => vec![0, 3, 4] ; "project unset")]
+    #[test_case("room:kitchen" => vec![1] ; "extra")]
+    #[test_case("room.has:kit" => vec![1] ; "extra contains")]
+    #[test_case("created.after:2024-01-31" => vec![1, 2, 3] ; "created after")]
+    #[test_case("created.before:2024-01-10" => vec![4] ; "created before")]
+    #[test_case("created:2024-02-01" => vec![1, 3] ; "created on day")]
+    #[test_case("completed.after:2024-02-01T12:00:00Z" => vec![3] ; "completed after timestamp")]
+    #[test_case("due.before:2024-02-11" => vec![1] ; "extra date before")]
+    #[test_case("due.after:2024-02-10" => Vec::<usize>::new() ; "extra date after")]
+    #[test_case("created.before:today" => vec![0, 1, 2, 3, 4] ; "today")]
+    #[test_case("completed.before:now" => vec![3] ; "now")]
+    #[test_case("status:pending" => vec![0, 1] ; "pending")]
+    #[test_case("status:started" => vec![2] ; "started")]
+    #[test_case("status:open" => vec![0, 1, 2] ; "open")]
+    #[test_case("status:completed" => vec![3] ; "completed")]
+    #[test_case("status:deleted" => vec![4] ; "deleted")]
+    #[test_case("+work or project:home" => vec![0, 1, 3] ; "or")]
+    #[test_case("+work and status:open" => vec![0] ; "and")]
+    #[test_case("+work status:open" => vec![0] ; "implicit and")]
+    #[test_case("not +work" => vec![1, 2, 4] ; "not")]
+    #[test_case("+work or +travel and priority:3" => vec![0, 2, 3] ; "and before or")]
+    #[test_case("(+work or +travel) and priority.above:3" => vec![0, 3] ; "parentheses")]
+    #[test_case("not (+work or status:deleted)" => vec![1, 2] ; "not group")]
+    #[test_case("((priority:1))" => vec![0] ; "nested parentheses")]
+    #[test_case("at 10:30 http://example.com" => Vec::<usize>::new() ; "colons in words")]
+    fn filter(filter: &str) -> Vec<usize> {
+        select(filter)
+    }
+
+    #[test_case("+work and" => Error::UnexpectedEnd ; "dangling and")]
+    #[test_case("or +work" => Error::Unexpected("or".to_string()) ; "leading or")]
+    #[test_case("(+work" => Error::UnclosedParenthesis ; "unclosed parenthesis")]
+    #[test_case("+work)" => Error::Unexpected(")".to_string()) ; "extra parenthesis")]
+    #[test_case("/abc" => Error::Unclosed("regular expression") ; "unclosed regex")]
+    #[test_case("project:\"abc" => Error::Unclosed("quote") ; "unclosed quote")]
+    #[test_case("priority.soon:1" => Error::UnknownModifier("soon".to_string()) ; "unknown modifier")]
+    #[test_case("room.above:kitchen" => matches Error::InvalidValue { .. } ; "extra date")]
+    #[test_case("priority.has:1" => Error::UnsupportedModifier { attribute: "priority".to_string(), modifier: "has".to_string() } ; "unsupported modifier")]
+    #[test_case("status.above:pending" => Error::UnsupportedModifier { attribute: "status".to_string(), modifier: "above".to_string() } ; "status modifier")]
+    #[test_case("priority:7" => matches Error::InvalidValue { .. } ; "priority")]
+    #[test_case("created.after:yesterday" => matches Error::InvalidValue { .. } ; "date")]
+    #[test_case("status:done" => matches Error::InvalidValue { .. } ; "status")]
+    #[test_case("/(/" => matches Error::InvalidRegex(_) ; "regex")]
+    fn parse_error(filter: &str) -> Error {
+        Filter::parse(filter).unwrap_err()
+    }
+
+    #[test_case(64 => (true, true) ; "at the limit")]
+    #[test_case(65 => (false, false) ; "past the limit")]
+    #[test_case(5000 => (false, false) ; "far past the limit")]
+    fn depth(depth: usize) -> (bool, bool) {
+        let parses = |filter: String| match Filter::parse(&filter) {
+            Ok(_) => true,
+            Err(Error::TooDeep(64)) => false,
+            Err(error) => panic!("unexpected error: {error}"),
+        };
+        (
+            parses(format!("{}+work{}", "(".repeat(depth), ")".repeat(depth))),
+            parses(format!("{}+work", "not ".repeat(depth))),
+        )
+    }
+
+    #[test]
+    fn long() {
+        let filter = Filter::parse(&format!("{}+work", "+work or ".repeat(100_000))).unwrap();
+        assert!(filter.matches(&tasks()[0]));
+        let filter = Filter::parse(&"+work ".repeat(100_000)).unwrap();
+        assert!(!filter.matches(&tasks()[1]));
+    }
+
+    #[test_case("+work project:office priority:2" => (vec!["work".to_string()], Some("office".to_string()), Some(2)) ; "all")]
+    #[test_case("+work and (+home or project:garden) -errand" => (vec!["work".to_string()], None, None) ; "only required")]
+    #[test_case("project.has:off priority.above:3" => (vec![], None, None) ; "only exact")]
+    fn apply_to(filter: &str) -> (Vec<String>, Option<String>, Option<u8>) {
+        let mut task = Task::new("Something".to_string());
+        Filter::parse(filter).unwrap().apply_to(&mut task);
+        (
+            task.tags().iter().cloned().collect(),
+            task.project().map(ToString::to_string),
+            task.priority().map(u8::from),
+        )
+    }
+
+    #[test]
+    fn includes_deleted() {
+        assert!(!Filter::parse("+work").unwrap().includes_deleted());
+        assert!(
+            Filter::parse("+work or status:deleted")
+                .unwrap()
+                .includes_deleted()
+        );
+        assert!(
+            Filter::parse("deleted.after:2024-01-01")
+                .unwrap()
+                .includes_deleted()
+        );
+    }
+
+    #[test]
+    fn and() {
+        let filter = Filter::default().and(Filter::parse("+work").unwrap());
+        assert!(!filter.is_empty());
+        let filter = filter.and(Filter::parse("priority:2").unwrap());
+        let tasks = tasks();
+        assert!(filter.matches(&tasks[3]));
+        assert!(!filter.matches(&tasks[0]));
+    }
+}
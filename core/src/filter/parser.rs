@@ -0,0 +1,362 @@
+use std::{cmp::Ordering, iter::Peekable, vec::IntoIter};
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use regex::Regex;
+
+use super::{Condition, Error, Expr, Field, Moment, Status, TextTest};
+use crate::Priority;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Regex(String),
+    Word(String),
+}
+
+/// Parse a whole expression, which is `None` if it is empty
+pub(super) fn parse(input: &str) -> Result<Option<Expr>, Error> {
+    let mut tokens = tokenize(input)?.into_iter().peekable();
+    if tokens.peek().is_none() {
+        return Ok(None);
+    }
+    let expr = or(&mut tokens, 0)?;
+    if let Some(token) = tokens.next() {
+        return Err(unexpected(token));
+    }
+    Ok(Some(expr))
+}
+
+type Tokens = Peekable<IntoIter<Token>>;
+
+/// How deeply parentheses and `not` can nest, so that a hostile filter can't
+/// overflow the stack
+pub(super) const MAX_DEPTH: usize = 64;
+
+fn or(tokens: &mut Tokens, depth: usize) -> Result<Expr, Error> {
+    let mut exprs = vec![and(tokens, depth)?];
+    while tokens.next_if(|token| is_keyword(token, "or")).is_some() {
+        exprs.push(and(tokens, depth)?);
+    }
+    Ok(flatten(exprs, Expr::Or))
+}
+
+fn and(tokens: &mut Tokens, depth: usize) -> Result<Expr, Error> {
+    let mut exprs = vec![not(tokens, depth)?];
+    loop {
+        let explicit = tokens.next_if(|token| is_keyword(token, "and")).is_some();
+        // Terms next to each other must both match
+        let implicit = tokens
+            .peek()
+            .is_some_and(|token| *token != Token::Close && !is_keyword(token, "or"));
+        if !explicit && !implicit {
+            return Ok(flatten(exprs, Expr::And));
+        }
+        exprs.push(not(tokens, depth)?);
+    }
+}
+
+/// A single term stands for itself, more are joined by `and` or `or`
+///
+/// Terms are kept in a list rather than nested, so that long filters don't
+/// make deep expressions.
+fn flatten(mut exprs: Vec<Expr>, join: fn(Vec<Expr>) -> Expr) -> Expr {
+    if exprs.len() == 1 {
+        exprs.remove(0)
+    } else {
+        join(exprs)
+    }
+}
+
+fn not(tokens: &mut Tokens, depth: usize) -> Result<Expr, Error> {
+    let nested = || {
+        if depth < MAX_DEPTH {
+            Ok(depth + 1)
+        } else {
+            Err(Error::TooDeep(MAX_DEPTH))
+        }
+    };
+    if tokens.next_if(|token| is_keyword(token, "not")).is_some() {
+        return Ok(Expr::Not(Box::new(not(tokens, nested()?)?)));
+    }
+    match tokens.next().ok_or(Error::UnexpectedEnd)? {
+        Token::Open => {
+            let expr = or(tokens, nested()?)?;
+            match tokens.next() {
+                Some(Token::Close) => Ok(expr),
+                Some(token) => Err(unexpected(token)),
+                None => Err(Error::UnclosedParenthesis),
+            }
+        }
+        Token::Regex(pattern) => Ok(Expr::Condition(Condition::Text(
+            Field::Description,
+            TextTest::Regex(regex(&pattern)?),
+        ))),
+        Token::Word(word)
+            if word.eq_ignore_ascii_case("and") || word.eq_ignore_ascii_case("or") =>
+        {
+            Err(Error::Unexpected(word))
+        }
+        Token::Word(word) => term(&word),
+        Token::Close => Err(unexpected(Token::Close)),
+    }
+}
+
+fn is_keyword(token: &Token, keyword: &str) -> bool {
+    matches!(token, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+}
+
+fn unexpected(token: Token) -> Error {
+    Error::Unexpected(match token {
+        Token::Open => "(".to_string(),
+        Token::Close => ")".to_string(),
+        Token::Regex(pattern) => format!("/{pattern}/"),
+        Token::Word(word) => word,
+    })
+}
+
+/// Split the input into words, parentheses and `/patterns/`
+///
+/// Double quotes group words with spaces or parentheses, and are removed.
+fn tokenize(input: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '/' => {
+                chars.next();
+                let mut pattern = String::new();
+                loop {
+                    match chars.next() {
+                        Some('/') => break,
+                        Some('\\') => {
+                            pattern.push('\\');
+                            pattern.extend(chars.next());
+                        }
+                        Some(c) => pattern.push(c),
+                        None => return Err(Error::Unclosed("regular expression")),
+                    }
+                }
+                tokens.push(Token::Regex(pattern));
+            }
+            _ => {
+                let mut word = String::new();
+                let mut quoted = false;
+                while let Some(&c) = chars.peek() {
+                    if !quoted && (c.is_whitespace() || c == '(' || c == ')') {
+                        break;
+                    }
+                    chars.next();
+                    if c == '"' {
+                        quoted = !quoted;
+                    } else {
+                        word.push(c);
+                    }
+                }
+                if quoted {
+                    return Err(Error::Unclosed("quote"));
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parse a single term: a tag, an attribute or a word
+fn term(word: &str) -> Result<Expr, Error> {
+    if let Some(tag) = word.strip_prefix('+').filter(|tag| !tag.is_empty()) {
+        return Ok(Expr::Condition(Condition::Tag(tag.to_string())));
+    }
+    if let Some(tag) = word.strip_prefix('-').filter(|tag| !tag.is_empty()) {
+        return Ok(Expr::Not(Box::new(Expr::Condition(Condition::Tag(
+            tag.to_string(),
+        )))));
+    }
+
+    let split = word
+        .split_once(':')
+        .filter(|(name, value)| is_attribute_name(name) && !value.starts_with("//"));
+    let Some((name, value)) = split else {
+        return Ok(Expr::Condition(Condition::Word(word.to_string())));
+    };
+    let (name, modifier) = name.split_once('.').unwrap_or((name, "is"));
+    attribute(name, modifier, value)
+}
+
+/// Attribute names are identifiers, optionally followed by `.modifier`
+fn is_attribute_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Modifier {
+    Is,
+    Not,
+    Above,
+    Below,
+    Has,
+    StartsWith,
+    EndsWith,
+    Regex,
+    Any,
+    None,
+}
+
+impl Modifier {
+    fn parse(name: &str) -> Result<Self, Error> {
+        Ok(match name {
+            "is" | "equals" => Self::Is,
+            "not" | "isnt" => Self::Not,
+            "above" | "after" => Self::Above,
+            "below" | "before" => Self::Below,
+            "has" | "contains" => Self::Has,
+            "startswith" => Self::StartsWith,
+            "endswith" => Self::EndsWith,
+            "regex" => Self::Regex,
+            "any" => Self::Any,
+            "none" => Self::None,
+            name => return Err(Error::UnknownModifier(name.to_string())),
+        })
+    }
+}
+
+fn attribute(name: &str, modifier: &str, value: &str) -> Result<Expr, Error> {
+    let field = Field::parse(name);
+    let unsupported = || Error::UnsupportedModifier {
+        attribute: name.to_string(),
+        modifier: modifier.to_string(),
+    };
+
+    let parsed = Modifier::parse(modifier)?;
+    if parsed == Modifier::Not {
+        return attribute(name, "is", value).map(|expr| Expr::Not(Box::new(expr)));
+    }
+
+    let condition = match (&field, parsed) {
+        (Field::Status, Modifier::Is) => Condition::Status(status(value)?),
+        (Field::Status, _) => return Err(unsupported()),
+        (_, Modifier::Any) => Condition::Set(field),
+        (_, Modifier::None) => {
+            return Ok(Expr::Not(Box::new(Expr::Condition(Condition::Set(field)))));
+        }
+        (_, Modifier::Is) if value.is_empty() => {
+            return Ok(Expr::Not(Box::new(Expr::Condition(Condition::Set(field)))));
+        }
+        (Field::Priority, Modifier::Is) => Condition::Priority(Ordering::Equal, priority(value)?),
+        (Field::Priority, Modifier::Above) => {
+            Condition::Priority(Ordering::Greater, priority(value)?)
+        }
+        (Field::Priority, Modifier::Below) => Condition::Priority(Ordering::Less, priority(value)?),
+        (field, Modifier::Is) if field.is_date() => {
+            Condition::Date(field.clone(), Ordering::Equal, moment(name, value)?)
+        }
+        (field, Modifier::Above) if field.is_date() => {
+            Condition::Date(field.clone(), Ordering::Greater, moment(name, value)?)
+        }
+        (field, Modifier::Below) if field.is_date() => {
+            Condition::Date(field.clone(), Ordering::Less, moment(name, value)?)
+        }
+        // Extra attributes holding dates can be compared with dates
+        (Field::Extra(_), Modifier::Above) => {
+            Condition::Date(field, Ordering::Greater, moment(name, value)?)
+        }
+        (Field::Extra(_), Modifier::Below) => {
+            Condition::Date(field, Ordering::Less, moment(name, value)?)
+        }
+        (Field::Priority, _) | (_, Modifier::Above | Modifier::Below) => {
+            return Err(unsupported());
+        }
+        (field, _) if field.is_date() => return Err(unsupported()),
+        (Field::Description, Modifier::Is) | (_, Modifier::Has) => {
+            Condition::Text(field, TextTest::Contains(value.to_string()))
+        }
+        (_, Modifier::Is) => Condition::Text(field, TextTest::Equals(value.to_string())),
+        (_, Modifier::StartsWith) => {
+            Condition::Text(field, TextTest::StartsWith(value.to_string()))
+        }
+        (_, Modifier::EndsWith) => Condition::Text(field, TextTest::EndsWith(value.to_string())),
+        (_, Modifier::Regex) => Condition::Text(field, TextTest::Regex(regex(value)?)),
+        (_, Modifier::Not) => unreachable!("handled above"),
+    };
+    Ok(Expr::Condition(condition))
+}
+
+fn status(value: &str) -> Result<Status, Error> {
+    Ok(match value {
+        "pending" => Status::Pending,
+        "started" => Status::Started,
+        "completed" => Status::Completed,
+        "deleted" => Status::Deleted,
+        "open" => Status::Open,
+        value => {
+            return Err(Error::InvalidValue {
+                attribute: "status".to_string(),
+                value: value.to_string(),
+                expected: "Expected pending, started, completed, deleted or open",
+            });
+        }
+    })
+}
+
+fn priority(value: &str) -> Result<Priority, Error> {
+    value.parse().map_err(|_| Error::InvalidValue {
+        attribute: "priority".to_string(),
+        value: value.to_string(),
+        expected: "Expected 1-4",
+    })
+}
+
+fn moment(name: &str, value: &str) -> Result<Moment, Error> {
+    let now = Utc::now();
+    match value {
+        "now" => {
+            return Ok(Moment {
+                start: now,
+                day: false,
+            });
+        }
+        "today" => {
+            return Ok(Moment {
+                start: now.date_naive().and_time(NaiveTime::MIN).and_utc(),
+                day: true,
+            });
+        }
+        _ => {}
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(Moment {
+            start: date.and_time(NaiveTime::MIN).and_utc(),
+            day: true,
+        });
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| Moment {
+            start: timestamp.to_utc(),
+            day: false,
+        })
+        .map_err(|_| Error::InvalidValue {
+            attribute: name.to_string(),
+            value: value.to_string(),
+            expected: "Expected a date (YYYY-MM-DD), an RFC 3339 timestamp, today or now",
+        })
+}
+
+fn regex(pattern: &str) -> Result<Regex, Error> {
+    Regex::new(pattern).map_err(|e| Error::InvalidRegex(e.to_string()))
+}
@@ -0,0 +1,8 @@
+//! Conversion between tasks and the file formats of other tools
+
+pub mod csv;
+pub mod ics;
+pub mod markdown;
+pub mod org;
+pub mod taskwarrior;
+pub mod todotxt;
@@ -0,0 +1,503 @@
+//! Comma-separated values, for round trips through spreadsheets
+//!
+//! Each [`Column`] holds one [`Task`] attribute. Timestamps are written as
+//! RFC 3339, and may be read back as either RFC 3339 or plain `YYYY-MM-DD`
+//! dates. Tags are separated by spaces, and [`Task::extra`] is a JSON object.
+//!
+//! Importing is forgiving. A row which can't be imported is reported, and the
+//! rest of the file is still processed. Rows whose `uuid` matches an existing
+//! task update just the columns present in the file, so a spreadsheet that
+//! only contains a few columns can still be used to edit tasks in bulk. A row
+//! which changes anything but `modified` marks the task as modified now.
+
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{ImportSummary, Priority, Store, Task};
+
+/// A task attribute that can be written to or read from a CSV column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    /// [`Task::uuid`]
+    Uuid,
+    /// [`Task::description`]
+    Description,
+    /// [`Task::priority`], as 1-4
+    Priority,
+    /// [`Task::project`]
+    Project,
+    /// [`Task::tags`], separated by spaces
+    Tags,
+    /// [`Task::created`]
+    Created,
+    /// [`Task::modified`]
+    Modified,
+    /// [`Task::start`]
+    Start,
+    /// [`Task::end`]
+    End,
+    /// [`Task::completed`]
+    Completed,
+    /// [`Task::extra`], as a JSON object
+    Extra,
+}
+
+impl Column {
+    /// Every column, in the default export order
+    pub const ALL: [Self; 11] = [
+        Self::Uuid,
+        Self::Description,
+        Self::Priority,
+        Self::Project,
+        Self::Tags,
+        Self::Created,
+        Self::Modified,
+        Self::Start,
+        Self::End,
+        Self::Completed,
+        Self::Extra,
+    ];
+
+    /// The header name of the column
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Uuid => "uuid",
+            Self::Description => "description",
+            Self::Priority => "priority",
+            Self::Project => "project",
+            Self::Tags => "tags",
+            Self::Created => "created",
+            Self::Modified => "modified",
+            Self::Start => "start",
+            Self::End => "end",
+            Self::Completed => "completed",
+            Self::Extra => "extra",
+        }
+    }
+
+    fn render(self, task: &Task) -> String {
+        let timestamp = |t: Option<DateTime<Utc>>| t.map(|t| t.to_rfc3339()).unwrap_or_default();
+        match self {
+            Self::Uuid => task.uuid().to_string(),
+            Self::Description => task.description().clone(),
+            Self::Priority => task.priority().map(|p| p.to_string()).unwrap_or_default(),
+            Self::Project => task.project().unwrap_or_default().to_string(),
+            Self::Tags => task
+                .tags()
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+            Self::Created => timestamp(Some(task.created())),
+            Self::Modified => timestamp(Some(task.modified())),
+            Self::Start => timestamp(task.start()),
+            Self::End => timestamp(task.end()),
+            Self::Completed => timestamp(task.completed()),
+            Self::Extra if task.extra().is_empty() => String::new(),
+            Self::Extra => Value::Object(
+                task.extra()
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect(),
+            )
+            .to_string(),
+        }
+    }
+
+    /// Write a cell into the serialised form of a [`Task`]
+    fn apply(self, cell: &str, task: &mut Map<String, Value>) -> Result<(), String> {
+        let cell = cell.trim();
+        let key = self.name().to_string();
+
+        let value = match self {
+            Self::Uuid => return Ok(()),
+            Self::Description if cell.is_empty() => return Err("description is empty".into()),
+            Self::Priority | Self::Project | Self::Extra if cell.is_empty() => Value::Null,
+            Self::Description | Self::Project => Value::String(cell.to_string()),
+            Self::Priority => {
+                let priority = Priority::from_str(cell).map_err(|e| e.to_string())?;
+                u8::from(priority).into()
+            }
+            Self::Tags => cell.split_whitespace().map(Value::from).collect(),
+            Self::Created | Self::Modified if cell.is_empty() => return Ok(()),
+            Self::Created | Self::Modified | Self::Start | Self::End | Self::Completed => {
+                parse_timestamp(cell)?.map_or(Value::Null, |t| Value::String(t.to_rfc3339()))
+            }
+            Self::Extra => match serde_json::from_str(cell) {
+                Ok(Value::Object(extra)) => Value::Object(extra),
+                _ => return Err(format!("extra must be a JSON object, got '{cell}'")),
+            },
+        };
+
+        if value.is_null() {
+            task.remove(&key);
+        } else {
+            task.insert(key, value);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Column {
+    type Err = UnknownColumn;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|column| column.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| UnknownColumn(s.to_string()))
+    }
+}
+
+/// A column name which doesn't match any [`Column`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "unknown column '{0}'. Expected one of uuid, description, priority, project, tags, created, \
+     modified, start, end, completed, extra"
+)]
+pub struct UnknownColumn(String);
+
+/// Which [`Column`] each CSV header holds
+///
+/// Headers which aren't explicitly mapped are matched against the column
+/// names, ignoring case. Headers which match nothing are skipped.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    headers: HashMap<String, Column>,
+}
+
+impl Mapping {
+    /// Create a mapping which only matches headers by column name
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the header `header` into `column`
+    #[must_use]
+    pub fn with(mut self, header: impl Into<String>, column: Column) -> Self {
+        self.headers.insert(header.into(), column);
+        self
+    }
+
+    fn resolve(&self, header: &str) -> Option<Column> {
+        self.headers
+            .get(header)
+            .copied()
+            .or_else(|| header.parse().ok())
+    }
+}
+
+/// The outcome of a CSV import
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// The tasks that were imported
+    pub summary: ImportSummary,
+    /// Rows which could not be imported
+    pub errors: Vec<RowError>,
+    /// Headers which weren't mapped to any column
+    pub ignored: Vec<String>,
+}
+
+/// A row which could not be imported
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("line {line}: {message}")]
+pub struct RowError {
+    /// The line number in the file, counting the header as line 1
+    pub line: usize,
+    /// What went wrong
+    pub message: String,
+}
+
+/// Render tasks as CSV with the given columns
+///
+/// # Errors
+///
+/// Returns an error if the CSV cannot be written.
+pub fn render<'a>(
+    tasks: impl IntoIterator<Item = &'a Task>,
+    columns: &[Column],
+) -> Result<String, Error> {
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    writer.write_record(columns.iter().map(|column| column.name()))?;
+    for task in tasks {
+        writer.write_record(columns.iter().map(|column| column.render(task)))?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| Error::Io(e.into_error()))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Import CSV rows into a [`Store`]
+///
+/// Rows with a `uuid` matching an existing task update that task. All other
+/// rows create a new task, and must have a description.
+///
+/// # Errors
+///
+/// Returns an error only if the header row cannot be read. Problems with
+/// individual rows are collected in the [`Report`].
+pub fn import(store: &mut Store, input: &str, mapping: &Mapping) -> Result<Report, Error> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(input.as_bytes());
+
+    let mut report = Report::default();
+    let mut columns = Vec::new();
+    for header in reader.headers()? {
+        let column = mapping.resolve(header);
+        if column.is_none() {
+            report.ignored.push(header.to_string());
+        }
+        columns.push(column);
+    }
+    let uuid_index = columns.iter().position(|c| *c == Some(Column::Uuid));
+
+    let mut tasks = Vec::new();
+    for record in reader.records() {
+        // The line the row starts on, which isn't its index once a quoted
+        // cell spans lines
+        let position = match &record {
+            Ok(record) => record.position(),
+            Err(e) => e.position(),
+        };
+        let line = position.map_or(0, |position| {
+            usize::try_from(position.line()).unwrap_or(usize::MAX)
+        });
+        let row = record
+            .map_err(|e| e.to_string())
+            .and_then(|record| parse_row(store, &columns, uuid_index, &record));
+        match row {
+            Ok(task) => tasks.push(task),
+            Err(message) => report.errors.push(RowError { line, message }),
+        }
+    }
+
+    report.summary = store.import(tasks);
+    Ok(report)
+}
+
+fn parse_row(
+    store: &Store,
+    columns: &[Option<Column>],
+    uuid_index: Option<usize>,
+    record: &::csv::StringRecord,
+) -> Result<Task, String> {
+    let uuid = uuid_index
+        .and_then(|index| record.get(index))
+        .map(str::trim)
+        .filter(|cell| !cell.is_empty())
+        .map(|cell| Uuid::parse_str(cell).map_err(|_| format!("invalid uuid '{cell}'")))
+        .transpose()?;
+
+    let existing = uuid.and_then(|uuid| store.get_by_uuid(uuid));
+    let original = match (existing, uuid) {
+        (Some(open), _) => open.task().clone(),
+        (None, Some(uuid)) => Task::new(String::new()).with_uuid(uuid),
+        (None, None) => Task::new(String::new()),
+    };
+
+    let Value::Object(mut fields) = serde_json::to_value(&original).map_err(|e| e.to_string())?
+    else {
+        unreachable!("tasks serialise to objects")
+    };
+    let mut unedited = fields.clone();
+    unedited.remove("modified");
+
+    for (column, cell) in columns.iter().zip(record) {
+        if let Some(column) = column {
+            column
+                .apply(cell, &mut fields)
+                .map_err(|e| format!("{column}: {e}"))?;
+        }
+    }
+
+    // An unmodified task exports its creation time as `modified`
+    if fields.get("modified") == fields.get("created") {
+        fields.remove("modified");
+    }
+
+    // A task closes when it's completed, unless the file says otherwise
+    if columns.contains(&Some(Column::Completed)) && !columns.contains(&Some(Column::End)) {
+        match fields.get("completed").cloned() {
+            Some(completed) => fields.insert("end".into(), completed),
+            None => fields.remove("end"),
+        };
+    }
+
+    let mut task: Task =
+        serde_json::from_value(Value::Object(fields)).map_err(|e| e.to_string())?;
+    if task.description().is_empty() {
+        return Err("new tasks need a description".into());
+    }
+    // A row edited in a spreadsheet keeps the `modified` it was exported with
+    if existing.is_some() && task != original {
+        let Value::Object(mut edited) = serde_json::to_value(&task).map_err(|e| e.to_string())?
+        else {
+            unreachable!("tasks serialise to objects")
+        };
+        edited.remove("modified");
+        if edited != unedited {
+            task.touch();
+        }
+    }
+
+    Ok(task)
+}
+
+fn parse_timestamp(cell: &str) -> Result<Option<DateTime<Utc>>, String> {
+    if cell.is_empty() {
+        return Ok(None);
+    }
+    DateTime::parse_from_rfc3339(cell)
+        .map(|t| t.to_utc())
+        .or_else(|_| {
+            NaiveDate::parse_from_str(cell, "%Y-%m-%d")
+                .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+        })
+        .map(Some)
+        .map_err(|_| format!("invalid date '{cell}'"))
+}
+
+/// Error type for CSV conversion
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The CSV is malformed
+    #[error("invalid CSV: {0}")]
+    Csv(#[from] ::csv::Error),
+    /// IO error
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::{Column, Mapping, import, render};
+    use crate::{OpenTask, Priority, Store, Task};
+
+    #[test_case("priority" => Ok(Column::Priority) ; "lowercase")]
+    #[test_case("Priority" => Ok(Column::Priority) ; "capitalised")]
+    #[test_case("urgency" => Err(()) ; "unknown")]
+    fn parse_column(input: &str) -> Result<Column, ()> {
+        input.parse().map_err(|_| ())
+    }
+
+    #[test]
+    fn render_selected_columns() {
+        let task = Task::new("write, then \"review\"".to_string()).with_priority(Priority::Two);
+        let output = render([&task], &[Column::Description, Column::Priority]).unwrap();
+        assert_eq!(
+            output,
+            "description,priority\n\"write, then \"\"review\"\"\",2\n"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut store = Store::new();
+        let task = Task::new("some task".to_string())
+            .with_priority(Priority::One)
+            .with_project("home".to_string())
+            .with_tag("a".to_string())
+            .with_tag("b".to_string())
+            .with_extra("due".to_string(), "tomorrow".into());
+        store.push(task.clone());
+
+        let output = render([&task], &Column::ALL).unwrap();
+
+        let mut fresh = Store::new();
+        let report = import(&mut fresh, &output, &Mapping::new()).unwrap();
+        assert!(report.errors.is_empty());
+        assert_eq!(report.summary.added, 1);
+        assert_eq!(fresh, store);
+
+        let report = import(&mut fresh, &output, &Mapping::new()).unwrap();
+        assert_eq!(report.summary.unchanged, 1);
+    }
+
+    #[test]
+    fn mapped_headers_and_row_errors() {
+        let input = "Title,Prio,Notes\nfirst,1,x\nsecond,7,y\n,2,z\nfourth,,w\n";
+        let mapping = Mapping::new()
+            .with("Title", Column::Description)
+            .with("Prio", Column::Priority);
+
+        let mut store = Store::new();
+        let report = import(&mut store, input, &mapping).unwrap();
+
+        assert_eq!(report.summary.added, 2);
+        assert_eq!(report.ignored, ["Notes"]);
+        assert_eq!(report.errors.len(), 2);
+        assert_eq!(report.errors[0].line, 3);
+        assert!(report.errors[0].message.contains("invalid priority"));
+        assert_eq!(report.errors[1].line, 4);
+
+        assert_eq!(store.get(0).unwrap().priority(), Some(Priority::One));
+        assert_eq!(store.get(1).unwrap().priority(), None);
+    }
+
+    #[test]
+    fn row_errors_after_multiline_cells() {
+        let input = "description,priority
+\"two\nlines\",1
+bad,7
+";
+        let report = import(&mut Store::new(), input, &Mapping::new()).unwrap();
+
+        assert_eq!(report.summary.added, 1);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].line, 4);
+    }
+
+    #[test]
+    fn edited_export_updates_tasks() {
+        let mut store = Store::new();
+        let id = store.push(Task::new("Water plants".to_string()).with_tag("home".to_string()));
+        store.get_mut(id).unwrap().task_mut().mark_started();
+        let before = store.clone();
+
+        let exported = render(store.iter().map(OpenTask::task), &Column::ALL).unwrap();
+        let report = import(&mut store, &exported, &Mapping::new()).unwrap();
+        assert_eq!(report.summary.unchanged, 1);
+        assert_eq!(store, before);
+
+        let edited = exported.replace("Water plants", "Water the plants");
+        let report = import(&mut store, &edited, &Mapping::new()).unwrap();
+        assert_eq!(report.summary.updated, 1);
+
+        let task = store.get(id).unwrap();
+        assert_eq!(task.description(), "Water the plants");
+        assert!(task.has_tag("home"));
+        assert_eq!(task.start(), before.get(id).unwrap().start());
+    }
+
+    #[test]
+    fn partial_update_by_uuid() {
+        let mut store = Store::new();
+        let task = Task::new("original".to_string()).with_priority(Priority::Four);
+        let id = store.push(task.clone());
+
+        let input = format!("uuid,priority,completed\n{},2,2024-01-02\n", task.uuid());
+        let report = import(&mut store, &input, &Mapping::new()).unwrap();
+
+        assert_eq!(report.summary.updated, 1);
+        let updated = store.get(id).unwrap();
+        assert_eq!(updated.description(), "original");
+        assert_eq!(updated.priority(), Some(Priority::Two));
+        assert!(updated.is_completed());
+        assert_eq!(updated.end(), updated.completed());
+        assert!(updated.modified() > task.modified());
+    }
+}
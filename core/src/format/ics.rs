@@ -0,0 +1,705 @@
+//! [iCalendar](https://www.rfc-editor.org/rfc/rfc5545) to-dos, for calendar and
+//! reminder apps
+//!
+//! | iCalendar               | [`Task`]                                        |
+//! |-------------------------|-------------------------------------------------|
+//! | `UID`                   | [`Task::uuid`]                                  |
+//! | `CREATED`, `DTSTAMP`    | [`Task::created`]                               |
+//! | `LAST-MODIFIED`         | [`Task::modified`]                              |
+//! | `SUMMARY`               | [`Task::description`]                           |
+//! | `COMPLETED`, `STATUS`   | [`Task::completed`]                             |
+//! | `STATUS`                | [`Task::deleted`], when `CANCELLED`             |
+//! | `DTSTART`, `STATUS`     | [`Task::start`], when `IN-PROCESS`              |
+//! | `PRIORITY`              | 1-2, 3-4, 5, 6-9 map to [`Priority::One`] - [`Priority::Four`] |
+//! | `CATEGORIES`            | [`Task::tags`]                                  |
+//! | `X-RUSTWARRIOR-PROJECT` | [`Task::project`]                               |
+//!
+//! Priorities are exported as 1, 3, 5 and 7. Any other properties of a
+//! `VTODO` are kept in [`Task::extra`], under their lower-cased name, and
+//! written back out on export. A `VALUE=DATE` value is kept as a date like
+//! `2024-02-01`, so filters can compare it, and exported with the parameter.
+//! Floating times are read as UTC. There is no time zone database to read
+//! times with a `TZID` with, so such properties are kept verbatim in
+//! [`Task::extra`], as `TZID=Europe/Paris:20240101T090000`, and exported with
+//! the parameter. Other property parameters are not kept.
+//!
+//! Apps that don't use UUIDs as `UID`s are given a UUID derived from their
+//! `UID`, so importing the same file again updates tasks rather than
+//! duplicating them. The original `UID` is kept so that it is exported
+//! unchanged.
+
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{Priority, Task};
+
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const FLOATING_DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+const DATE_FORMAT: &str = "%Y%m%d";
+const ISO_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The longest content line allowed before it must be folded, in octets
+const MAX_LINE_LENGTH: usize = 75;
+
+const PRODUCT_ID: &str = "-//RustWarrior//rw//EN";
+const PROJECT_PROPERTY: &str = "X-RUSTWARRIOR-PROJECT";
+
+/// Extra attribute holding a `UID` which isn't a UUID
+const UID_KEY: &str = "uid";
+const STATUS_KEY: &str = "status";
+const CANCELLED: &str = "CANCELLED";
+/// How times with a `TZID` start in [`Task::extra`]
+const TZID_PREFIX: &str = "TZID=";
+
+/// Parse the `VTODO` components of an iCalendar file
+///
+/// Other components, such as events, are skipped.
+///
+/// # Errors
+///
+/// Returns an error if a to-do has no `SUMMARY`, a property has an invalid
+/// value, or a component is not closed.
+pub fn parse(input: &str) -> Result<Vec<Task>, Error> {
+    let mut tasks = Vec::new();
+    let mut todo: Option<Todo> = None;
+    // Components nested inside a to-do, such as alarms, are skipped
+    let mut nested = Vec::new();
+
+    for (line, content) in unfold(input) {
+        let (name, parameters, value) = split_property(&content).ok_or_else(|| Error {
+            line,
+            kind: ErrorKind::InvalidLine(content.clone()),
+        })?;
+        let name = name.to_ascii_uppercase();
+        let parameter = |wanted: &str| {
+            parameters
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(wanted))
+                .map(|(_, value)| *value)
+        };
+
+        match (name.as_str(), todo.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VTODO") => {
+                todo = Some(Todo {
+                    line,
+                    properties: Vec::new(),
+                });
+            }
+            ("BEGIN", Some(_)) => nested.push(value.to_ascii_uppercase()),
+            ("END", Some(_)) if !nested.is_empty() => {
+                nested.pop();
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VTODO") => {
+                if let Some(todo) = todo.take() {
+                    tasks.push(todo.into_task()?);
+                }
+            }
+            (_, Some(todo)) if nested.is_empty() => {
+                todo.properties.push(Property {
+                    line,
+                    name,
+                    value: value.to_string(),
+                    is_date: parameter("VALUE")
+                        .is_some_and(|value| value.eq_ignore_ascii_case("DATE")),
+                    zone: parameter("TZID").map(ToString::to_string),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    match todo {
+        Some(todo) => Err(Error {
+            line: todo.line,
+            kind: ErrorKind::Unterminated,
+        }),
+        None => Ok(tasks),
+    }
+}
+
+/// Render tasks as an iCalendar file of `VTODO` components
+#[must_use]
+pub fn render<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{PRODUCT_ID}"),
+    ];
+    for task in tasks {
+        lines.push("BEGIN:VTODO".to_string());
+        lines.extend(
+            render_properties(task)
+                .into_iter()
+                .map(|(name, value)| format!("{name}:{value}")),
+        );
+        lines.push("END:VTODO".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().fold(String::new(), |mut output, line| {
+        fold(line, &mut output);
+        output
+    })
+}
+
+struct Todo {
+    line: usize,
+    properties: Vec<Property>,
+}
+
+struct Property {
+    line: usize,
+    name: String,
+    value: String,
+    /// Whether the value is a `VALUE=DATE` date, without a time
+    is_date: bool,
+    /// The `TZID` parameter of a time in a time zone
+    zone: Option<String>,
+}
+
+impl Property {
+    /// The value as an extra attribute. Dates are kept as `YYYY-MM-DD`,
+    /// which filters understand, and times in a time zone verbatim with their
+    /// `TZID`
+    fn text(&self) -> Result<Value, Error> {
+        if let Some(zone) = &self.zone {
+            return Ok(format!("{TZID_PREFIX}{zone}:{}", self.value).into());
+        }
+        if !self.is_date {
+            return Ok(unescape(&self.value).into());
+        }
+        NaiveDate::parse_from_str(&self.value, DATE_FORMAT)
+            .map(|date| date.format(ISO_DATE_FORMAT).to_string().into())
+            .map_err(|_| self.invalid_date())
+    }
+
+    /// The value as a UTC time
+    fn date(&self) -> Result<DateTime<Utc>, Error> {
+        parse_date(&self.value).ok_or_else(|| self.invalid_date())
+    }
+
+    fn invalid_date(&self) -> Error {
+        Error {
+            line: self.line,
+            kind: ErrorKind::InvalidDate {
+                property: self.name.clone(),
+                value: self.value.clone(),
+            },
+        }
+    }
+}
+
+impl Todo {
+    fn into_task(self) -> Result<Task, Error> {
+        let mut task = Map::new();
+        let mut extra = Map::new();
+        let mut tags = BTreeSet::new();
+        let mut status = None;
+        let mut start = None;
+        let mut completed = None;
+        let mut modified = None;
+        let mut stamp = None;
+
+        for property in self.properties {
+            let text = property.text();
+            let value = property.value.as_str();
+            let date = || property.date();
+            let error = |kind| Error {
+                line: property.line,
+                kind,
+            };
+
+            match property.name.as_str() {
+                // Times in a time zone are kept verbatim, whatever the property
+                name if property.zone.is_some() => {
+                    extra.insert(name.to_ascii_lowercase(), text?);
+                }
+                "UID" => {
+                    let uid = unescape(value);
+                    let parsed = Uuid::parse_str(&uid).unwrap_or_else(|_| {
+                        extra.insert(UID_KEY.into(), uid.clone().into());
+                        Uuid::new_v5(&Uuid::NAMESPACE_OID, uid.as_bytes())
+                    });
+                    task.insert("uuid".into(), parsed.to_string().into());
+                }
+                "SUMMARY" => {
+                    task.insert("description".into(), unescape(value).into());
+                }
+                "CREATED" => {
+                    task.insert("created".into(), date_value(date()?));
+                }
+                "LAST-MODIFIED" => modified = Some(date()?),
+                "COMPLETED" => completed = Some(date()?),
+                "DTSTART" => start = Some((date()?, text?)),
+                "STATUS" => status = Some(value.to_ascii_uppercase()),
+                "PRIORITY" => {
+                    if let Some(priority) = parse_priority(value).map_err(error)? {
+                        task.insert("priority".into(), u8::from(priority).into());
+                    }
+                }
+                "CATEGORIES" => tags.extend(split_list(value)),
+                PROJECT_PROPERTY => {
+                    task.insert("project".into(), unescape(value).into());
+                }
+                "DTSTAMP" => stamp = Some(date()?),
+                name => {
+                    extra.insert(name.to_ascii_lowercase(), text?);
+                }
+            }
+        }
+
+        if !task.contains_key("description") {
+            return Err(Error {
+                line: self.line,
+                kind: ErrorKind::MissingSummary,
+            });
+        }
+        task.entry("uuid")
+            .or_insert_with(|| Uuid::new_v4().to_string().into());
+        let created = task
+            .entry("created")
+            .or_insert_with(|| date_value(stamp.unwrap_or_else(Utc::now)))
+            .clone();
+
+        match status.as_deref() {
+            Some("COMPLETED") => {
+                let completed = completed.or(modified).unwrap_or_else(Utc::now);
+                task.insert("completed".into(), date_value(completed));
+                task.insert("end".into(), date_value(completed));
+            }
+            Some(CANCELLED) => {
+                let deleted = modified.unwrap_or_else(Utc::now);
+                task.insert("deleted".into(), date_value(deleted));
+                task.insert("end".into(), date_value(deleted));
+            }
+            Some("IN-PROCESS") if start.is_some() => {
+                let (start, _) = start.take().expect("checked is_some");
+                task.insert("start".into(), date_value(start));
+            }
+            Some("NEEDS-ACTION" | "IN-PROCESS") | None => {}
+            Some(other) => {
+                extra.insert(STATUS_KEY.into(), other.into());
+            }
+        }
+        if let Some((_, value)) = start {
+            extra.insert("dtstart".into(), value);
+        }
+
+        // An unmodified task exports its creation time as `LAST-MODIFIED`
+        if let Some(modified) = modified.map(date_value).filter(|m| *m != created) {
+            task.insert("modified".into(), modified);
+        }
+        if !tags.is_empty() {
+            task.insert("tags".into(), tags.into_iter().collect());
+        }
+        if !extra.is_empty() {
+            task.insert("extra".into(), Value::Object(extra));
+        }
+
+        Ok(serde_json::from_value(Value::Object(task)).expect("built from a task's own fields"))
+    }
+}
+
+fn render_properties(task: &Task) -> Vec<(String, String)> {
+    let uid = task
+        .extra()
+        .get(UID_KEY)
+        .and_then(Value::as_str)
+        .map_or_else(|| task.uuid().to_string(), ToString::to_string);
+
+    let status = if task.is_completed() {
+        "COMPLETED"
+    } else if task.is_started() {
+        "IN-PROCESS"
+    } else if task.is_deleted() {
+        CANCELLED
+    } else {
+        "NEEDS-ACTION"
+    };
+
+    let mut properties = vec![
+        ("UID".to_string(), escape(&uid)),
+        ("DTSTAMP".to_string(), format_date(task.modified())),
+        ("CREATED".to_string(), format_date(task.created())),
+        ("LAST-MODIFIED".to_string(), format_date(task.modified())),
+        ("SUMMARY".to_string(), escape(task.description())),
+        ("STATUS".to_string(), status.to_string()),
+    ];
+
+    if let Some(completed) = task.completed() {
+        properties.push(("COMPLETED".to_string(), format_date(completed)));
+    }
+    if let Some(start) = task.start() {
+        properties.push(("DTSTART".to_string(), format_date(start)));
+    }
+    if let Some(priority) = task.priority() {
+        properties.push((
+            "PRIORITY".to_string(),
+            format_priority(priority).to_string(),
+        ));
+    }
+    if !task.tags().is_empty() {
+        let tags: Vec<String> = task.tags().iter().map(|tag| escape(tag)).collect();
+        properties.push(("CATEGORIES".to_string(), tags.join(",")));
+    }
+    if let Some(project) = task.project() {
+        properties.push((PROJECT_PROPERTY.to_string(), escape(project)));
+    }
+
+    for (key, value) in task.extra() {
+        let name = key.to_ascii_uppercase();
+        let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        let written =
+            key == UID_KEY || key == STATUS_KEY || properties.iter().any(|(n, _)| *n == name);
+        if let (true, false, Some(value)) = (valid, written, value.as_str()) {
+            if let Some((zone, time)) = value
+                .strip_prefix(TZID_PREFIX)
+                .and_then(|zoned| zoned.split_once(':'))
+            {
+                properties.push((format!("{name};TZID={zone}"), time.to_string()));
+                continue;
+            }
+            match NaiveDate::parse_from_str(value, ISO_DATE_FORMAT) {
+                Ok(date) => properties.push((
+                    format!("{name};VALUE=DATE"),
+                    date.format(DATE_FORMAT).to_string(),
+                )),
+                Err(_) => properties.push((name, escape(value))),
+            }
+        }
+    }
+
+    properties
+}
+
+/// Join folded lines, returning each content line with its line number
+fn unfold(input: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some((_, previous))) => previous.push_str(continuation),
+            _ if line.trim().is_empty() => {}
+            _ => lines.push((index + 1, line.to_string())),
+        }
+    }
+    lines
+}
+
+/// Write a content line, folding it so that no line exceeds
+/// [`MAX_LINE_LENGTH`] octets
+fn fold(line: &str, output: &mut String) {
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            output.push_str("\r\n ");
+            length = 1;
+        }
+        output.push(c);
+        length += c.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+/// A content line's name, its parameters as names and values, and its value
+type ContentLine<'a> = (&'a str, Vec<(&'a str, &'a str)>, &'a str);
+
+/// Split a content line into its name, parameters and value
+fn split_property(line: &str) -> Option<ContentLine<'_>> {
+    let colon = find_unquoted(line, ':')?;
+    let (name, value) = (&line[..colon], &line[colon + 1..]);
+
+    let mut parts = Vec::new();
+    let mut rest = name;
+    while let Some(semicolon) = find_unquoted(rest, ';') {
+        parts.push(&rest[..semicolon]);
+        rest = &rest[semicolon + 1..];
+    }
+    parts.push(rest);
+
+    let name = parts[0];
+    let parameters = parts[1..]
+        .iter()
+        .filter_map(|parameter| parameter.split_once('='))
+        .map(|(name, value)| (name, value.trim_matches('"')))
+        .collect();
+    (!name.is_empty()).then_some((name, parameters, value))
+}
+
+/// The index of the first `target` outside double quotes
+fn find_unquoted(text: &str, target: char) -> Option<usize> {
+    let mut quoted = false;
+    text.char_indices().find_map(|(index, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        c if c == target && !quoted => Some(index),
+        _ => None,
+    })
+}
+
+/// Split a list value on unescaped commas
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = vec![String::new()];
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            ',' => items.push(String::new()),
+            '\\' => items
+                .last_mut()
+                .expect("never empty")
+                .push(unescape_char(chars.next().unwrap_or('\\'))),
+            c => items.last_mut().expect("never empty").push(c),
+        }
+    }
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        output.push(match c {
+            '\\' => unescape_char(chars.next().unwrap_or('\\')),
+            c => c,
+        });
+    }
+    output
+}
+
+const fn unescape_char(c: char) -> char {
+    match c {
+        'n' | 'N' => '\n',
+        c => c,
+    }
+}
+
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value, DATE_TIME_FORMAT)
+        .or_else(|_| NaiveDateTime::parse_from_str(value, FLOATING_DATE_TIME_FORMAT))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, DATE_FORMAT).map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .map(|date| date.and_utc())
+        .ok()
+}
+
+fn format_date(date: DateTime<Utc>) -> String {
+    date.format(DATE_TIME_FORMAT).to_string()
+}
+
+fn date_value(date: DateTime<Utc>) -> Value {
+    Value::String(date.to_rfc3339())
+}
+
+/// Parse an iCalendar priority, where 0 means undefined
+fn parse_priority(value: &str) -> Result<Option<Priority>, ErrorKind> {
+    match value.trim().parse::<u8>() {
+        Ok(0) => Ok(None),
+        Ok(1..=2) => Ok(Some(Priority::One)),
+        Ok(3..=4) => Ok(Some(Priority::Two)),
+        Ok(5) => Ok(Some(Priority::Three)),
+        Ok(6..=9) => Ok(Some(Priority::Four)),
+        _ => Err(ErrorKind::InvalidPriority(value.to_string())),
+    }
+}
+
+const fn format_priority(priority: Priority) -> u8 {
+    match priority {
+        Priority::One => 1,
+        Priority::Two => 3,
+        Priority::Three => 5,
+        Priority::Four => 7,
+    }
+}
+
+/// An error parsing an iCalendar file
+#[derive(Debug, thiserror::Error)]
+#[error("line {line}: {kind}")]
+pub struct Error {
+    /// The line number, starting from 1
+    pub line: usize,
+    /// What went wrong
+    pub kind: ErrorKind,
+}
+
+/// The kinds of error that can occur parsing an iCalendar file
+#[derive(Debug, thiserror::Error)]
+pub enum ErrorKind {
+    /// The line is not a `NAME:value` property
+    #[error("invalid content line '{0}'")]
+    InvalidLine(String),
+    /// The to-do has no `SUMMARY`
+    #[error("to-do has no SUMMARY")]
+    MissingSummary,
+    /// The to-do has no matching `END:VTODO`
+    #[error("to-do is not terminated by END:VTODO")]
+    Unterminated,
+    /// A date property has an unparseable value
+    #[error("invalid date for {property}: '{value}'")]
+    InvalidDate {
+        /// The property name
+        property: String,
+        /// The offending value
+        value: String,
+    },
+    /// The `PRIORITY` is not between 0 and 9
+    #[error("invalid PRIORITY '{0}'. Expected 0-9")]
+    InvalidPriority(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use test_case::test_case;
+    use uuid::uuid;
+
+    use super::{parse, render};
+    use crate::Priority;
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+PRODID:-//Example//Reminders//EN\r
+BEGIN:VEVENT\r
+UID:event@example.com\r
+SUMMARY:Not a to-do\r
+END:VEVENT\r
+BEGIN:VTODO\r
+UID:5b1b0fb2-6a1f-4a83-9f4b-46f2e3b2e0a1\r
+DTSTAMP:20240110T090000Z\r
+CREATED:20240101T090000Z\r
+LAST-MODIFIED:20240102T090000Z\r
+SUMMARY:Pay rent\\, then\r
+  file the receipt\r
+PRIORITY:2\r
+CATEGORIES:bills,monthly\r
+DUE;VALUE=DATE:20240201\r
+BEGIN:VALARM\r
+ACTION:DISPLAY\r
+TRIGGER:-PT15M\r
+END:VALARM\r
+END:VTODO\r
+BEGIN:VTODO\r
+UID:passport@example.com\r
+CREATED:20240301T090000Z\r
+SUMMARY:Renew passport\r
+STATUS:COMPLETED\r
+COMPLETED:20240305T120000Z\r
+END:VTODO\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn parse_calendar() {
+        let tasks = parse(CALENDAR).unwrap();
+        assert_eq!(tasks.len(), 2);
+
+        let rent = &tasks[0];
+        assert_eq!(rent.uuid(), uuid!("5b1b0fb2-6a1f-4a83-9f4b-46f2e3b2e0a1"));
+        assert_eq!(rent.description(), "Pay rent, then file the receipt");
+        assert_eq!(rent.created().to_rfc3339(), "2024-01-01T09:00:00+00:00");
+        assert_eq!(rent.modified().to_rfc3339(), "2024-01-02T09:00:00+00:00");
+        assert_eq!(rent.priority(), Some(Priority::One));
+        assert!(rent.has_tag("bills") && rent.has_tag("monthly"));
+        assert_eq!(rent.extra().get("due"), Some(&json!("2024-02-01")));
+        assert!(!rent.extra().contains_key("action"));
+
+        let passport = &tasks[1];
+        assert!(passport.is_completed());
+        assert_eq!(passport.end(), passport.completed());
+        assert_eq!(
+            passport.extra().get("uid"),
+            Some(&json!("passport@example.com"))
+        );
+        assert_eq!(parse(CALENDAR).unwrap()[1].uuid(), passport.uuid());
+    }
+
+    #[test_case("0" => None ; "undefined")]
+    #[test_case("1" => Some(Priority::One) ; "one")]
+    #[test_case("4" => Some(Priority::Two) ; "four")]
+    #[test_case("5" => Some(Priority::Three) ; "five")]
+    #[test_case("9" => Some(Priority::Four) ; "nine")]
+    fn priority(value: &str) -> Option<Priority> {
+        let input = format!("BEGIN:VTODO\nSUMMARY:task\nPRIORITY:{value}\nEND:VTODO\n");
+        parse(&input).unwrap()[0].priority()
+    }
+
+    #[test_case("BEGIN:VTODO\nPRIORITY:1\nEND:VTODO" => 1 ; "missing summary")]
+    #[test_case("BEGIN:VTODO\nSUMMARY:task\nPRIORITY:10\nEND:VTODO" => 3 ; "invalid priority")]
+    #[test_case("BEGIN:VTODO\nSUMMARY:task\nCOMPLETED:soon\nEND:VTODO" => 3 ; "invalid date")]
+    #[test_case("BEGIN:VTODO\nSUMMARY:task" => 1 ; "unterminated")]
+    #[test_case("BEGIN:VTODO\nSUMMARY:task\nDUE;VALUE=DATE:soon\nEND:VTODO" => 3 ; "invalid due date")]
+    fn error_line(input: &str) -> usize {
+        parse(input).unwrap_err().line
+    }
+
+    #[test]
+    fn cancelled() {
+        let input = "BEGIN:VTODO\nSUMMARY:task\nSTATUS:CANCELLED\nEND:VTODO\n";
+        let tasks = parse(input).unwrap();
+        assert!(tasks[0].is_deleted());
+        assert!(render(&tasks).contains("STATUS:CANCELLED\r\n"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let tasks = parse(CALENDAR).unwrap();
+        let exported = render(&tasks);
+        assert_eq!(parse(&exported).unwrap(), tasks);
+
+        assert!(exported.contains("UID:passport@example.com\r\n"));
+        assert!(exported.contains("PRIORITY:1\r\n"));
+        assert!(exported.contains("DUE;VALUE=DATE:20240201\r\n"));
+        assert!(exported.lines().all(|line| line.len() <= 75));
+    }
+
+    #[test]
+    fn time_zones_are_kept() {
+        let tasks = parse(
+            "BEGIN:VTODO
+CREATED:20240101T090000Z
+SUMMARY:task
+DUE;TZID=Europe/Paris:20240101T090000
+DTSTART;TZID=Europe/Paris:20231231T090000
+STATUS:IN-PROCESS
+END:VTODO
+",
+        )
+        .unwrap();
+        assert_eq!(
+            tasks[0].extra().get("due"),
+            Some(&json!("TZID=Europe/Paris:20240101T090000"))
+        );
+        assert!(!tasks[0].is_started());
+
+        let exported = render(&tasks);
+        assert!(exported.contains("DUE;TZID=Europe/Paris:20240101T090000\r\n"));
+        assert!(exported.contains("DTSTART;TZID=Europe/Paris:20231231T090000\r\n"));
+        assert_eq!(parse(&exported).unwrap(), tasks);
+    }
+
+    #[test]
+    fn long_lines_are_folded() {
+        let tasks = parse(&format!(
+            "BEGIN:VTODO\nCREATED:20240101T090000Z\nSUMMARY:{}\nEND:VTODO\n",
+            "é".repeat(100)
+        ))
+        .unwrap();
+        let exported = render(&tasks);
+
+        assert!(exported.lines().all(|line| line.len() <= 75));
+        assert_eq!(parse(&exported).unwrap(), tasks);
+    }
+}
@@ -0,0 +1,376 @@
+//! GitHub-style Markdown checklists, as used in meeting notes and PR
+//! descriptions
+//!
+//! ```markdown
+//! ## Priority 1
+//!
+//! - [ ] Pay rent <!-- uuid:5b1b0fb2-6a1f-4a83-9f4b-46f2e3b2e0a1 -->
+//!   - [x] Find chequebook <!-- uuid:0d5b3d7f-1c3a-4cbb-9d3e-7c5a8c8c2e11 -->
+//!
+//! ## No priority
+//!
+//! - [ ] Renew passport <!-- uuid:9f6e0c1d-8f1e-4a0e-a3b4-1b2f7c9d0e22 -->
+//! ```
+//!
+//! Tasks are exported grouped by priority, with subtasks indented under the
+//! task they belong to. A subtask records its parent's UUID in
+//! [`Task::extra`], under `parent`.
+//!
+//! On import, each `- [ ]` or `- [x]` item becomes a task, and every other
+//! line is ignored. Top-level items take their priority from a preceding
+//! `Priority N` heading. Exported items carry their UUID in an HTML comment,
+//! which GitHub doesn't display. Items written by hand are given a UUID
+//! derived from their description and parent, so importing the same notes
+//! twice updates tasks rather than duplicating them.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{ImportSummary, Priority, Store, Task};
+
+/// Extra attribute holding the UUID of a subtask's parent
+const PARENT_KEY: &str = "parent";
+const UUID_PREFIX: &str = "<!-- uuid:";
+const UUID_SUFFIX: &str = "-->";
+/// Indentation per level of nesting on export
+const INDENT: &str = "  ";
+
+/// Render tasks as Markdown checklists, grouped by priority
+#[must_use]
+pub fn render<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> String {
+    let tasks: Vec<&Task> = tasks.into_iter().collect();
+    let by_uuid: HashMap<Uuid, &Task> = tasks.iter().map(|task| (task.uuid(), *task)).collect();
+
+    let mut children: HashMap<Uuid, Vec<&Task>> = HashMap::new();
+    let mut roots = Vec::new();
+    for task in &tasks {
+        match parent(task).filter(|parent| by_uuid.contains_key(parent)) {
+            Some(parent) => children.entry(parent).or_default().push(task),
+            None => roots.push(*task),
+        }
+    }
+
+    // Subtasks whose parents form a cycle are never reached from a root, so
+    // they are promoted to the top level
+    let mut reached = HashSet::new();
+    for root in &roots {
+        reach(root, &children, &mut reached);
+    }
+    for task in &tasks {
+        if !reached.contains(&task.uuid()) {
+            roots.push(task);
+            reach(task, &children, &mut reached);
+        }
+    }
+
+    let groups = [
+        Some(Priority::One),
+        Some(Priority::Two),
+        Some(Priority::Three),
+        Some(Priority::Four),
+        None,
+    ];
+    let mut sections = Vec::new();
+    for priority in groups {
+        let mut section = heading(priority);
+        let mut rendered = HashSet::new();
+        for root in roots.iter().filter(|root| root.priority() == priority) {
+            render_item(root, 0, &children, &mut rendered, &mut section);
+        }
+        if !rendered.is_empty() {
+            sections.push(section);
+        }
+    }
+
+    sections.join("\n")
+}
+
+/// Import the checklist items of a Markdown file into a [`Store`]
+///
+/// Items which already exist, matched by UUID, have their description,
+/// completion, priority and parent updated. Everything else about them is left
+/// alone.
+pub fn import(store: &mut Store, input: &str) -> ImportSummary {
+    let mut tasks = Vec::new();
+    let mut priority = None;
+    // The indentation and UUID of each item enclosing the current line
+    let mut enclosing: Vec<(usize, Uuid)> = Vec::new();
+
+    for line in input.lines() {
+        if let Some(title) = parse_heading(line) {
+            priority = parse_priority_heading(title);
+            enclosing.clear();
+            continue;
+        }
+        let Some(item) = parse_item(line) else {
+            continue;
+        };
+
+        while enclosing
+            .last()
+            .is_some_and(|(indent, _)| *indent >= item.indent)
+        {
+            enclosing.pop();
+        }
+        let parent = enclosing.last().map(|(_, uuid)| *uuid);
+        let uuid = item.uuid.unwrap_or_else(|| {
+            let key = format!("{}/{}", parent.unwrap_or_default(), item.description);
+            Uuid::new_v5(&Uuid::NAMESPACE_OID, key.as_bytes())
+        });
+        enclosing.push((item.indent, uuid));
+
+        let existing = store.get_by_uuid(uuid).map(|open| open.task().clone());
+        let mut task =
+            existing.unwrap_or_else(|| Task::new(item.description.clone()).with_uuid(uuid));
+
+        if *task.description() != item.description {
+            task.set_description(item.description);
+        }
+        match (item.checked, task.is_completed()) {
+            (true, false) => task.mark_completed(),
+            (false, true) => task.mark_active(),
+            _ => {}
+        }
+        if parent.is_none() && task.priority() != priority {
+            task.set_priority(priority);
+        }
+        match parent.map(|parent| Value::String(parent.to_string())) {
+            Some(parent) if task.extra().get(PARENT_KEY) != Some(&parent) => {
+                task.set_extra(PARENT_KEY.to_string(), parent);
+            }
+            None if task.extra().contains_key(PARENT_KEY) => {
+                task.remove_extra(PARENT_KEY);
+            }
+            _ => {}
+        }
+
+        tasks.push(task);
+    }
+
+    store.import(tasks)
+}
+
+fn parent(task: &Task) -> Option<Uuid> {
+    task.extra()
+        .get(PARENT_KEY)
+        .and_then(Value::as_str)
+        .and_then(|parent| Uuid::parse_str(parent).ok())
+}
+
+fn reach(task: &Task, children: &HashMap<Uuid, Vec<&Task>>, reached: &mut HashSet<Uuid>) {
+    if reached.insert(task.uuid()) {
+        for child in children.get(&task.uuid()).into_iter().flatten() {
+            reach(child, children, reached);
+        }
+    }
+}
+
+fn render_item(
+    task: &Task,
+    depth: usize,
+    children: &HashMap<Uuid, Vec<&Task>>,
+    rendered: &mut HashSet<Uuid>,
+    output: &mut String,
+) {
+    if !rendered.insert(task.uuid()) {
+        return;
+    }
+
+    let check = if task.is_completed() { 'x' } else { ' ' };
+    let description = task.description().replace('\n', " ");
+    writeln!(
+        output,
+        "{}- [{check}] {description} {UUID_PREFIX}{} {UUID_SUFFIX}",
+        INDENT.repeat(depth),
+        task.uuid(),
+    )
+    .expect("writing to a String can't fail");
+
+    for child in children.get(&task.uuid()).into_iter().flatten() {
+        render_item(child, depth + 1, children, rendered, output);
+    }
+}
+
+fn heading(priority: Option<Priority>) -> String {
+    priority.map_or_else(
+        || "## No priority\n\n".to_string(),
+        |priority| format!("## Priority {priority}\n\n"),
+    )
+}
+
+fn parse_heading(line: &str) -> Option<&str> {
+    let title = line.trim_start().trim_start_matches('#');
+    let is_heading = line.trim_start().starts_with('#') && title.starts_with(' ');
+    is_heading.then(|| title.trim())
+}
+
+fn parse_priority_heading(title: &str) -> Option<Priority> {
+    let (word, value) = title.split_once(' ')?;
+    word.eq_ignore_ascii_case("priority")
+        .then(|| value.trim().parse().ok())
+        .flatten()
+}
+
+struct Item {
+    indent: usize,
+    checked: bool,
+    description: String,
+    uuid: Option<Uuid>,
+}
+
+/// Parse a `- [ ] description` line, with any list marker
+fn parse_item(line: &str) -> Option<Item> {
+    let content = line.trim_start();
+    let indent = line[..line.len() - content.len()]
+        .chars()
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum();
+
+    let rest = strip_list_marker(content)?;
+    let checked = match rest.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    let mut text = rest[3..].trim();
+
+    let mut uuid = None;
+    if let Some((before, marker)) = text.rsplit_once(UUID_PREFIX) {
+        if let Some(Ok(parsed)) = marker
+            .strip_suffix(UUID_SUFFIX)
+            .map(|marker| Uuid::parse_str(marker.trim()))
+        {
+            uuid = Some(parsed);
+            text = before.trim_end();
+        }
+    }
+
+    (!text.is_empty()).then(|| Item {
+        indent,
+        checked,
+        description: text.to_string(),
+        uuid,
+    })
+}
+
+fn strip_list_marker(content: &str) -> Option<&str> {
+    let rest = content.strip_prefix(['-', '*', '+']).or_else(|| {
+        let digits = content.len()
+            - content
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+        (digits > 0)
+            .then(|| content[digits..].strip_prefix(['.', ')']))
+            .flatten()
+    })?;
+    rest.strip_prefix([' ', '\t']).map(str::trim_start)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::{import, render};
+    use crate::{OpenTask, Priority, Store, Task};
+
+    const NOTES: &str = "# Standup
+
+Some discussion, then:
+
+- [ ] Book the venue
+  - [x] Get quotes
+  - [ ] Pay deposit
+    * [ ] Ask finance
+- a plain bullet
+- [X] Send the minutes
+
+## Priority 2
+
+1. [ ] Fix the build
+";
+
+    fn tasks(store: &Store) -> Vec<&Task> {
+        store.iter().map(OpenTask::task).collect()
+    }
+
+    #[test]
+    fn import_notes() {
+        let mut store = Store::new();
+        let summary = import(&mut store, NOTES);
+        assert_eq!(summary.added, 6);
+
+        let tasks = tasks(&store);
+        let descriptions: Vec<&str> = tasks.iter().map(|t| t.description().as_str()).collect();
+        assert_eq!(
+            descriptions,
+            [
+                "Book the venue",
+                "Get quotes",
+                "Pay deposit",
+                "Ask finance",
+                "Send the minutes",
+                "Fix the build"
+            ]
+        );
+
+        assert!(tasks[1].is_completed() && tasks[4].is_completed());
+        assert!(!tasks[0].is_completed());
+        assert_eq!(super::parent(tasks[1]), Some(tasks[0].uuid()));
+        assert_eq!(super::parent(tasks[3]), Some(tasks[2].uuid()));
+        assert_eq!(super::parent(tasks[4]), None);
+        assert_eq!(tasks[5].priority(), Some(Priority::Two));
+        assert_eq!(tasks[0].priority(), None);
+    }
+
+    #[test]
+    fn reimport_is_idempotent() {
+        let mut store = Store::new();
+        import(&mut store, NOTES);
+        let before = store.clone();
+
+        let summary = import(&mut store, NOTES);
+        assert_eq!(summary.added, 0);
+        assert_eq!(summary.unchanged, 6);
+        assert_eq!(store, before);
+
+        let checked = NOTES.replace("- [ ] Book the venue", "- [x] Book the venue");
+        let summary = import(&mut store, &checked);
+        assert_eq!(summary.updated, 1);
+        assert!(store.get(0).unwrap().is_completed());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut store = Store::new();
+        import(&mut store, NOTES);
+
+        let exported = render(tasks(&store));
+        assert!(exported.starts_with("## Priority 2\n\n- [ ] Fix the build <!-- uuid:"));
+        assert!(exported.contains("\n    - [ ] Ask finance <!-- uuid:"));
+
+        let mut reimported = store.clone();
+        let summary = import(&mut reimported, &exported);
+        assert_eq!(summary.unchanged, 6);
+        assert_eq!(reimported, store);
+
+        let mut fresh = Store::new();
+        import(&mut fresh, &exported);
+        assert_eq!(tasks(&fresh).len(), 6);
+        assert_eq!(render(tasks(&fresh)), exported);
+    }
+
+    #[test_case("- [ ] task" => Some((0, false)) ; "unchecked")]
+    #[test_case("    * [x] task" => Some((4, true)) ; "indented star")]
+    #[test_case("\t12) [X] task" => Some((4, true)) ; "ordered")]
+    #[test_case("- [ ]" => None ; "empty")]
+    #[test_case("- [-] task" => None ; "not a checkbox")]
+    #[test_case("-[ ] task" => None ; "no space")]
+    fn parse_item(line: &str) -> Option<(usize, bool)> {
+        super::parse_item(line).map(|item| (item.indent, item.checked))
+    }
+}
@@ -0,0 +1,593 @@
+//! [Org mode](https://orgmode.org) headlines, for Emacs users
+//!
+//! ```org
+//! * TODO [#A] Pay rent :bills:monthly:
+//!   :PROPERTIES:
+//!   :UUID:     5b1b0fb2-6a1f-4a83-9f4b-46f2e3b2e0a1
+//!   :CREATED:  [2024-01-01 Mon 09:00]
+//!   :PROJECT:  home
+//!   :END:
+//! * DONE Renew passport
+//!   CLOSED: [2024-03-05 Tue 12:00]
+//!   :PROPERTIES:
+//!   :UUID:     0d5b3d7f-1c3a-4cbb-9d3e-7c5a8c8c2e11
+//!   :CREATED:  [2024-03-01 Fri 09:00]
+//!   :END:
+//! ```
+//!
+//! | Org                     | [`Task`]                                    |
+//! |-------------------------|---------------------------------------------|
+//! | `TODO` / `DONE`         | [`Task::is_completed`]                      |
+//! | `CLOSED:`               | [`Task::completed`]                         |
+//! | `[#A]` - `[#D]`         | [`Priority::One`] - [`Priority::Four`]      |
+//! | headline title          | [`Task::description`]                       |
+//! | `:tag:` list            | [`Task::tags`]                              |
+//! | `:UUID:`                | [`Task::uuid`]                              |
+//! | `:CREATED:`             | [`Task::created`]                           |
+//! | `:PROJECT:`             | [`Task::project`]                           |
+//! | other properties        | [`Task::extra`]                             |
+//!
+//! Headlines without a `TODO` or `DONE` keyword, and the text under
+//! headlines, are ignored on import. Timestamps are UTC, to the minute.
+//!
+//! Importing updates tasks in place, matched by `:UUID:`, so a file can be
+//! exported, edited in Emacs and imported again. Headlines added by hand are
+//! given a UUID derived from their title, so importing them twice does not
+//! create duplicates.
+
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{ImportSummary, OpenTask, Priority, Store, Task};
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %a %H:%M";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMAT: &str = "%H:%M";
+
+const UUID_PROPERTY: &str = "UUID";
+const CREATED_PROPERTY: &str = "CREATED";
+const PROJECT_PROPERTY: &str = "PROJECT";
+
+/// Render tasks as an Org file of `TODO` and `DONE` headlines
+#[must_use]
+pub fn render<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> String {
+    tasks.into_iter().fold(
+        "#+TODO: TODO | DONE\n#+PRIORITIES: A D D\n".to_string(),
+        |mut output, task| {
+            output.push_str(&render_headline(task));
+            output
+        },
+    )
+}
+
+/// Import the `TODO` and `DONE` headlines of an Org file into a [`Store`]
+///
+/// Headlines which match an existing task by `:UUID:` update it, and any
+/// attribute missing from the headline is left alone.
+///
+/// # Errors
+///
+/// Returns an error if a headline has no title, or a property has an invalid
+/// value. Nothing is imported if there is an error.
+pub fn import(store: &mut Store, input: &str) -> Result<ImportSummary, Error> {
+    let headlines = parse(input)?;
+    let tasks: Vec<Task> = headlines
+        .into_iter()
+        .map(|headline| {
+            let existing = store.get_by_uuid(headline.uuid()).map(OpenTask::task);
+            headline.apply(existing)
+        })
+        .collect();
+
+    Ok(store.import(tasks))
+}
+
+struct Headline {
+    done: bool,
+    priority: Option<Priority>,
+    title: String,
+    tags: BTreeSet<String>,
+    closed: Option<DateTime<Utc>>,
+    uuid: Option<Uuid>,
+    created: Option<DateTime<Utc>>,
+    project: Option<String>,
+    properties: Vec<(String, String)>,
+}
+
+impl Headline {
+    fn uuid(&self) -> Uuid {
+        self.uuid
+            .unwrap_or_else(|| Uuid::new_v5(&Uuid::NAMESPACE_OID, self.title.as_bytes()))
+    }
+
+    /// Build the task described by the headline, starting from the task it
+    /// updates if there is one
+    fn apply(self, existing: Option<&Task>) -> Task {
+        let Some(existing) = existing else {
+            let uuid = self.uuid();
+            let mut task = Task::new(self.title).with_uuid(uuid);
+            if let Some(created) = self.created {
+                task = task.with_created(created);
+            }
+            if let Some(priority) = self.priority {
+                task = task.with_priority(priority);
+            }
+            if let Some(project) = self.project {
+                task = task.with_project(project);
+            }
+            for tag in self.tags {
+                task = task.with_tag(tag);
+            }
+            for (key, value) in self.properties {
+                task = task.with_extra(key.to_ascii_lowercase(), Value::String(value));
+            }
+            if self.done {
+                task = task.with_completed(self.closed.unwrap_or_else(Utc::now));
+            }
+            return task;
+        };
+
+        let mut task = existing.clone();
+        if let Some(created) = self.created.filter(|c| *c != truncate(task.created())) {
+            task = task.with_created(created);
+            task.touch();
+        }
+        if *task.description() != self.title {
+            task.set_description(self.title);
+        }
+        match (self.done, task.is_completed()) {
+            (true, false) => task.mark_completed(),
+            (false, true) => task.mark_active(),
+            _ => {}
+        }
+        if task.priority() != self.priority {
+            task.set_priority(self.priority);
+        }
+        if task.project() != self.project.as_deref() {
+            task.set_project(self.project);
+        }
+
+        let removed: Vec<String> = task.tags().difference(&self.tags).cloned().collect();
+        for tag in removed {
+            task.remove_tag(&tag);
+        }
+        for tag in self.tags {
+            task.add_tag(tag);
+        }
+
+        let properties: Vec<(String, Value)> = self
+            .properties
+            .into_iter()
+            .map(|(key, value)| (key.to_ascii_lowercase(), Value::String(value)))
+            .collect();
+        let removed: Vec<String> = task
+            .extra()
+            .iter()
+            .filter(|(key, value)| is_property(key, value))
+            .filter(|(key, _)| !properties.iter().any(|(k, _)| k == *key))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in removed {
+            task.remove_extra(&key);
+        }
+        for (key, value) in properties {
+            if task.extra().get(&key) != Some(&value) {
+                task.set_extra(key, value);
+            }
+        }
+
+        task
+    }
+}
+
+fn parse(input: &str) -> Result<Vec<Headline>, Error> {
+    let mut headlines: Vec<Headline> = Vec::new();
+    // Whether the lines being read belong to a task headline
+    let mut in_task = false;
+    let mut in_drawer = false;
+
+    for (index, line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let error = |kind| Error {
+            line: line_number,
+            kind,
+        };
+
+        if let Some(headline) = line.strip_prefix('*') {
+            in_drawer = false;
+            in_task = false;
+            let headline = headline.trim_start_matches('*');
+            if !headline.starts_with(' ') {
+                continue;
+            }
+            if let Some(headline) = parse_headline(headline.trim(), line_number)? {
+                headlines.push(headline);
+                in_task = true;
+            }
+            continue;
+        }
+
+        let Some(headline) = headlines.last_mut().filter(|_| in_task) else {
+            continue;
+        };
+        let line = line.trim();
+
+        if in_drawer {
+            if line.eq_ignore_ascii_case(":END:") {
+                in_drawer = false;
+            } else if let Some((name, value)) = parse_property(line) {
+                let value = value.to_string();
+                match name.to_ascii_uppercase().as_str() {
+                    UUID_PROPERTY => {
+                        let uuid = Uuid::parse_str(&value)
+                            .map_err(|_| error(ErrorKind::InvalidUuid(value)))?;
+                        headline.uuid = Some(uuid);
+                    }
+                    CREATED_PROPERTY => {
+                        let created = parse_timestamp(&value).ok_or_else(|| {
+                            error(ErrorKind::InvalidTimestamp {
+                                property: CREATED_PROPERTY,
+                                value,
+                            })
+                        })?;
+                        headline.created = Some(created);
+                    }
+                    PROJECT_PROPERTY => headline.project = Some(value).filter(|p| !p.is_empty()),
+                    _ => headline.properties.push((name.to_string(), value)),
+                }
+            }
+        } else if line.eq_ignore_ascii_case(":PROPERTIES:") {
+            in_drawer = true;
+        } else if let Some(closed) = line
+            .split_once("CLOSED:")
+            .map(|(_, rest)| closing_timestamp(rest))
+        {
+            let closed = parse_timestamp(closed).ok_or_else(|| {
+                error(ErrorKind::InvalidTimestamp {
+                    property: "CLOSED",
+                    value: closed.to_string(),
+                })
+            })?;
+            headline.closed = Some(closed);
+        }
+    }
+
+    Ok(headlines)
+}
+
+/// Parse the text of a headline after its stars
+///
+/// Returns `None` for headlines which aren't tasks.
+fn parse_headline(text: &str, line: usize) -> Result<Option<Headline>, Error> {
+    let (keyword, rest) = text.split_once(' ').unwrap_or((text, ""));
+    let done = match keyword {
+        "TODO" => false,
+        "DONE" => true,
+        _ => return Ok(None),
+    };
+
+    let mut rest = rest.trim();
+    let mut priority = None;
+    if let Some(cookie) = rest
+        .strip_prefix("[#")
+        .and_then(|r| r.get(..2))
+        .filter(|c| c.ends_with(']'))
+    {
+        priority = priority_from_letter(cookie.chars().next().unwrap_or_default());
+        rest = rest[4..].trim_start();
+    }
+
+    let mut tags = BTreeSet::new();
+    if let Some((title, tag_list)) = rest.rsplit_once(' ').or(Some(("", rest))) {
+        if is_tag_list(tag_list) {
+            tags = tag_list
+                .split(':')
+                .filter(|tag| !tag.is_empty())
+                .map(ToString::to_string)
+                .collect();
+            rest = title.trim_end();
+        }
+    }
+
+    if rest.is_empty() {
+        return Err(Error {
+            line,
+            kind: ErrorKind::MissingTitle,
+        });
+    }
+
+    Ok(Some(Headline {
+        done,
+        priority,
+        title: rest.to_string(),
+        tags,
+        closed: None,
+        uuid: None,
+        created: None,
+        project: None,
+        properties: Vec::new(),
+    }))
+}
+
+fn render_headline(task: &Task) -> String {
+    let mut headline = vec![
+        if task.is_completed() {
+            "* DONE"
+        } else {
+            "* TODO"
+        }
+        .to_string(),
+    ];
+    if let Some(priority) = task.priority() {
+        headline.push(format!("[#{}]", priority_letter(priority)));
+    }
+    headline.push(task.description().replace('\n', " "));
+    if !task.tags().is_empty() {
+        let tags: Vec<&str> = task.tags().iter().map(String::as_str).collect();
+        headline.push(format!(":{}:", tags.join(":")));
+    }
+
+    let mut lines = vec![headline.join(" ")];
+    if let Some(completed) = task.completed() {
+        lines.push(format!("  CLOSED: [{}]", format_timestamp(completed)));
+    }
+
+    let mut properties = vec![
+        (UUID_PROPERTY.to_string(), task.uuid().to_string()),
+        (
+            CREATED_PROPERTY.to_string(),
+            format!("[{}]", format_timestamp(task.created())),
+        ),
+    ];
+    if let Some(project) = task.project() {
+        properties.push((PROJECT_PROPERTY.to_string(), project.to_string()));
+    }
+    for (key, value) in task.extra() {
+        let name = key.to_ascii_uppercase();
+        if let (true, false, Some(value)) = (
+            is_property(key, value),
+            properties.iter().any(|(n, _)| *n == name),
+            value.as_str(),
+        ) {
+            properties.push((name, value.to_string()));
+        }
+    }
+
+    lines.push("  :PROPERTIES:".to_string());
+    lines.extend(
+        properties
+            .into_iter()
+            .map(|(name, value)| format!("  {:<10}{value}", format!(":{name}:"))),
+    );
+    lines.push("  :END:".to_string());
+
+    lines.iter().fold(String::new(), |mut output, line| {
+        output.push_str(line);
+        output.push('\n');
+        output
+    })
+}
+
+/// Whether an extra attribute can be written as a property
+fn is_property(key: &str, value: &Value) -> bool {
+    let valid_name = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    let valid_value = value.as_str().is_some_and(|v| !v.contains('\n'));
+    valid_name && valid_value
+}
+
+/// Whether the last word of a headline is a `:tag:` list
+fn is_tag_list(word: &str) -> bool {
+    word.len() > 2
+        && word.starts_with(':')
+        && word.ends_with(':')
+        && word
+            .chars()
+            .all(|c| c == ':' || c.is_alphanumeric() || "_@#%".contains(c))
+}
+
+fn parse_property(line: &str) -> Option<(&str, &str)> {
+    let (name, value) = line.strip_prefix(':')?.split_once(':')?;
+    (!name.is_empty() && !name.contains(' ')).then(|| (name, value.trim()))
+}
+
+/// The timestamp following `CLOSED:` on a planning line
+fn closing_timestamp(rest: &str) -> &str {
+    let rest = rest.trim_start();
+    rest.find(']').map_or(rest, |end| &rest[..=end])
+}
+
+/// Parse an active or inactive timestamp such as `[2024-01-01 Mon 09:00]`
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let inner = value
+        .trim()
+        .strip_prefix(['[', '<'])?
+        .strip_suffix([']', '>'])?;
+    let mut parts = inner.split_whitespace();
+
+    let date = NaiveDate::parse_from_str(parts.next()?, DATE_FORMAT).ok()?;
+    let time = parts
+        .find_map(|part| NaiveTime::parse_from_str(part, TIME_FORMAT).ok())
+        .unwrap_or(NaiveTime::MIN);
+
+    Some(NaiveDateTime::new(date, time).and_utc())
+}
+
+fn format_timestamp(date: DateTime<Utc>) -> String {
+    date.format(TIMESTAMP_FORMAT).to_string()
+}
+
+/// Drop the seconds that an Org timestamp can't represent
+fn truncate(date: DateTime<Utc>) -> DateTime<Utc> {
+    date.with_second(0)
+        .and_then(|date| date.with_nanosecond(0))
+        .unwrap_or(date)
+}
+
+const fn priority_from_letter(letter: char) -> Option<Priority> {
+    match letter {
+        'A' => Some(Priority::One),
+        'B' => Some(Priority::Two),
+        'C' => Some(Priority::Three),
+        'D' => Some(Priority::Four),
+        _ => None,
+    }
+}
+
+const fn priority_letter(priority: Priority) -> char {
+    match priority {
+        Priority::One => 'A',
+        Priority::Two => 'B',
+        Priority::Three => 'C',
+        Priority::Four => 'D',
+    }
+}
+
+/// An error parsing an Org file
+#[derive(Debug, thiserror::Error)]
+#[error("line {line}: {kind}")]
+pub struct Error {
+    /// The line number, starting from 1
+    pub line: usize,
+    /// What went wrong
+    pub kind: ErrorKind,
+}
+
+/// The kinds of error that can occur parsing an Org file
+#[derive(Debug, thiserror::Error)]
+pub enum ErrorKind {
+    /// A `TODO` or `DONE` headline has no title
+    #[error("headline has no title")]
+    MissingTitle,
+    /// The `:UUID:` property is not a valid UUID
+    #[error("invalid UUID '{0}'")]
+    InvalidUuid(String),
+    /// A timestamp cannot be parsed
+    #[error("invalid {property} timestamp '{value}'")]
+    InvalidTimestamp {
+        /// The property or planning keyword holding the timestamp
+        property: &'static str,
+        /// The offending value
+        value: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+    use uuid::uuid;
+
+    use super::{import, render};
+    use crate::{OpenTask, Priority, Store, Task};
+
+    const ORG: &str = "#+TITLE: Chores
+
+* Home
+** TODO [#A] Pay rent :bills:monthly:
+   :PROPERTIES:
+   :UUID:     5b1b0fb2-6a1f-4a83-9f4b-46f2e3b2e0a1
+   :CREATED:  [2024-01-01 Mon 09:00]
+   :PROJECT:  home
+   :EFFORT:   0:30
+   :END:
+   Some notes which are ignored.
+** DONE Renew passport
+   CLOSED: [2024-03-05 Tue 12:00]
+   :PROPERTIES:
+   :CREATED:  [2024-03-01 Fri 09:00]
+   :END:
+** Not a task
+";
+
+    fn tasks(store: &Store) -> Vec<&Task> {
+        store.iter().map(OpenTask::task).collect()
+    }
+
+    #[test]
+    fn import_file() {
+        let mut store = Store::new();
+        let summary = import(&mut store, ORG).unwrap();
+        assert_eq!(summary.added, 2);
+
+        let tasks = tasks(&store);
+        let rent = tasks[0];
+        assert_eq!(rent.uuid(), uuid!("5b1b0fb2-6a1f-4a83-9f4b-46f2e3b2e0a1"));
+        assert_eq!(rent.description(), "Pay rent");
+        assert_eq!(rent.priority(), Some(Priority::One));
+        assert_eq!(rent.created().to_rfc3339(), "2024-01-01T09:00:00+00:00");
+        assert_eq!(rent.project(), Some("home"));
+        assert!(rent.has_tag("bills") && rent.has_tag("monthly"));
+        assert_eq!(rent.extra().get("effort"), Some(&"0:30".into()));
+        assert!(!rent.is_completed());
+
+        let passport = tasks[1];
+        assert_eq!(
+            passport.completed().unwrap().to_rfc3339(),
+            "2024-03-05T12:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn edit_in_place() {
+        let mut store = Store::new();
+        store.push(
+            Task::new("Pay rent".to_string())
+                .with_priority(Priority::Two)
+                .with_tag("bills".to_string()),
+        );
+        let before = store.clone();
+
+        let exported = render(tasks(&store));
+        assert!(exported.contains("\n* TODO [#B] Pay rent :bills:\n"));
+        assert_eq!(import(&mut store, &exported).unwrap().unchanged, 1);
+        assert_eq!(store, before);
+
+        let edited = exported
+            .replace("* TODO [#B] Pay rent :bills:", "* DONE [#A] Pay the rent")
+            .replace("  :END:", "  :PROJECT: home\n  :END:");
+        let summary = import(&mut store, &edited).unwrap();
+        assert_eq!(summary.updated, 1);
+        assert_eq!(store.len(), 1);
+
+        let task = store.get(0).unwrap();
+        assert_eq!(task.description(), "Pay the rent");
+        assert_eq!(task.priority(), Some(Priority::One));
+        assert_eq!(task.project(), Some("home"));
+        assert!(task.tags().is_empty());
+        assert!(task.is_completed());
+        assert_eq!(task.created(), before.get(0).unwrap().created());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut store = Store::new();
+        import(&mut store, ORG).unwrap();
+
+        let mut fresh = Store::new();
+        import(&mut fresh, &render(tasks(&store))).unwrap();
+        assert_eq!(fresh, store);
+    }
+
+    #[test_case("* TODO [#C] Task" => Some(Priority::Three) ; "priority")]
+    #[test_case("* TODO [#D] Task :tag:" => Some(Priority::Four) ; "priority with tags")]
+    #[test_case("* TODO [#E] Task" => None ; "unknown priority")]
+    #[test_case("* TODO Task" => None ; "no priority")]
+    fn priority(headline: &str) -> Option<Priority> {
+        let mut store = Store::new();
+        import(&mut store, headline).unwrap();
+        store.get(0).unwrap().priority()
+    }
+
+    #[test_case("* TODO\n" => 1 ; "missing title")]
+    #[test_case("* TODO :tag:\n" => 1 ; "only tags")]
+    #[test_case("* TODO Task\n:PROPERTIES:\n:UUID: nope\n:END:\n" => 3 ; "invalid uuid")]
+    #[test_case("* DONE Task\nCLOSED: [yesterday]\n" => 2 ; "invalid timestamp")]
+    fn error_line(input: &str) -> usize {
+        import(&mut Store::new(), input).map_or_else(|e| e.line, |_| 0)
+    }
+}
@@ -0,0 +1,367 @@
+//! [Taskwarrior](https://taskwarrior.org) JSON, as produced by `task export`
+//!
+//! | Taskwarrior   | [`Task`]                                         |
+//! |---------------|--------------------------------------------------|
+//! | `uuid`        | [`Task::uuid`]                                   |
+//! | `entry`       | [`Task::created`]                                |
+//! | `description` | [`Task::description`]                            |
+//! | `modified`    | [`Task::modified`]                               |
+//! | `start`       | [`Task::start`]                                  |
+//! | `end`         | [`Task::end`], and [`Task::completed`] if completed |
+//! | `status`      | `completed` and `deleted` mark the task completed or deleted |
+//! | `priority`    | `H`, `M`, `L` map to [`Priority::One`] - [`Priority::Three`] |
+//! | `project`     | [`Task::project`]                                |
+//! | `tags`        | [`Task::tags`]                                   |
+//!
+//! Everything else, including other statuses such as `recurring`,
+//! is kept in [`Task::extra`] and written back out on export. The exceptions
+//! are `id` and `urgency`, which Taskwarrior computes and ignores on import.
+//!
+//! [`Priority::Four`] has no Taskwarrior equivalent, and is exported as `L`.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{Priority, Task};
+
+const DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+/// Attributes which Taskwarrior derives, and so aren't worth keeping
+const DERIVED: &[&str] = &["id", "urgency"];
+
+/// Parse the output of `task export`
+///
+/// Accepts either a JSON array of tasks, or one JSON object per line.
+///
+/// # Errors
+///
+/// Returns an error if the input is not valid JSON, or a task is missing its
+/// description or has an unparseable date.
+pub fn parse(input: &str) -> Result<Vec<Task>, Error> {
+    let objects: Vec<Map<String, Value>> = if input.trim_start().starts_with('[') {
+        serde_json::from_str(input)?
+    } else {
+        input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?
+    };
+
+    objects.into_iter().map(parse_task).collect()
+}
+
+/// Render tasks in the format produced by `task export`
+///
+/// # Errors
+///
+/// Returns an error if a task cannot be serialised.
+pub fn render<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> Result<String, Error> {
+    let lines = tasks
+        .into_iter()
+        .map(|task| serde_json::to_string(&render_task(task)?))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if lines.is_empty() {
+        return Ok("[]\n".to_string());
+    }
+    Ok(format!("[\n{}\n]\n", lines.join(",\n")))
+}
+
+fn parse_task(mut tw: Map<String, Value>) -> Result<Task, Error> {
+    for key in DERIVED {
+        tw.remove(*key);
+    }
+
+    let mut task = Map::new();
+
+    let description = tw
+        .remove("description")
+        .ok_or(Error::MissingField("description"))?;
+    task.insert("description".into(), description);
+
+    let uuid = tw
+        .remove("uuid")
+        .unwrap_or_else(|| Value::String(Uuid::new_v4().to_string()));
+    task.insert("uuid".into(), uuid);
+
+    let created = take_date(&mut tw, "entry")?.unwrap_or_else(Utc::now);
+    task.insert("created".into(), date_value(created));
+
+    for key in ["modified", "start"] {
+        if let Some(date) = take_date(&mut tw, key)? {
+            task.insert(key.into(), date_value(date));
+        }
+    }
+
+    let mut end = take_date(&mut tw, "end")?;
+
+    match tw.remove("status") {
+        Some(Value::String(status)) if status == "pending" => {}
+        Some(Value::String(status)) if status == "completed" || status == "deleted" => {
+            let closed = *end.get_or_insert_with(Utc::now);
+            task.insert(status, date_value(closed));
+        }
+        Some(other) => {
+            tw.insert("status".into(), other);
+        }
+        None => {}
+    }
+
+    if let Some(end) = end {
+        task.insert("end".into(), date_value(end));
+    }
+
+    match tw.remove("priority") {
+        Some(Value::String(priority)) if priority_from_tw(&priority).is_some() => {
+            let priority = priority_from_tw(&priority).map(u8::from);
+            task.insert("priority".into(), priority.into());
+        }
+        Some(other) => {
+            tw.insert("priority".into(), other);
+        }
+        None => {}
+    }
+
+    match tw.remove("project") {
+        Some(Value::String(project)) => {
+            task.insert("project".into(), project.into());
+        }
+        Some(other) => {
+            tw.insert("project".into(), other);
+        }
+        None => {}
+    }
+
+    match tw.remove("tags") {
+        Some(Value::Array(tags)) if tags.iter().all(Value::is_string) => {
+            task.insert("tags".into(), Value::Array(tags));
+        }
+        Some(other) => {
+            tw.insert("tags".into(), other);
+        }
+        None => {}
+    }
+
+    if !tw.is_empty() {
+        task.insert("extra".into(), Value::Object(tw));
+    }
+
+    Ok(serde_json::from_value(Value::Object(task))?)
+}
+
+fn render_task(task: &Task) -> Result<Value, serde_json::Error> {
+    let mut tw = Map::new();
+
+    tw.insert("uuid".into(), task.uuid().to_string().into());
+    tw.insert("description".into(), task.description().clone().into());
+    tw.insert("entry".into(), format_date(task.created()).into());
+    tw.insert("modified".into(), format_date(task.modified()).into());
+
+    if let Some(start) = task.start() {
+        tw.insert("start".into(), format_date(start).into());
+    }
+    if let Some(end) = task.end().or_else(|| task.completed()) {
+        tw.insert("end".into(), format_date(end).into());
+    }
+    if task.is_deleted() {
+        tw.insert("status".into(), "deleted".into());
+    } else if task.is_completed() {
+        tw.insert("status".into(), "completed".into());
+    }
+    if let Some(priority) = task.priority() {
+        tw.insert("priority".into(), priority_to_tw(priority).into());
+    }
+    if let Some(project) = task.project() {
+        tw.insert("project".into(), project.into());
+    }
+    if !task.tags().is_empty() {
+        tw.insert("tags".into(), serde_json::to_value(task.tags())?);
+    }
+
+    for (key, value) in task.extra() {
+        tw.entry(key.clone()).or_insert_with(|| value.clone());
+    }
+    tw.entry("status").or_insert_with(|| "pending".into());
+
+    Ok(Value::Object(tw))
+}
+
+fn take_date(
+    tw: &mut Map<String, Value>,
+    key: &'static str,
+) -> Result<Option<DateTime<Utc>>, Error> {
+    let Some(value) = tw.remove(key) else {
+        return Ok(None);
+    };
+    let invalid = || Error::InvalidField {
+        field: key,
+        value: value.to_string(),
+    };
+    let text = value.as_str().ok_or_else(invalid)?;
+
+    NaiveDateTime::parse_from_str(text, DATE_FORMAT)
+        .map(|date| date.and_utc())
+        .or_else(|_| DateTime::parse_from_rfc3339(text).map(|date| date.to_utc()))
+        .map(Some)
+        .map_err(|_| invalid())
+}
+
+fn date_value(date: DateTime<Utc>) -> Value {
+    Value::String(date.to_rfc3339())
+}
+
+fn format_date(date: DateTime<Utc>) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+fn priority_from_tw(priority: &str) -> Option<Priority> {
+    match priority {
+        "H" => Some(Priority::One),
+        "M" => Some(Priority::Two),
+        "L" => Some(Priority::Three),
+        _ => None,
+    }
+}
+
+const fn priority_to_tw(priority: Priority) -> &'static str {
+    match priority {
+        Priority::One => "H",
+        Priority::Two => "M",
+        Priority::Three | Priority::Four => "L",
+    }
+}
+
+/// Error type for Taskwarrior conversion
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The input is not valid JSON
+    #[error("invalid Taskwarrior JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    /// A required attribute is missing
+    #[error("task is missing required attribute '{0}'")]
+    MissingField(&'static str),
+    /// An attribute has a value which cannot be interpreted
+    #[error("invalid value for '{field}': {value}")]
+    InvalidField {
+        /// The attribute name
+        field: &'static str,
+        /// The offending value
+        value: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use uuid::uuid;
+
+    use super::{parse, render};
+    use crate::{OpenTask, Priority, Store, Task};
+
+    const EXPORT: &str = r#"[
+{"id":1,"description":"Pay rent","entry":"20240101T090000Z","modified":"20240102T090000Z","priority":"H","project":"home","status":"pending","tags":["bills","monthly"],"uuid":"5b1b0fb2-6a1f-4a83-9f4b-46f2e3b2e0a1","urgency":8.2,"due":"20240201T000000Z"},
+{"id":0,"description":"Renew passport","end":"20240305T120000Z","entry":"20240301T090000Z","modified":"20240305T120000Z","status":"completed","uuid":"0d5b3d7f-1c3a-4cbb-9d3e-7c5a8c8c2e11"},
+{"id":0,"description":"Old idea","end":"20240305T120000Z","entry":"20240301T090000Z","modified":"20240305T120000Z","status":"deleted","uuid":"9f6e0c1d-8f1e-4a0e-a3b4-1b2f7c9d0e22"}
+]"#;
+
+    #[test]
+    fn parse_export() {
+        let tasks = parse(EXPORT).unwrap();
+        assert_eq!(tasks.len(), 3);
+
+        let rent = &tasks[0];
+        assert_eq!(rent.uuid(), uuid!("5b1b0fb2-6a1f-4a83-9f4b-46f2e3b2e0a1"));
+        assert_eq!(rent.description(), "Pay rent");
+        assert_eq!(rent.created().to_rfc3339(), "2024-01-01T09:00:00+00:00");
+        assert_eq!(rent.priority(), Some(Priority::One));
+        assert!(rent.has_tag("bills") && rent.has_tag("monthly"));
+        assert!(!rent.is_completed());
+        assert_eq!(rent.project(), Some("home"));
+        assert_eq!(rent.extra().get("due"), Some(&json!("20240201T000000Z")));
+        assert!(!rent.extra().contains_key("urgency"));
+
+        let passport = &tasks[1];
+        assert!(passport.is_completed());
+        assert_eq!(passport.completed(), passport.end());
+
+        let idea = &tasks[2];
+        assert!(idea.is_deleted());
+        assert_eq!(idea.deleted(), idea.end());
+        assert!(!idea.extra().contains_key("status"));
+        assert!(!rent.is_deleted());
+    }
+
+    #[test]
+    fn parse_json_lines() {
+        let input = r#"{"description":"one","status":"pending"}
+{"description":"two","status":"pending","priority":"L"}
+"#;
+        let tasks = parse(input).unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[1].priority(), Some(Priority::Three));
+    }
+
+    #[test]
+    fn missing_description() {
+        assert!(parse(r#"[{"uuid":"5b1b0fb2-6a1f-4a83-9f4b-46f2e3b2e0a1"}]"#).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let tasks = parse(EXPORT).unwrap();
+        let exported = render(&tasks).unwrap();
+        assert_eq!(parse(&exported).unwrap(), tasks);
+
+        let exported: serde_json::Value = serde_json::from_str(&exported).unwrap();
+        assert_eq!(exported[0]["project"], json!("home"));
+        assert_eq!(exported[0]["priority"], json!("H"));
+        assert_eq!(exported[1]["status"], json!("completed"));
+        assert_eq!(exported[2]["status"], json!("deleted"));
+    }
+
+    #[test]
+    fn edited_export_updates_tasks() {
+        let mut store = Store::new();
+        let mut task = Task::new("Water plants".to_string()).with_priority(Priority::Two);
+        task.mark_started();
+        let id = store.push(task);
+        let before = store.clone();
+
+        let exported = render(store.iter().map(OpenTask::task)).unwrap();
+        assert_eq!(store.import(parse(&exported).unwrap()).unchanged, 1);
+        assert_eq!(store, before);
+
+        let edited = exported
+            .replace("Water plants", "Water the plants")
+            .replace(r#""priority":"M","#, "");
+        assert_eq!(store.import(parse(&edited).unwrap()).updated, 1);
+
+        let task = store.get(id).unwrap();
+        assert_eq!(task.description(), "Water the plants");
+        assert_eq!(task.priority(), None);
+        assert_eq!(task.start(), before.get(id).unwrap().start());
+    }
+
+    #[test]
+    fn render_deleted() {
+        let mut task = Task::new("gone".to_string());
+        task.mark_deleted();
+
+        let exported: serde_json::Value = serde_json::from_str(&render([&task]).unwrap()).unwrap();
+        assert_eq!(exported[0]["status"], json!("deleted"));
+        assert!(exported[0].get("end").is_some());
+    }
+
+    #[test]
+    fn deleted_round_trip() {
+        let mut task = Task::new("gone".to_string());
+        task.mark_deleted();
+
+        let imported = parse(&render([&task]).unwrap()).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert!(imported[0].is_deleted());
+        assert_eq!(imported[0].uuid(), task.uuid());
+    }
+}
@@ -0,0 +1,444 @@
+//! [todo.txt](https://github.com/todotxt/todo.txt), one task per line
+//!
+//! | todo.txt                 | [`Task`]                                    |
+//! |--------------------------|---------------------------------------------|
+//! | `x YYYY-MM-DD`           | [`Task::completed`]                         |
+//! | `(A)` - `(D)`            | [`Priority::One`] - [`Priority::Four`]      |
+//! | creation date            | [`Task::created`]                           |
+//! | first `+project`         | [`Task::project`]                           |
+//! | `@context`               | [`Task::tags`]                              |
+//! | `uuid:...`               | [`Task::uuid`]                              |
+//! | other `key:value` pairs  | [`Task::extra`]                             |
+//!
+//! Completed tasks carry their priority as `pri:A`, per the todo.txt
+//! convention. Priorities `(E)` - `(Z)`, and any further `+project` tokens, are
+//! kept in [`Task::extra`] so that they are written back out.
+//!
+//! Tasks are exported with a `uuid:` tag so that importing the file again
+//! updates tasks rather than duplicating them. Dates are whole days (UTC), so
+//! times of day are lost, and a date on the same day as the one it replaces is
+//! left alone. Lines which change a task mark it as modified, as todo.txt has
+//! no modification times.
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{ImportSummary, OpenTask, Priority, Store, Task};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Extra attribute holding a priority outside `(A)` - `(D)`
+const PRIORITY_KEY: &str = "pri";
+/// Extra attribute holding the `+project` tokens after the first
+const PROJECTS_KEY: &str = "projects";
+const UUID_KEY: &str = "uuid";
+
+/// Parse a todo.txt file
+///
+/// Blank lines are skipped.
+///
+/// # Errors
+///
+/// Returns an error if a line has no description, or an invalid `uuid:` tag.
+pub fn parse(input: &str) -> Result<Vec<Task>, Error> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            parse_line(line).map_err(|kind| Error {
+                line: index + 1,
+                kind,
+            })
+        })
+        .collect()
+}
+
+/// Import a todo.txt file into a [`Store`]
+///
+/// Lines with the `uuid:` of an existing task update it, leaving alone
+/// anything todo.txt can't hold.
+///
+/// # Errors
+///
+/// Returns an error if a line can't be parsed (see [`parse`]). Nothing is
+/// imported if there is an error.
+pub fn import(store: &mut Store, input: &str) -> Result<ImportSummary, Error> {
+    let tasks: Vec<Task> = parse(input)?
+        .into_iter()
+        .map(
+            |line| match store.get_by_uuid(line.uuid()).map(OpenTask::task) {
+                Some(existing) => apply(existing, &line),
+                None => line,
+            },
+        )
+        .collect();
+
+    Ok(store.import(tasks))
+}
+
+/// Render tasks as a todo.txt file
+#[must_use]
+pub fn render<'a>(tasks: impl IntoIterator<Item = &'a Task>) -> String {
+    tasks.into_iter().fold(String::new(), |mut output, task| {
+        output.push_str(&render_line(task));
+        output.push('\n');
+        output
+    })
+}
+
+fn parse_line(line: &str) -> Result<Task, ErrorKind> {
+    let mut tokens = line.split_whitespace().peekable();
+
+    let completed = tokens.next_if_eq(&"x").map(|_| {
+        tokens
+            .next_if(|token| parse_date(token).is_some())
+            .and_then(parse_date)
+            .unwrap_or_else(Utc::now)
+    });
+
+    let mut priority = None;
+    let mut other_priority = None;
+    if let Some(letter) = tokens.next_if(|token| parse_priority(token).is_some()) {
+        match parse_priority(letter) {
+            Some(Ok(p)) => priority = Some(p),
+            Some(Err(letter)) => other_priority = Some(letter),
+            None => unreachable!(),
+        }
+    }
+
+    let created = tokens
+        .next_if(|token| parse_date(token).is_some())
+        .and_then(parse_date);
+
+    let mut words = Vec::new();
+    let mut projects = Vec::new();
+    let mut contexts = Vec::new();
+    let mut pairs = Vec::new();
+    for token in tokens {
+        if let Some(project) = token.strip_prefix('+').filter(|p| !p.is_empty()) {
+            projects.push(project.to_string());
+        } else if let Some(context) = token.strip_prefix('@').filter(|c| !c.is_empty()) {
+            contexts.push(context.to_string());
+        } else if let Some((key, value)) = split_pair(token) {
+            pairs.push((key.to_string(), value.to_string()));
+        } else {
+            words.push(token);
+        }
+    }
+
+    if words.is_empty() {
+        return Err(ErrorKind::MissingDescription);
+    }
+
+    let mut task = Task::new(words.join(" "));
+    if let Some(created) = created {
+        task = task.with_created(created);
+    }
+
+    for (key, value) in pairs {
+        match key.as_str() {
+            UUID_KEY => {
+                let uuid = Uuid::parse_str(&value).map_err(|_| ErrorKind::InvalidUuid(value))?;
+                task = task.with_uuid(uuid);
+            }
+            PRIORITY_KEY => match parse_priority(&format!("({value})")) {
+                Some(Ok(p)) => priority = Some(p),
+                _ => other_priority = Some(value),
+            },
+            _ => task = task.with_extra(key, Value::String(value)),
+        }
+    }
+
+    if let Some(priority) = priority {
+        task = task.with_priority(priority);
+    }
+    if let Some(letter) = other_priority {
+        task = task.with_extra(PRIORITY_KEY.to_string(), Value::String(letter));
+    }
+
+    let mut projects = projects.into_iter();
+    if let Some(project) = projects.next() {
+        task = task.with_project(project);
+    }
+    let projects: Vec<Value> = projects.map(Value::String).collect();
+    if !projects.is_empty() {
+        task = task.with_extra(PROJECTS_KEY.to_string(), Value::Array(projects));
+    }
+
+    for context in contexts {
+        task = task.with_tag(context);
+    }
+
+    if let Some(completed) = completed {
+        task = task.with_completed(completed);
+    }
+
+    Ok(task)
+}
+
+/// Update a task with what has changed in its todo.txt line
+fn apply(existing: &Task, line: &Task) -> Task {
+    let mut task = existing.clone();
+
+    if *task.description() != *line.description() {
+        task.set_description(line.description().clone());
+    }
+    if !same_day(task.created(), line.created()) {
+        task = task.with_created(line.created());
+        task.touch();
+    }
+    match (line.completed(), task.completed()) {
+        (Some(completed), None) => {
+            task.mark_completed();
+            task = task.with_completed(completed);
+        }
+        (None, Some(_)) => task.mark_active(),
+        (Some(completed), Some(existing)) if !same_day(completed, existing) => {
+            task = task.with_completed(completed);
+            task.touch();
+        }
+        _ => {}
+    }
+    if task.priority() != line.priority() {
+        task.set_priority(line.priority());
+    }
+    if task.project() != line.project() {
+        task.set_project(line.project().map(ToString::to_string));
+    }
+
+    for tag in existing.tags().difference(line.tags()) {
+        task.remove_tag(tag);
+    }
+    for tag in line.tags().difference(existing.tags()) {
+        task.add_tag(tag.clone());
+    }
+
+    for (key, value) in existing.extra() {
+        if !line.extra().contains_key(key) && is_written(key, value) {
+            task.remove_extra(key);
+        }
+    }
+    for (key, value) in line.extra() {
+        if existing.extra().get(key) != Some(value) {
+            task.set_extra(key.clone(), value.clone());
+        }
+    }
+
+    task
+}
+
+/// Whether an extra attribute is written on a task's line, so that a line
+/// without it has had it removed
+fn is_written(key: &str, value: &Value) -> bool {
+    key == PROJECTS_KEY
+        || !(key.contains(char::is_whitespace) || key.contains(':'))
+            && value
+                .as_str()
+                .is_some_and(|v| !v.contains(char::is_whitespace))
+}
+
+fn same_day(a: DateTime<Utc>, b: DateTime<Utc>) -> bool {
+    a.date_naive() == b.date_naive()
+}
+
+fn render_line(task: &Task) -> String {
+    let mut parts = Vec::new();
+
+    let other_priority = task.extra().get(PRIORITY_KEY).and_then(Value::as_str);
+    if let Some(completed) = task.completed() {
+        parts.push("x".to_string());
+        parts.push(format_date(completed));
+    } else if let Some(priority) = task.priority() {
+        parts.push(format!("({})", priority_letter(priority)));
+    } else if let Some(letter) = other_priority {
+        parts.push(format!("({letter})"));
+    }
+
+    parts.push(format_date(task.created()));
+    parts.push(task.description().clone());
+
+    if let Some(project) = task.project() {
+        parts.push(format!("+{project}"));
+    }
+    if let Some(Value::Array(projects)) = task.extra().get(PROJECTS_KEY) {
+        parts.extend(
+            projects
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|project| format!("+{project}")),
+        );
+    }
+    parts.extend(task.tags().iter().map(|tag| format!("@{tag}")));
+
+    if let (Some(priority), true) = (task.priority(), task.is_completed()) {
+        parts.push(format!("{PRIORITY_KEY}:{}", priority_letter(priority)));
+    }
+    for (key, value) in task.extra() {
+        let written = key == PROJECTS_KEY || (key == PRIORITY_KEY && !task.is_completed());
+        if written || !is_written(key, value) {
+            continue;
+        }
+        if let Some(value) = value.as_str() {
+            parts.push(format!("{key}:{value}"));
+        }
+    }
+    parts.push(format!("{UUID_KEY}:{}", task.uuid()));
+
+    parts.join(" ")
+}
+
+fn parse_date(token: &str) -> Option<DateTime<Utc>> {
+    NaiveDate::parse_from_str(token, DATE_FORMAT)
+        .ok()
+        .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+}
+
+fn format_date(date: DateTime<Utc>) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+/// Parse a `(X)` priority token.
+///
+/// Letters beyond `D` are returned as an error, so they can be preserved.
+fn parse_priority(token: &str) -> Option<Result<Priority, String>> {
+    let letter = token.strip_prefix('(')?.strip_suffix(')')?;
+    match letter {
+        "A" => Some(Ok(Priority::One)),
+        "B" => Some(Ok(Priority::Two)),
+        "C" => Some(Ok(Priority::Three)),
+        "D" => Some(Ok(Priority::Four)),
+        _ if letter.len() == 1 && letter.chars().all(|c| c.is_ascii_uppercase()) => {
+            Some(Err(letter.to_string()))
+        }
+        _ => None,
+    }
+}
+
+const fn priority_letter(priority: Priority) -> char {
+    match priority {
+        Priority::One => 'A',
+        Priority::Two => 'B',
+        Priority::Three => 'C',
+        Priority::Four => 'D',
+    }
+}
+
+/// Split a `key:value` token. URLs such as `https://example.com` are not
+/// treated as pairs.
+fn split_pair(token: &str) -> Option<(&str, &str)> {
+    let (key, value) = token.split_once(':')?;
+    let valid =
+        !key.is_empty() && !value.is_empty() && !value.starts_with("//") && !value.contains(':');
+    valid.then_some((key, value))
+}
+
+/// An error parsing a todo.txt file
+#[derive(Debug, thiserror::Error)]
+#[error("line {line}: {kind}")]
+pub struct Error {
+    /// The line number, starting from 1
+    pub line: usize,
+    /// What went wrong
+    pub kind: ErrorKind,
+}
+
+/// The kinds of error that can occur parsing a todo.txt line
+#[derive(Debug, thiserror::Error)]
+pub enum ErrorKind {
+    /// The line has no description text
+    #[error("task has no description")]
+    MissingDescription,
+    /// The `uuid:` tag is not a valid UUID
+    #[error("invalid uuid '{0}'")]
+    InvalidUuid(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use test_case::test_case;
+
+    use super::{import, parse, render};
+    use crate::{OpenTask, Priority, Store, Task};
+
+    #[test_case("(A) Call mom" => Some(Priority::One) ; "A")]
+    #[test_case("(B) Call mom" => Some(Priority::Two) ; "B")]
+    #[test_case("(C) Call mom" => Some(Priority::Three) ; "C")]
+    #[test_case("(D) Call mom" => Some(Priority::Four) ; "D")]
+    #[test_case("(E) Call mom" => None ; "E")]
+    #[test_case("Call mom (A)" => None ; "not leading")]
+    #[test_case("x 2024-01-02 Call mom pri:B" => Some(Priority::Two) ; "completed")]
+    fn priority(line: &str) -> Option<Priority> {
+        parse(line).unwrap()[0].priority()
+    }
+
+    #[test]
+    fn parse_full_line() {
+        let task = &parse(
+            "x 2024-03-02 2024-03-01 Review PR +work +oss @laptop due:2024-03-05 see https://example.com",
+        )
+        .unwrap()[0];
+
+        assert_eq!(task.description(), "Review PR see https://example.com");
+        assert_eq!(
+            task.completed().unwrap().to_rfc3339(),
+            "2024-03-02T00:00:00+00:00"
+        );
+        assert_eq!(task.created().to_rfc3339(), "2024-03-01T00:00:00+00:00");
+        assert_eq!(task.project(), Some("work"));
+        assert_eq!(task.extra().get("projects"), Some(&json!(["oss"])));
+        assert!(task.has_tag("laptop"));
+        assert_eq!(task.extra().get("due"), Some(&json!("2024-03-05")));
+    }
+
+    #[test]
+    fn edited_export_updates_tasks() {
+        let mut store = Store::new();
+        let task = Task::new("Call mom".to_string())
+            .with_priority(Priority::Two)
+            .with_tag("phone".to_string())
+            .with_extra("note".to_string(), json!("has spaces"));
+        let id = store.push(task);
+        store.get_mut(id).unwrap().task_mut().mark_started();
+        let before = store.clone();
+
+        let exported = render(store.iter().map(OpenTask::task));
+        assert_eq!(import(&mut store, &exported).unwrap().unchanged, 1);
+        assert_eq!(store, before);
+
+        let line = exported.replace("(B) ", "").replace("mom", "dad");
+        let edited = format!("x {} {line}", &line[..10]);
+        assert_eq!(import(&mut store, &edited).unwrap().updated, 1);
+
+        let task = store.get(id).unwrap();
+        let original = before.get(id).unwrap();
+        assert_eq!(task.description(), "Call dad");
+        assert!(task.is_completed());
+        assert_eq!(task.priority(), None);
+        assert_eq!(task.created(), original.created());
+        assert!(task.has_tag("phone"));
+        assert_eq!(task.extra().get("note"), Some(&json!("has spaces")));
+        assert_eq!(task.intervals().len(), 1);
+    }
+
+    #[test]
+    fn empty_description() {
+        let error = parse("Call mom\n(A) +project @context").unwrap_err();
+        assert_eq!(error.line, 2);
+    }
+
+    #[test_case("(A) 2024-01-01 Call mom +family @phone" ; "pending")]
+    #[test_case("x 2024-01-03 2024-01-01 Call mom +family +chores @phone pri:B" ; "completed")]
+    #[test_case("(F) 2024-01-01 Call mom due:2024-01-05" ; "unmapped priority")]
+    fn round_trip(line: &str) {
+        let tasks = parse(line).unwrap();
+        let rendered = render(&tasks);
+        let uuid = tasks[0].uuid();
+
+        assert!(rendered.starts_with(line.split(" pri:").next().unwrap()));
+        assert!(rendered.trim_end().ends_with(&format!("uuid:{uuid}")));
+        assert_eq!(parse(&rendered).unwrap(), tasks);
+    }
+}
@@ -0,0 +1,80 @@
+//! Keeping the data directory under git version control
+//!
+//! Once the data directory is a repository, every change saved by the CLI or
+//! the MCP server is committed with [`auto_commit`].
+
+use std::{
+    ffi::OsStr,
+    io,
+    path::Path,
+    process::{Command, Output},
+};
+
+/// The name of the merge driver registered in the repository config
+pub const MERGE_DRIVER: &str = "rustwarrior";
+
+/// Whether the data directory is a git repository
+#[must_use]
+pub fn is_repo(data_dir: &Path) -> bool {
+    data_dir.join(".git").exists()
+}
+
+/// Run a git command in the data directory, failing if it exits unsuccessfully
+///
+/// # Errors
+///
+/// Returns an error if git can't be run, or exits unsuccessfully.
+pub fn run<I, S>(data_dir: &Path, args: I) -> Result<Output, Error>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(data_dir)
+        .args(args)
+        .output()
+        .map_err(Error::Spawn)?;
+
+    if !output.status.success() {
+        return Err(Error::Failed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(output)
+}
+
+/// Commit any changes in the data directory, if it is a git repository
+///
+/// # Errors
+///
+/// Returns an error if git can't be run, or fails to commit, e.g. because no
+/// `user.name` is set.
+pub fn auto_commit(data_dir: &Path, message: &str) -> Result<(), Error> {
+    if !is_repo(data_dir) {
+        return Ok(());
+    }
+
+    run(data_dir, ["add", "--all"])?;
+
+    let status = run(data_dir, ["status", "--porcelain"])?;
+    if status.stdout.is_empty() {
+        return Ok(());
+    }
+
+    run(data_dir, ["commit", "--quiet", "--message", message.trim()])?;
+
+    Ok(())
+}
+
+/// Error type for git operations
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// git couldn't be started
+    #[error("failed to run git. Is it installed?")]
+    Spawn(#[source] io::Error),
+    /// git exited unsuccessfully, with this error output
+    #[error("git failed: {0}")]
+    Failed(String),
+}
@@ -9,10 +9,21 @@
 #![warn(clippy::pedantic, clippy::nursery)]
 #![allow(clippy::cargo_common_metadata)]
 
+pub mod config;
+pub mod context;
+pub mod filter;
+pub mod format;
+pub mod git;
+pub mod report;
+pub mod sort;
 /// Task storage and persistence
 pub mod store;
+pub mod sync;
 /// Task domain model
 pub mod task;
+pub mod timesheet;
 
-pub use store::{Error as StoreError, OpenTask, Store, paths};
+pub use config::{Color, Config};
+pub use filter::Filter;
+pub use store::{Error as StoreError, ImportSummary, MergeSummary, OpenTask, Store, paths};
 pub use task::{Priority, Task};
@@ -0,0 +1,407 @@
+//! Named reports, like `next` or `overdue`
+//!
+//! A [`Report`] bundles a [`Filter`], a [`Sort`], the columns to show and a
+//! limit on the number of tasks. The built-in reports are:
+//!
+//! - `next`: the ten most important open tasks
+//! - `overdue`: open tasks with a `due` date before today
+//! - `completed`: completed tasks, most recent first
+//! - `all`: every task which hasn't been deleted
+//!
+//! Reports are defined in the `[reports]` table of the
+//! [config file](crate::config). A definition with the name of a built-in
+//! report overrides the fields it sets, and keeps the others.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Filter, OpenTask, Store, filter, sort, sort::Sort};
+
+/// A report as written in the config file, where every field is optional
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Definition {
+    /// What the report shows
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// A filter expression
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    /// A sort order, like `priority-,created+`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    /// The names of the columns to show
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Vec<String>>,
+    /// The most tasks to show
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+impl Definition {
+    /// Fill in the fields this definition doesn't set from another
+    fn or(self, other: Self) -> Self {
+        Self {
+            description: self.description.or(other.description),
+            filter: self.filter.or(other.filter),
+            sort: self.sort.or(other.sort),
+            columns: self.columns.or(other.columns),
+            limit: self.limit.or(other.limit),
+        }
+    }
+}
+
+/// A named listing of tasks
+#[derive(Debug, Clone)]
+pub struct Report {
+    name: String,
+    description: String,
+    filter_text: String,
+    filter: Filter,
+    sort: Sort,
+    columns: Vec<String>,
+    limit: Option<usize>,
+}
+
+impl Report {
+    fn new(name: &str, definition: Definition) -> Result<Self, Error> {
+        let filter_text = definition.filter.unwrap_or_default();
+        let filter = Filter::parse(&filter_text).map_err(|source| Error::Filter {
+            report: name.to_string(),
+            source,
+        })?;
+        let sort = definition
+            .sort
+            .as_deref()
+            .unwrap_or_default()
+            .parse()
+            .map_err(|source| Error::Sort {
+                report: name.to_string(),
+                source,
+            })?;
+        Ok(Self {
+            name: name.to_string(),
+            description: definition.description.unwrap_or_default(),
+            filter_text,
+            filter,
+            sort,
+            columns: definition.columns.unwrap_or_default(),
+            limit: definition.limit,
+        })
+    }
+
+    /// The name the report is run by
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// What the report shows
+    #[must_use]
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// The filter expression, as written
+    #[must_use]
+    pub fn filter(&self) -> &str {
+        &self.filter_text
+    }
+
+    /// The order tasks are listed in
+    #[must_use]
+    pub const fn sort(&self) -> &Sort {
+        &self.sort
+    }
+
+    /// The names of the columns to show, which may be empty for the default
+    /// columns
+    #[must_use]
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// The most tasks to show
+    #[must_use]
+    pub const fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// The tasks in the report, also matching `extra`, sorted and limited
+    ///
+    /// Deleted tasks are left out unless either filter asks for them.
+    #[must_use]
+    pub fn run<'a>(&self, store: &'a Store, extra: &Filter) -> Vec<&'a OpenTask> {
+        let filter = self.filter.clone().and(extra.clone());
+        let mut tasks: Vec<&OpenTask> = store
+            .iter()
+            .filter(|task| !task.is_deleted() || filter.includes_deleted())
+            .filter(|task| filter.matches(task))
+            .collect();
+        self.sort.sort(&mut tasks);
+        if let Some(limit) = self.limit {
+            tasks.truncate(limit);
+        }
+        tasks
+    }
+}
+
+/// Names which can't be given to a report, as `rw` has commands called them
+pub const RESERVED: [&str; 24] = [
+    "add",
+    "list",
+    "info",
+    "done",
+    "reopen",
+    "start",
+    "stop",
+    "timesheet",
+    "reports",
+    "modify",
+    "edit",
+    "delete",
+    "purge",
+    "import",
+    "export",
+    "sync",
+    "git",
+    "backup",
+    "restore",
+    "config",
+    "context",
+    "completions",
+    "tui",
+    "help",
+];
+
+/// The built-in and configured reports, by name
+#[derive(Debug, Clone)]
+pub struct Reports {
+    reports: BTreeMap<String, Report>,
+}
+
+impl Reports {
+    /// The built-in reports, overridden and extended by definitions
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a report has a [reserved](RESERVED) name, or an
+    /// invalid filter or sort order.
+    pub fn new(definitions: &BTreeMap<String, Definition>) -> Result<Self, Error> {
+        if let Some(name) = definitions
+            .keys()
+            .find(|name| RESERVED.contains(&name.as_str()))
+        {
+            return Err(Error::Reserved(name.clone()));
+        }
+        let mut merged: BTreeMap<String, Definition> = builtin().collect();
+        for (name, definition) in definitions {
+            let builtin = merged.remove(name).unwrap_or_default();
+            merged.insert(name.clone(), definition.clone().or(builtin));
+        }
+        let reports = merged
+            .into_iter()
+            .map(|(name, definition)| Ok((name.clone(), Report::new(&name, definition)?)))
+            .collect::<Result<_, Error>>()?;
+        Ok(Self { reports })
+    }
+
+    /// The report with a name
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Report> {
+        self.reports.get(name)
+    }
+
+    /// Every report, in order of name
+    pub fn iter(&self) -> impl Iterator<Item = &Report> {
+        self.reports.values()
+    }
+}
+
+impl Default for Reports {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new()).expect("built-in reports are valid")
+    }
+}
+
+fn builtin() -> impl Iterator<Item = (String, Definition)> {
+    let columns = |names: &[&str]| Some(names.iter().map(ToString::to_string).collect());
+    [
+        (
+            "next",
+            Definition {
+                description: Some("The most important open tasks".to_string()),
+                filter: Some("status:open".to_string()),
+                sort: Some("priority-,created+".to_string()),
+                columns: columns(&[
+                    "id",
+                    "priority",
+                    "project",
+                    "tags",
+                    "description",
+                    "age",
+                    "tracked",
+                ]),
+                limit: Some(10),
+            },
+        ),
+        (
+            "overdue",
+            Definition {
+                description: Some("Open tasks with a due date before today".to_string()),
+                filter: Some("status:open due.before:today".to_string()),
+                sort: Some("priority-,created+".to_string()),
+                columns: columns(&["id", "priority", "project", "description", "age"]),
+                limit: None,
+            },
+        ),
+        (
+            "completed",
+            Definition {
+                description: Some("Completed tasks, most recent first".to_string()),
+                filter: Some("status:completed".to_string()),
+                sort: Some("completed-".to_string()),
+                columns: columns(&["id", "completed", "project", "description", "tracked"]),
+                limit: None,
+            },
+        ),
+        (
+            "all",
+            Definition {
+                description: Some("Every task which hasn't been deleted".to_string()),
+                filter: None,
+                sort: None,
+                columns: columns(&[
+                    "id",
+                    "status",
+                    "priority",
+                    "project",
+                    "tags",
+                    "description",
+                    "age",
+                ]),
+                limit: None,
+            },
+        ),
+    ]
+    .into_iter()
+    .map(|(name, definition)| (name.to_string(), definition))
+}
+
+/// Error type for defining [`Reports`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    /// A report's filter couldn't be parsed
+    #[error("report '{report}' has an invalid filter: {source}")]
+    Filter {
+        /// The name of the report
+        report: String,
+        /// Why the filter is invalid
+        source: filter::Error,
+    },
+    /// A report's sort order couldn't be parsed
+    #[error("report '{report}' has an invalid sort order: {source}")]
+    Sort {
+        /// The name of the report
+        report: String,
+        /// Why the sort order is invalid
+        source: sort::Error,
+    },
+    /// A report has the name of a command
+    #[error("'{0}' is a command, so it can't be the name of a report")]
+    Reserved(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::{Duration, Utc};
+
+    use super::{Definition, Error, Reports};
+    use crate::{Filter, Priority, Store, Task};
+
+    fn store() -> Store {
+        let yesterday = (Utc::now() - Duration::days(1)).format("%Y-%m-%d");
+        let mut store = Store::new();
+        store.push(Task::new("Low".to_string()).with_priority(Priority::Four));
+        store.push(Task::new("High".to_string()).with_priority(Priority::One));
+        store.push(
+            Task::new("Late".to_string())
+                .with_extra("due".to_string(), serde_json::json!(yesterday.to_string())),
+        );
+        store.push(Task::new("Done".to_string()).with_completed(Utc::now()));
+        store
+    }
+
+    fn run(reports: &Reports, name: &str) -> Vec<String> {
+        let store = store();
+        reports
+            .get(name)
+            .unwrap()
+            .run(&store, &Filter::default())
+            .iter()
+            .map(|task| task.description().clone())
+            .collect()
+    }
+
+    #[test]
+    fn builtin() {
+        let reports = Reports::default();
+        assert_eq!(run(&reports, "next"), ["High", "Low", "Late"]);
+        assert_eq!(run(&reports, "overdue"), ["Late"]);
+        assert_eq!(run(&reports, "completed"), ["Done"]);
+        assert_eq!(run(&reports, "all"), ["Low", "High", "Late", "Done"]);
+    }
+
+    #[test]
+    fn override_and_custom() {
+        let definitions = BTreeMap::from([
+            (
+                "next".to_string(),
+                Definition {
+                    limit: Some(1),
+                    ..Definition::default()
+                },
+            ),
+            (
+                "low".to_string(),
+                Definition {
+                    filter: Some("priority:4".to_string()),
+                    ..Definition::default()
+                },
+            ),
+        ]);
+        let reports = Reports::new(&definitions).unwrap();
+
+        assert_eq!(run(&reports, "next"), ["High"]);
+        assert_eq!(
+            reports.get("next").unwrap().sort().to_string(),
+            "priority-,created+"
+        );
+        assert_eq!(run(&reports, "low"), ["Low"]);
+        assert!(reports.get("low").unwrap().columns().is_empty());
+    }
+
+    #[test]
+    fn invalid() {
+        let definitions = BTreeMap::from([(
+            "broken".to_string(),
+            Definition {
+                sort: Some("urgency".to_string()),
+                ..Definition::default()
+            },
+        )]);
+        assert!(matches!(
+            Reports::new(&definitions),
+            Err(Error::Sort { report, .. }) if report == "broken"
+        ));
+
+        let definitions = BTreeMap::from([("done".to_string(), Definition::default())]);
+        assert!(matches!(
+            Reports::new(&definitions),
+            Err(Error::Reserved(name)) if name == "done"
+        ));
+    }
+}
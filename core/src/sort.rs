@@ -0,0 +1,289 @@
+//! Orderings of tasks, like `priority-,created+`
+//!
+//! A [`Sort`] is a list of keys separated by commas. Each key is a field
+//! name, followed by `+` for ascending order (the default) or `-` for
+//! descending order. Later keys break ties between earlier ones, and tasks
+//! which are still tied keep their order. Tasks where a field is unset come
+//! after the others, whichever the direction.
+//!
+//! Priorities ascend from least to most important, so `priority-` puts the
+//! most important tasks first.
+
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use crate::OpenTask;
+
+/// A field tasks can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    /// [`OpenTask::id`]
+    Id,
+    /// [`Task::uuid`](crate::Task::uuid)
+    Uuid,
+    /// [`Task::description`](crate::Task::description), ignoring case
+    Description,
+    /// Pending, then started, completed and deleted
+    Status,
+    /// [`Task::priority`](crate::Task::priority)
+    Priority,
+    /// [`Task::project`](crate::Task::project)
+    Project,
+    /// [`Task::created`](crate::Task::created)
+    Created,
+    /// [`Task::modified`](crate::Task::modified)
+    Modified,
+    /// [`Task::start`](crate::Task::start)
+    Start,
+    /// [`Task::end`](crate::Task::end)
+    End,
+    /// [`Task::completed`](crate::Task::completed)
+    Completed,
+    /// [`Task::deleted`](crate::Task::deleted)
+    Deleted,
+}
+
+impl Field {
+    /// Every field, in the order they are listed in help text
+    pub const ALL: [Self; 12] = [
+        Self::Id,
+        Self::Uuid,
+        Self::Description,
+        Self::Status,
+        Self::Priority,
+        Self::Project,
+        Self::Created,
+        Self::Modified,
+        Self::Start,
+        Self::End,
+        Self::Completed,
+        Self::Deleted,
+    ];
+
+    /// The name of the field, as parsed
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Uuid => "uuid",
+            Self::Description => "description",
+            Self::Status => "status",
+            Self::Priority => "priority",
+            Self::Project => "project",
+            Self::Created => "created",
+            Self::Modified => "modified",
+            Self::Start => "start",
+            Self::End => "end",
+            Self::Completed => "completed",
+            Self::Deleted => "deleted",
+        }
+    }
+
+    /// Compare two tasks by this field, or `None` if it is unset on either
+    fn compare(self, a: &OpenTask, b: &OpenTask) -> Option<Ordering> {
+        fn both<T: Ord>(a: Option<T>, b: Option<T>) -> Option<Ordering> {
+            Some(a?.cmp(&b?))
+        }
+        match self {
+            Self::Id => Some(a.id().cmp(&b.id())),
+            Self::Uuid => Some(a.uuid().cmp(&b.uuid())),
+            Self::Description => Some(
+                a.description()
+                    .to_lowercase()
+                    .cmp(&b.description().to_lowercase()),
+            ),
+            Self::Status => Some(status_rank(a).cmp(&status_rank(b))),
+            Self::Priority => {
+                both(a.priority().map(u8::from), b.priority().map(u8::from)).map(Ordering::reverse)
+            }
+            Self::Project => both(a.project(), b.project()),
+            Self::Created => Some(a.created().cmp(&b.created())),
+            Self::Modified => Some(a.modified().cmp(&b.modified())),
+            Self::Start => both(a.start(), b.start()),
+            Self::End => both(a.end(), b.end()),
+            Self::Completed => both(a.completed(), b.completed()),
+            Self::Deleted => both(a.deleted(), b.deleted()),
+        }
+    }
+
+    /// Whether the field is unset on a task
+    fn is_unset(self, task: &OpenTask) -> bool {
+        match self {
+            Self::Priority => task.priority().is_none(),
+            Self::Project => task.project().is_none(),
+            Self::Start => task.start().is_none(),
+            Self::End => task.end().is_none(),
+            Self::Completed => task.completed().is_none(),
+            Self::Deleted => task.deleted().is_none(),
+            _ => false,
+        }
+    }
+}
+
+/// Pending, then started, completed and deleted
+fn status_rank(task: &OpenTask) -> u8 {
+    match (task.is_deleted(), task.is_completed(), task.is_started()) {
+        (true, ..) => 3,
+        (false, true, _) => 2,
+        (false, false, true) => 1,
+        (false, false, false) => 0,
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Field {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|field| field.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| Error::UnknownField(s.to_string()))
+    }
+}
+
+/// One key of a [`Sort`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    /// The field to compare
+    pub field: Field,
+    /// Whether larger values come first
+    pub descending: bool,
+}
+
+/// An ordering of tasks by one or more [`Keys`](Key)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Sort {
+    keys: Vec<Key>,
+}
+
+impl Sort {
+    /// The keys, most significant first
+    #[must_use]
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+
+    /// Compare two tasks by each key in turn
+    #[must_use]
+    pub fn compare(&self, a: &OpenTask, b: &OpenTask) -> Ordering {
+        for key in &self.keys {
+            let ordering = match key.field.compare(a, b) {
+                Some(ordering) if key.descending => ordering.reverse(),
+                Some(ordering) => ordering,
+                None => key.field.is_unset(a).cmp(&key.field.is_unset(b)),
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        Ordering::Equal
+    }
+
+    /// Sort tasks in place, keeping the order of tied tasks
+    pub fn sort(&self, tasks: &mut [&OpenTask]) {
+        tasks.sort_by(|a, b| self.compare(a, b));
+    }
+}
+
+impl FromStr for Sort {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keys = s
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(|key| {
+                Ok(Key {
+                    field: key.strip_suffix(['+', '-']).unwrap_or(key).parse()?,
+                    descending: key.ends_with('-'),
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Self { keys })
+    }
+}
+
+impl fmt::Display for Sort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<String> = self
+            .keys
+            .iter()
+            .map(|key| format!("{}{}", key.field, if key.descending { '-' } else { '+' }))
+            .collect();
+        f.write_str(&keys.join(","))
+    }
+}
+
+/// Error type for parsing a [`Sort`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    /// The field can't be sorted by
+    #[error(
+        "unknown sort field '{0}'. Expected id, uuid, description, status, priority, project, \
+         created, modified, start, end, completed or deleted"
+    )]
+    UnknownField(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use test_case::test_case;
+
+    use super::{Error, Sort};
+    use crate::{OpenTask, Priority, Store, Task};
+
+    fn store() -> Store {
+        let now = Utc::now();
+        let mut store = Store::new();
+        store.push(
+            Task::new("b".to_string())
+                .with_priority(Priority::Three)
+                .with_created(now - Duration::days(2)),
+        );
+        store.push(Task::new("A".to_string()).with_created(now - Duration::days(3)));
+        store.push(
+            Task::new("c".to_string())
+                .with_priority(Priority::One)
+                .with_project("home".to_string())
+                .with_created(now - Duration::days(1)),
+        );
+        store.push(
+            Task::new("d".to_string())
+                .with_priority(Priority::Three)
+                .with_created(now - Duration::days(4)),
+        );
+        store
+    }
+
+    #[test_case("" => vec![0, 1, 2, 3] ; "none")]
+    #[test_case("id-" => vec![3, 2, 1, 0] ; "descending")]
+    #[test_case("description" => vec![1, 0, 2, 3] ; "ignoring case")]
+    #[test_case("priority-" => vec![2, 0, 3, 1] ; "most important first")]
+    #[test_case("priority+" => vec![0, 3, 2, 1] ; "unset last")]
+    #[test_case("priority-,created+" => vec![2, 3, 0, 1] ; "two keys")]
+    #[test_case("project-" => vec![2, 0, 1, 3] ; "unset last descending")]
+    #[test_case("created" => vec![3, 1, 0, 2] ; "created")]
+    fn sort(sort: &str) -> Vec<usize> {
+        let store = store();
+        let mut tasks: Vec<&OpenTask> = store.iter().collect();
+        sort.parse::<Sort>().unwrap().sort(&mut tasks);
+        tasks.iter().map(|task| task.id()).collect()
+    }
+
+    #[test]
+    fn parse() {
+        let sort: Sort = "Priority-, created".parse().unwrap();
+        assert_eq!(sort.to_string(), "priority-,created+");
+        assert_eq!(
+            "urgency-".parse::<Sort>(),
+            Err(Error::UnknownField("urgency".to_string()))
+        );
+    }
+}
@@ -5,18 +5,24 @@ use std::{
     path::Path,
 };
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-use crate::task::Task;
+use crate::{filter::Filter, task::Task};
 
+pub mod backup;
+mod merge;
 /// Path resolution for task storage
 pub mod paths;
 
+pub use self::merge::{MergeSummary, merge_files};
+
 /// A collection of [`Tasks`](Task).
 ///
 /// These are represented as [`OpenTasks`](OpenTask), which are simply a wrapper
 /// around a [`Task`] that adds a short ID field.
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Store {
     open_tasks: Vec<OpenTask>,
 }
@@ -57,9 +63,39 @@ impl Store {
         id
     }
 
-    /// Delete a task by ID
+    /// Add or update tasks, matching existing tasks by UUID
+    ///
+    /// Tasks which already exist keep their ID, so importing the same tasks
+    /// twice does not create duplicates. They are updated with the imported
+    /// version (see [`Task::update`]), so that importing an old export doesn't
+    /// revert newer changes, but a file edited since it was exported does.
+    pub fn import(&mut self, tasks: impl IntoIterator<Item = Task>) -> ImportSummary {
+        let mut summary = ImportSummary::default();
+
+        for task in tasks {
+            let Some(existing) = self.open_tasks.iter_mut().find(|t| t.uuid() == task.uuid())
+            else {
+                self.push(task);
+                summary.added += 1;
+                continue;
+            };
+
+            let merged = existing.task.update(&task);
+            if merged == existing.task {
+                summary.unchanged += 1;
+            } else {
+                existing.task = merged;
+                summary.updated += 1;
+            }
+        }
+
+        summary
+    }
+
+    /// Permanently remove a task by ID
     ///
-    /// Returns the deleted task if found, otherwise `None`.
+    /// Returns the removed task if found, otherwise `None`. To delete a task
+    /// in a way that can be synced, use [`Task::mark_deleted`].
     pub fn delete(&mut self, id: usize) -> Option<OpenTask> {
         self.open_tasks
             .iter()
@@ -67,6 +103,31 @@ impl Store {
             .map(|idx| self.open_tasks.remove(idx))
     }
 
+    /// Permanently remove tasks which were deleted before `cutoff`
+    ///
+    /// Returns the removed tasks.
+    pub fn purge(&mut self, cutoff: DateTime<Utc>) -> Vec<OpenTask> {
+        let (purged, kept) = std::mem::take(&mut self.open_tasks)
+            .into_iter()
+            .partition(|task| task.deleted().is_some_and(|deleted| deleted < cutoff));
+        self.open_tasks = kept;
+        purged
+    }
+
+    /// Stop work on every task except `id`
+    ///
+    /// Returns the IDs of the tasks which were stopped.
+    pub fn stop_others(&mut self, id: usize) -> Vec<usize> {
+        self.open_tasks
+            .iter_mut()
+            .filter(|task| task.id != id && task.is_started())
+            .map(|task| {
+                task.task.mark_stopped();
+                task.id
+            })
+            .collect()
+    }
+
     /// Get a task by ID
     ///
     /// Returns a reference to the task if found, otherwise `None`.
@@ -75,6 +136,14 @@ impl Store {
         self.open_tasks.iter().find(|t| t.id == id)
     }
 
+    /// Get a task by UUID
+    ///
+    /// Returns a reference to the task if found, otherwise `None`.
+    #[must_use]
+    pub fn get_by_uuid(&self, uuid: Uuid) -> Option<&OpenTask> {
+        self.open_tasks.iter().find(|t| t.uuid() == uuid)
+    }
+
     /// Get a mutable task by ID
     ///
     /// Returns a mutable reference to the task if found, otherwise `None`.
@@ -83,6 +152,23 @@ impl Store {
         self.open_tasks.iter_mut().find(|t| t.id == id)
     }
 
+    /// Consume the [`Store`], returning its tasks without their IDs
+    #[must_use]
+    pub fn into_tasks(self) -> Vec<Task> {
+        self.open_tasks.into_iter().map(|t| t.task).collect()
+    }
+
+    /// Iterate over the tasks matching a [`Filter`]
+    ///
+    /// Deleted tasks are left out, unless the filter asks for them (see
+    /// [`Filter::includes_deleted`]).
+    pub fn matching<'a>(&'a self, filter: &'a Filter) -> impl Iterator<Item = &'a OpenTask> + 'a {
+        self.open_tasks
+            .iter()
+            .filter(|task| !task.is_deleted() || filter.includes_deleted())
+            .filter(|task| filter.matches(task))
+    }
+
     /// Iterate over all tasks in the store
     pub fn iter(&self) -> std::slice::Iter<'_, OpenTask> {
         self.open_tasks.iter()
@@ -143,6 +229,17 @@ impl<'a> IntoIterator for &'a Store {
     }
 }
 
+/// A count of the changes made by [`Store::import`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Tasks which were not previously in the [`Store`]
+    pub added: usize,
+    /// Existing tasks which were replaced with a different version
+    pub updated: usize,
+    /// Existing tasks which were identical to the imported version
+    pub unchanged: usize,
+}
+
 /// Error type for store operations
 #[derive(Debug, thiserror::Error)]
 #[error("Failed to load tasks from file: {0}")]
@@ -193,10 +290,11 @@ impl Deref for OpenTask {
 
 #[cfg(test)]
 mod tests {
+    use chrono::{Duration, Utc};
     use tempfile::TempDir;
 
-    use super::{OpenTask, Store};
-    use crate::{store::paths, task::Task};
+    use super::{ImportSummary, OpenTask, Store};
+    use crate::{Filter, store::paths, task::Task};
 
     #[test]
     fn save_to_file() {
@@ -216,6 +314,67 @@ mod tests {
         assert_eq!(store, store2);
     }
 
+    #[test]
+    fn import_deduplicates_by_uuid() {
+        let mut store = Store::default();
+        let original = Task::new("some task".to_string());
+        let id = store.push(original.clone());
+
+        let mut updated = original.clone();
+        updated.mark_completed();
+        let new = Task::new("another task".to_string());
+
+        let summary = store.import([original, updated, new]);
+
+        assert_eq!(
+            summary,
+            ImportSummary {
+                added: 1,
+                updated: 1,
+                unchanged: 1,
+            }
+        );
+        assert_eq!(store.len(), 2);
+        assert!(store.get(id).unwrap().is_completed());
+    }
+
+    #[test]
+    fn import_keeps_newer_changes() {
+        let mut store = Store::default();
+        let original = Task::new("some task".to_string());
+        let id = store.push(original.clone());
+
+        // Exported before the task was completed here
+        let mut old = original;
+        old.set_description("old description".to_string());
+        store.get_mut(id).unwrap().task_mut().mark_completed();
+
+        let summary = store.import([old]);
+        assert_eq!(summary.unchanged, 1);
+        let task = store.get(id).unwrap();
+        assert_eq!(task.description(), "some task");
+        assert!(task.is_completed());
+
+        let mut newer = task.task().clone();
+        newer.set_description("new description".to_string());
+        let summary = store.import([newer]);
+        assert_eq!(summary.updated, 1);
+        let task = store.get(id).unwrap();
+        assert_eq!(task.description(), "new description");
+        assert!(task.is_completed());
+    }
+
+    #[test]
+    fn mutation_updates_modified() {
+        let mut store = Store::default();
+        let created = Utc::now() - Duration::hours(1);
+        let id = store.push(Task::new("some task".to_string()).with_created(created));
+
+        store.get_mut(id).unwrap().task_mut().mark_started();
+
+        assert!(store.get(id).unwrap().modified() > created);
+    }
+
     #[test]
     fn missing_id() {
         let mut store = Store::default();
@@ -246,4 +405,51 @@ mod tests {
 
         assert_eq!(store.first_missing_id(), 4);
     }
+
+    #[test]
+    fn matching() {
+        let mut store = Store::default();
+        store.push(Task::new("water plants".to_string()));
+        store.push(Task::new("water lawn".to_string()).with_deleted(Utc::now()));
+        store.push(Task::new("pay rent".to_string()));
+
+        let ids = |filter: &str| -> Vec<usize> {
+            let filter = Filter::parse(filter).unwrap();
+            store.matching(&filter).map(OpenTask::id).collect()
+        };
+        assert_eq!(ids("water"), [0]);
+        assert_eq!(ids("water status:deleted"), [1]);
+        assert_eq!(ids(""), [0, 2]);
+    }
+
+    #[test]
+    fn purge() {
+        let now = Utc::now();
+        let mut store = Store::default();
+        let old = store.push(Task::new("old".to_string()).with_deleted(now - Duration::days(40)));
+        let recent = store.push(Task::new("recent".to_string()).with_deleted(now));
+        let open = store.push(Task::new("open".to_string()));
+
+        let purged = store.purge(now - Duration::days(30));
+
+        assert_eq!(purged.len(), 1);
+        assert_eq!(purged[0].id(), old);
+        assert!(store.get(old).is_none());
+        assert!(store.get(recent).is_some());
+        assert!(store.get(open).is_some());
+    }
+
+    #[test]
+    fn stop_others() {
+        let mut store = Store::default();
+        let first = store.push(Task::new("first".to_string()));
+        let second = store.push(Task::new("second".to_string()));
+        store.get_mut(first).unwrap().task_mut().mark_started();
+        store.get_mut(second).unwrap().task_mut().mark_started();
+
+        assert_eq!(store.stop_others(second), vec![first]);
+        assert!(!store.get(first).unwrap().is_started());
+        assert_eq!(store.get(first).unwrap().intervals().len(), 1);
+        assert!(store.get(second).unwrap().is_started());
+    }
 }
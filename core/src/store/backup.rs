@@ -0,0 +1,466 @@
+//! Rotating snapshots of the tasks file
+//!
+//! Before the tasks file is overwritten, [`rotate`] copies it into the backups
+//! directory as `<timestamp>.jsonl`, then removes the snapshots which fall
+//! outside the [`Retention`] policy. The timestamp, e.g. `20240101T090000Z`,
+//! is the snapshot's ID. Later snapshots within the same second are numbered,
+//! as in `20240101T090000Z-1`.
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+
+use super::Store;
+use crate::task::Task;
+
+const ID_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const EXTENSION: &str = "jsonl";
+const DEFAULT_COUNT: usize = 20;
+
+/// How many snapshots to keep
+///
+/// A snapshot is removed once it is beyond the newest `count` snapshots, or
+/// older than `age`. A `count` of zero disables backups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retention {
+    /// The number of snapshots to keep, or `None` for no limit
+    pub count: Option<usize>,
+    /// How long to keep snapshots, or `None` for no limit
+    pub age: Option<Duration>,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            count: Some(DEFAULT_COUNT),
+            age: None,
+        }
+    }
+}
+
+impl Retention {
+    /// Whether snapshots should be taken at all
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        !matches!(self.count, Some(0))
+    }
+
+    fn keeps(&self, index: usize, snapshot: &Snapshot, now: DateTime<Utc>) -> bool {
+        self.count.is_none_or(|count| index < count)
+            && self.age.is_none_or(|age| now - snapshot.created <= age)
+    }
+}
+
+/// A copy of the tasks file in the backups directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    id: String,
+    path: PathBuf,
+    created: DateTime<Utc>,
+    /// How many snapshots were taken earlier in the same second
+    sequence: u32,
+}
+
+impl Snapshot {
+    /// The ID used to refer to the snapshot, e.g. `20240101T090000Z` or
+    /// `20240101T090000Z-1`
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The location of the snapshot
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// When the snapshot was taken
+    #[must_use]
+    pub const fn created(&self) -> DateTime<Utc> {
+        self.created
+    }
+
+    /// Load the tasks in the snapshot
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot cannot be read or parsed.
+    pub fn load(&self) -> Result<Store, super::Error> {
+        Store::load_from_path(&self.path)
+    }
+
+    fn from_path(path: PathBuf) -> Option<Self> {
+        if path.extension()? != EXTENSION {
+            return None;
+        }
+        let id = path.file_stem()?.to_str()?.to_string();
+        let (created, sequence) = match id.split_once('-') {
+            Some((created, sequence)) => (created, sequence.parse().ok()?),
+            None => (id.as_str(), 0),
+        };
+        let created = NaiveDateTime::parse_from_str(created, ID_FORMAT)
+            .ok()?
+            .and_utc();
+        Some(Self {
+            id,
+            path,
+            created,
+            sequence,
+        })
+    }
+}
+
+/// Snapshot the tasks file, then prune old snapshots
+///
+/// Call this before overwriting the tasks file. Returns the new snapshot, if
+/// one was taken. Nothing is taken if the tasks file is empty or missing, if
+/// it is identical to the latest snapshot, or if backups are disabled.
+///
+/// # Errors
+///
+/// Returns an error if the tasks file cannot be copied, or if old snapshots
+/// cannot be removed.
+pub fn rotate(
+    tasks_file: &Path,
+    backups_dir: &Path,
+    retention: &Retention,
+) -> io::Result<Option<Snapshot>> {
+    rotate_at(tasks_file, backups_dir, retention, Utc::now())
+}
+
+fn rotate_at(
+    tasks_file: &Path,
+    backups_dir: &Path,
+    retention: &Retention,
+    now: DateTime<Utc>,
+) -> io::Result<Option<Snapshot>> {
+    let snapshot = if retention.is_enabled() {
+        snapshot_at(tasks_file, backups_dir, now)?
+    } else {
+        None
+    };
+    prune_at(backups_dir, retention, now)?;
+    Ok(snapshot)
+}
+
+fn snapshot_at(
+    tasks_file: &Path,
+    backups_dir: &Path,
+    now: DateTime<Utc>,
+) -> io::Result<Option<Snapshot>> {
+    let contents = match fs::read(tasks_file) {
+        Ok(contents) if !contents.is_empty() => contents,
+        Ok(_) => return Ok(None),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    if let Some(latest) = list(backups_dir)?.first() {
+        if fs::read(&latest.path)? == contents {
+            return Ok(None);
+        }
+    }
+
+    let id = now.format(ID_FORMAT).to_string();
+    let mut path = backups_dir.join(format!("{id}.{EXTENSION}"));
+    let mut sequence = 0;
+    while path.exists() {
+        sequence += 1;
+        path = backups_dir.join(format!("{id}-{sequence}.{EXTENSION}"));
+    }
+
+    fs::create_dir_all(backups_dir)?;
+    fs::write(&path, contents)?;
+    Ok(Snapshot::from_path(path))
+}
+
+/// Remove the snapshots which fall outside the [`Retention`] policy
+///
+/// Returns the removed snapshots.
+///
+/// # Errors
+///
+/// Returns an error if the backups directory cannot be read, or a snapshot
+/// cannot be removed.
+pub fn prune(backups_dir: &Path, retention: &Retention) -> io::Result<Vec<Snapshot>> {
+    prune_at(backups_dir, retention, Utc::now())
+}
+
+fn prune_at(
+    backups_dir: &Path,
+    retention: &Retention,
+    now: DateTime<Utc>,
+) -> io::Result<Vec<Snapshot>> {
+    let mut removed = Vec::new();
+    for (index, snapshot) in list(backups_dir)?.into_iter().enumerate() {
+        if !retention.keeps(index, &snapshot, now) {
+            fs::remove_file(&snapshot.path)?;
+            removed.push(snapshot);
+        }
+    }
+    Ok(removed)
+}
+
+/// List the snapshots in the backups directory, newest first
+///
+/// Files which don't look like snapshots are ignored.
+///
+/// # Errors
+///
+/// Returns an error if the backups directory exists but cannot be read.
+pub fn list(backups_dir: &Path) -> io::Result<Vec<Snapshot>> {
+    let entries = match fs::read_dir(backups_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut snapshots = entries
+        .map(|entry| entry.map(|entry| Snapshot::from_path(entry.path())))
+        .filter_map(Result::transpose)
+        .collect::<io::Result<Vec<_>>>()?;
+    snapshots.sort_by_key(|snapshot| std::cmp::Reverse((snapshot.created, snapshot.sequence)));
+    Ok(snapshots)
+}
+
+/// Find a snapshot by its ID, or a unique prefix of it
+///
+/// # Errors
+///
+/// Returns an error if the backups directory cannot be read, or if no single
+/// snapshot matches.
+pub fn find(backups_dir: &Path, id: &str) -> Result<Snapshot, Error> {
+    let mut matches: Vec<Snapshot> = list(backups_dir)?
+        .into_iter()
+        .filter(|snapshot| snapshot.id.starts_with(id))
+        .collect();
+
+    if let Some(exact) = matches.iter().position(|snapshot| snapshot.id == id) {
+        return Ok(matches.swap_remove(exact));
+    }
+    match matches.len() {
+        0 => Err(Error::NotFound(id.to_string())),
+        1 => Ok(matches.remove(0)),
+        _ => Err(Error::Ambiguous(id.to_string())),
+    }
+}
+
+/// What restoring a snapshot would change, compared by UUID
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Changes {
+    /// Tasks which are only in the snapshot
+    pub added: Vec<Task>,
+    /// Tasks which are only in the current store
+    pub removed: Vec<Task>,
+    /// Tasks which differ, as they are in the snapshot
+    pub changed: Vec<Task>,
+}
+
+impl Changes {
+    /// Compare the current tasks with those to be restored
+    #[must_use]
+    pub fn between(current: &Store, restored: &Store) -> Self {
+        let mut current: HashMap<_, _> = current.iter().map(|t| (t.uuid(), t.task())).collect();
+        let mut changes = Self::default();
+
+        for task in restored.iter().map(super::OpenTask::task) {
+            match current.remove(&task.uuid()) {
+                Some(existing) if existing == task => {}
+                Some(_) => changes.changed.push(task.clone()),
+                None => changes.added.push(task.clone()),
+            }
+        }
+        changes.removed = current.into_values().cloned().collect();
+        changes.removed.sort_by_key(Task::created);
+
+        changes
+    }
+
+    /// Whether restoring would change nothing
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Error type for backup operations
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The backups directory couldn't be read or written
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// No snapshot has the given ID
+    #[error("no snapshot matches {0:?}")]
+    NotFound(String),
+    /// More than one snapshot starts with the given ID
+    #[error("{0:?} matches more than one snapshot")]
+    Ambiguous(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use tempfile::TempDir;
+    use test_case::test_case;
+
+    use super::{Changes, Error, Retention, find, list, prune_at, rotate_at};
+    use crate::{store::Store, task::Task};
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap() + Duration::seconds(seconds)
+    }
+
+    /// Save a store with the given descriptions, snapshotting the old file
+    fn save(dir: &TempDir, descriptions: &[&str], retention: &Retention, now: DateTime<Utc>) {
+        let tasks_file = dir.path().join("open_tasks.jsonl");
+        rotate_at(&tasks_file, &dir.path().join("backups"), retention, now).unwrap();
+
+        let mut store = Store::new();
+        for description in descriptions {
+            store.push(Task::new((*description).to_string()));
+        }
+        store.save_to_path(&tasks_file).unwrap();
+    }
+
+    fn ids(dir: &TempDir) -> Vec<String> {
+        list(&dir.path().join("backups"))
+            .unwrap()
+            .iter()
+            .map(|snapshot| snapshot.id().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn snapshots_previous_contents() {
+        let dir = TempDir::new().unwrap();
+        let retention = Retention::default();
+
+        save(&dir, &["first"], &retention, at(0));
+        assert!(ids(&dir).is_empty());
+
+        save(&dir, &["first", "second"], &retention, at(1));
+        save(&dir, &["third"], &retention, at(2));
+        assert_eq!(ids(&dir), ["20240101T090002Z", "20240101T090001Z"]);
+
+        let snapshot = find(&dir.path().join("backups"), "20240101T090001").unwrap();
+        let descriptions: Vec<String> = snapshot
+            .load()
+            .unwrap()
+            .iter()
+            .map(|task| task.description().clone())
+            .collect();
+        assert_eq!(descriptions, ["first"]);
+    }
+
+    #[test]
+    fn snapshots_every_save_within_a_second() {
+        let dir = TempDir::new().unwrap();
+        let retention = Retention::default();
+
+        save(&dir, &["first"], &retention, at(0));
+        for description in ["second", "third", "fourth"] {
+            save(&dir, &[description], &retention, at(1));
+        }
+
+        assert_eq!(
+            ids(&dir),
+            [
+                "20240101T090001Z-2",
+                "20240101T090001Z-1",
+                "20240101T090001Z"
+            ]
+        );
+        let descriptions: Vec<String> = list(&dir.path().join("backups"))
+            .unwrap()
+            .iter()
+            .map(|snapshot| {
+                snapshot
+                    .load()
+                    .unwrap()
+                    .get(0)
+                    .unwrap()
+                    .description()
+                    .clone()
+            })
+            .collect();
+        assert_eq!(descriptions, ["third", "second", "first"]);
+    }
+
+    #[test_case(Some(2), None => vec!["20240101T090500Z", "20240101T090400Z"] ; "by count")]
+    #[test_case(None, Some(150) => vec!["20240101T090500Z", "20240101T090400Z", "20240101T090300Z"] ; "by age")]
+    #[test_case(Some(1), Some(150) => vec!["20240101T090500Z"] ; "by both")]
+    #[test_case(None, None => vec!["20240101T090500Z", "20240101T090400Z", "20240101T090300Z", "20240101T090200Z", "20240101T090100Z"] ; "unlimited")]
+    fn prunes(count: Option<usize>, age: Option<i64>) -> Vec<String> {
+        let dir = TempDir::new().unwrap();
+        let unlimited = Retention {
+            count: None,
+            age: None,
+        };
+        for minute in 0..=5 {
+            save(&dir, &[&minute.to_string()], &unlimited, at(minute * 60));
+        }
+
+        let retention = Retention {
+            count,
+            age: age.map(Duration::seconds),
+        };
+        prune_at(&dir.path().join("backups"), &retention, at(5 * 60)).unwrap();
+        ids(&dir)
+    }
+
+    #[test]
+    fn disabled() {
+        let dir = TempDir::new().unwrap();
+        let retention = Retention {
+            count: Some(0),
+            age: None,
+        };
+
+        save(&dir, &["first"], &retention, at(0));
+        save(&dir, &["second"], &retention, at(1));
+        assert!(ids(&dir).is_empty());
+    }
+
+    #[test]
+    fn find_by_prefix() {
+        let dir = TempDir::new().unwrap();
+        let backups = dir.path().join("backups");
+        let retention = Retention::default();
+        save(&dir, &["first"], &retention, at(0));
+        save(&dir, &["second"], &retention, at(1));
+        save(&dir, &["third"], &retention, at(60));
+
+        assert_eq!(
+            find(&backups, "20240101T0901").unwrap().id(),
+            "20240101T090100Z"
+        );
+        assert!(matches!(find(&backups, "2024"), Err(Error::Ambiguous(_))));
+        assert!(matches!(find(&backups, "2023"), Err(Error::NotFound(_))));
+    }
+
+    #[test]
+    fn changes() {
+        let kept = Task::new("kept".to_string());
+        let edited = Task::new("edited".to_string());
+        let dropped = Task::new("dropped".to_string());
+        let mut current = Store::new();
+        current.import([kept.clone(), edited.clone(), dropped.clone()]);
+
+        let mut restored = Store::new();
+        let mut original = edited;
+        original.set_description("original".to_string());
+        let recovered = Task::new("recovered".to_string());
+        restored.import([kept, original.clone(), recovered.clone()]);
+
+        let changes = Changes::between(&current, &restored);
+        assert_eq!(changes.added, [recovered]);
+        assert_eq!(changes.removed, [dropped]);
+        assert_eq!(changes.changed, [original]);
+        assert!(Changes::between(&current, &current).is_empty());
+    }
+}
@@ -0,0 +1,254 @@
+//! Three-way merging of task collections by UUID
+
+use std::{collections::HashMap, path::Path};
+
+use uuid::Uuid;
+
+use super::{Error, OpenTask, Store};
+use crate::task::Task;
+
+/// A count of the changes applied to a [`Store`] by [`Store::merge`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MergeSummary {
+    /// Tasks which only existed on the other side
+    pub added: usize,
+    /// Tasks whose contents changed as a result of the merge
+    pub updated: usize,
+    /// Tasks which were deleted on the other side
+    pub removed: usize,
+}
+
+impl MergeSummary {
+    /// Whether the merge left the [`Store`] untouched
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.added == 0 && self.updated == 0 && self.removed == 0
+    }
+}
+
+impl Store {
+    /// Merge another replica's tasks into this [`Store`].
+    ///
+    /// Tasks are matched by UUID. `base` is the last state both sides agreed
+    /// on, and is used to tell deletions apart from additions, and to decide
+    /// which side changed each field (see [`Task::merge`]). If there is no
+    /// common history, pass an empty slice.
+    ///
+    /// A task deleted on one side is only removed if the other side has not
+    /// modified it since `base`. Existing tasks keep their IDs; tasks new to
+    /// this [`Store`] are assigned fresh ones.
+    pub fn merge(&mut self, base: &[Task], theirs: &[Task]) -> MergeSummary {
+        let base = by_uuid(base);
+        let mut theirs_by_uuid = by_uuid(theirs);
+        let mut summary = MergeSummary::default();
+
+        let open_tasks = std::mem::take(&mut self.open_tasks);
+        for OpenTask { id, task: ours } in open_tasks {
+            let ancestor = base.get(&ours.uuid()).copied();
+            let task = match (theirs_by_uuid.remove(&ours.uuid()), ancestor) {
+                (Some(theirs), ancestor) => Task::merge(ancestor, &ours, theirs),
+                (None, Some(ancestor)) if *ancestor == ours => {
+                    summary.removed += 1;
+                    continue;
+                }
+                (None, _) => ours.clone(),
+            };
+
+            if task != ours {
+                summary.updated += 1;
+            }
+            self.open_tasks.push(OpenTask { id, task });
+        }
+
+        for task in theirs {
+            if !theirs_by_uuid.contains_key(&task.uuid()) {
+                continue;
+            }
+            match base.get(&task.uuid()) {
+                Some(ancestor) if *ancestor == task => {}
+                _ => {
+                    self.push(task.clone());
+                    summary.added += 1;
+                }
+            }
+        }
+
+        summary
+    }
+}
+
+/// Three-way merge of task files, in the manner of a version control merge
+/// driver.
+///
+/// The merged result is written back to `ours`.
+///
+/// # Errors
+///
+/// Returns an error if any of the files cannot be read or parsed, or the
+/// result cannot be written.
+pub fn merge_files(
+    base: impl AsRef<Path>,
+    ours: impl AsRef<Path>,
+    theirs: impl AsRef<Path>,
+) -> Result<MergeSummary, Error> {
+    let base = Store::load_from_path(base)?.into_tasks();
+    let theirs = Store::load_from_path(theirs)?.into_tasks();
+    let mut store = Store::load_from_path(&ours)?;
+
+    let summary = store.merge(&base, &theirs);
+    store.save_to_path(ours)?;
+
+    Ok(summary)
+}
+
+fn by_uuid(tasks: &[Task]) -> HashMap<Uuid, &Task> {
+    tasks.iter().map(|task| (task.uuid(), task)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use tempfile::TempDir;
+
+    use super::{MergeSummary, merge_files};
+    use crate::{Priority, Store, Task, task::Interval};
+
+    fn tasks(store: &Store) -> Vec<Task> {
+        store.clone().into_tasks()
+    }
+
+    #[test]
+    fn additions_from_both_sides_are_kept() {
+        let mut ours = Store::new();
+        ours.push(Task::new("ours".to_string()));
+
+        let theirs = vec![Task::new("theirs".to_string())];
+
+        let summary = ours.merge(&[], &theirs);
+
+        assert_eq!(
+            summary,
+            MergeSummary {
+                added: 1,
+                ..MergeSummary::default()
+            }
+        );
+        assert_eq!(ours.len(), 2);
+        assert_eq!(ours.get(1).unwrap().description(), "theirs");
+    }
+
+    #[test]
+    fn deletions_propagate() {
+        let mut ours = Store::new();
+        ours.push(Task::new("kept".to_string()));
+        ours.push(Task::new("deleted by them".to_string()));
+        let base = tasks(&ours);
+
+        let mut theirs = base.clone();
+        theirs.remove(1);
+        let deleted_by_us = Task::new("deleted by us".to_string());
+        let base: Vec<Task> = base.into_iter().chain([deleted_by_us.clone()]).collect();
+        theirs.push(deleted_by_us);
+
+        let summary = ours.merge(&base, &theirs);
+
+        assert_eq!(summary.removed, 1);
+        assert_eq!(summary.added, 0);
+        assert_eq!(ours.len(), 1);
+        assert_eq!(ours.get(0).unwrap().description(), "kept");
+    }
+
+    #[test]
+    fn modification_wins_over_deletion() {
+        let mut ours = Store::new();
+        ours.push(Task::new("some task".to_string()));
+        let base = tasks(&ours);
+
+        ours.get_mut(0)
+            .unwrap()
+            .task_mut()
+            .set_priority(Some(Priority::Two));
+
+        let summary = ours.merge(&base, &[]);
+
+        assert!(summary.is_empty());
+        assert_eq!(ours.len(), 1);
+    }
+
+    #[test]
+    fn merged_tasks_keep_their_ids() {
+        let mut ours = Store::new();
+        ours.push(Task::new("first".to_string()));
+        ours.push(Task::new("second".to_string()));
+        let base = tasks(&ours);
+
+        let mut theirs = base.clone();
+        theirs[1].mark_completed();
+
+        let summary = ours.merge(&base, &theirs);
+
+        assert_eq!(summary.updated, 1);
+        assert!(ours.get(1).unwrap().is_completed());
+        assert!(!ours.get(0).unwrap().is_completed());
+    }
+
+    #[test]
+    fn work_tracked_on_both_sides_is_kept() {
+        let at = |time: &str| time.parse::<DateTime<Utc>>().unwrap();
+        let morning = Interval::new(at("2024-03-01T09:00:00Z"), at("2024-03-01T10:00:00Z"));
+        let noon = Interval::new(at("2024-03-01T12:00:00Z"), at("2024-03-01T12:30:00Z"));
+        let evening = Interval::new(at("2024-03-01T18:00:00Z"), at("2024-03-01T19:00:00Z"));
+
+        let mut ours = Store::new();
+        ours.push(Task::new("billable".to_string()).with_interval(morning));
+        let base = tasks(&ours);
+
+        ours.get_mut(0).unwrap().task_mut().mark_started();
+        ours.get_mut(0).unwrap().task_mut().mark_stopped();
+        let mut theirs = base.clone();
+        theirs[0] = theirs[0].clone().with_interval(evening).with_interval(noon);
+
+        ours.merge(&base, &theirs);
+
+        let task = ours.get(0).unwrap();
+        assert_eq!(task.intervals().len(), 4);
+        assert_eq!(&task.intervals()[..3], [morning, noon, evening]);
+    }
+
+    #[test]
+    fn merge_files_writes_result_to_ours() {
+        let dir = TempDir::new().expect("unable to create temporary directory");
+        let [base_file, ours_file, theirs_file] =
+            ["base", "ours", "theirs"].map(|name| dir.path().join(name));
+
+        let mut base = Store::new();
+        base.push(Task::new("shared".to_string()));
+        base.save_to_path(&base_file).unwrap();
+
+        // Both sides add a task, which lands on the same ID
+        let mut ours = base.clone();
+        ours.push(Task::new("ours".to_string()));
+        ours.save_to_path(&ours_file).unwrap();
+
+        let mut theirs = base.clone();
+        theirs.push(Task::new("theirs".to_string()));
+        theirs.get_mut(0).unwrap().task_mut().mark_completed();
+        theirs.save_to_path(&theirs_file).unwrap();
+
+        let summary = merge_files(&base_file, &ours_file, &theirs_file).unwrap();
+        assert_eq!(
+            summary,
+            MergeSummary {
+                added: 1,
+                updated: 1,
+                removed: 0
+            }
+        );
+
+        let merged = Store::load_from_path(&ours_file).unwrap();
+        assert_eq!(merged.len(), 3);
+        assert!(merged.get(0).unwrap().is_completed());
+        assert_eq!(merged.get(1).unwrap().description(), "ours");
+        assert_eq!(merged.get(2).unwrap().description(), "theirs");
+    }
+}
@@ -5,16 +5,23 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use crate::Config;
+
 const OPEN_TASKS_FILE: &str = "open_tasks.jsonl";
+const SYNC_STATE_FILE: &str = "sync_state.json";
+const BACKUPS_DIR: &str = "backups";
+const CONFIG_FILE: &str = "config.toml";
 
 /// Get the data directory for storing tasks
 ///
-/// Respects the `RUSTWARRIOR_DATA_DIR` environment variable if set.
-/// Otherwise, uses the default platform-specific data directory.
+/// Respects the `RUSTWARRIOR_DATA_DIR` environment variable if set, then
+/// `data_dir` in the [config file](crate::config). Otherwise, uses the
+/// default platform-specific data directory.
 ///
 /// # Errors
 ///
-/// Returns an error if the data directory cannot be determined or created.
+/// Returns an error if the data directory cannot be determined or created,
+/// or the config file is invalid.
 pub fn get_data_dir() -> io::Result<PathBuf> {
     if let Ok(custom_dir) = std::env::var("RUSTWARRIOR_DATA_DIR") {
         let path = PathBuf::from(custom_dir);
@@ -22,14 +29,61 @@ pub fn get_data_dir() -> io::Result<PathBuf> {
         return Ok(path);
     }
 
-    let dir = dirs::data_dir()
-        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "couldn't find data directory"))?
-        .join("rustwarrior");
+    let config = Config::load(&get_config_file()?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    get_configured_data_dir(&config)
+}
+
+/// Get the data directory set in an already loaded [`Config`], or the
+/// default one
+///
+/// Unlike [`get_data_dir`], the environment isn't consulted, as
+/// [`Config::load_default`] has already applied it.
+///
+/// # Errors
+///
+/// Returns an error if the data directory cannot be determined or created.
+pub fn get_configured_data_dir(config: &Config) -> io::Result<PathBuf> {
+    let dir = match config.data_dir() {
+        Some(dir) => dir,
+        None => default_data_dir()?,
+    };
 
     fs::create_dir_all(&dir)?;
     Ok(dir)
 }
 
+/// Get the platform-specific data directory, used unless another is
+/// configured
+///
+/// # Errors
+///
+/// Returns an error if the platform has no data directory.
+pub fn default_data_dir() -> io::Result<PathBuf> {
+    Ok(dirs::data_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "couldn't find data directory"))?
+        .join("rustwarrior"))
+}
+
+/// Get the path to the config file
+///
+/// Respects the `RUSTWARRIOR_CONFIG` environment variable if set. Otherwise,
+/// uses `rustwarrior/config.toml` in the platform-specific config directory.
+/// The file may not exist.
+///
+/// # Errors
+///
+/// Returns an error if the config directory cannot be determined.
+pub fn get_config_file() -> io::Result<PathBuf> {
+    if let Ok(custom_file) = std::env::var("RUSTWARRIOR_CONFIG") {
+        return Ok(PathBuf::from(custom_file));
+    }
+
+    let dir = dirs::config_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "couldn't find config directory"))?;
+    Ok(dir.join("rustwarrior").join(CONFIG_FILE))
+}
+
 /// Get the full path to the tasks file
 ///
 /// # Arguments
@@ -41,13 +95,47 @@ pub fn get_data_dir() -> io::Result<PathBuf> {
 ///
 /// Returns an error if the data directory cannot be determined.
 pub fn get_tasks_file(data_dir: Option<&Path>) -> io::Result<PathBuf> {
+    data_file(data_dir, OPEN_TASKS_FILE)
+}
+
+/// Get the full path to the file recording the state of the last sync
+///
+/// # Arguments
+///
+/// * `data_dir` - Optional custom data directory. If None, uses
+///   `get_data_dir()`
+///
+/// # Errors
+///
+/// Returns an error if the data directory cannot be determined.
+pub fn get_sync_state_file(data_dir: Option<&Path>) -> io::Result<PathBuf> {
+    data_file(data_dir, SYNC_STATE_FILE)
+}
+
+/// Get the directory holding snapshots of the tasks file
+///
+/// See [`backup`](super::backup).
+///
+/// # Arguments
+///
+/// * `data_dir` - Optional custom data directory. If None, uses
+///   `get_data_dir()`
+///
+/// # Errors
+///
+/// Returns an error if the data directory cannot be determined.
+pub fn get_backups_dir(data_dir: Option<&Path>) -> io::Result<PathBuf> {
+    data_file(data_dir, BACKUPS_DIR)
+}
+
+fn data_file(data_dir: Option<&Path>, name: &str) -> io::Result<PathBuf> {
     let dir = if let Some(d) = data_dir {
         d.to_path_buf()
     } else {
         get_data_dir()?
     };
 
-    Ok(dir.join(OPEN_TASKS_FILE))
+    Ok(dir.join(name))
 }
 
 #[cfg(test)]
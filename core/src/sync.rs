@@ -0,0 +1,96 @@
+//! Wire types and client state for synchronising replicas via a sync server
+//!
+//! The protocol is deliberately simple. The server holds a single versioned
+//! [`Snapshot`] of the task list. A client pulls the snapshot, merges it into
+//! its local [`Store`](crate::Store) using the state recorded at the last
+//! successful sync as the common ancestor, and pushes the result back along
+//! with the version it merged against. If another client pushed in the
+//! meantime the server rejects the push and the client starts over.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{store::Error, task::Task};
+
+/// A versioned copy of the full task list
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Incremented by the server on each accepted push
+    pub version: u64,
+    /// Every task known to the server
+    pub tasks: Vec<Task>,
+}
+
+/// What a client remembers between syncs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SyncState {
+    /// The server this replica syncs with
+    pub server: String,
+    /// The server's state as of the last successful sync
+    ///
+    /// Used as the common ancestor for the next merge.
+    pub base: Snapshot,
+}
+
+impl SyncState {
+    /// Start tracking a new server, with no shared history
+    #[must_use]
+    pub fn new(server: String) -> Self {
+        Self {
+            server,
+            base: Snapshot::default(),
+        }
+    }
+
+    /// Load the sync state from a file, if it exists
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub fn load_from_path(path: impl AsRef<Path>) -> Result<Option<Self>, Error> {
+        match File::open(path) {
+            Ok(file) => Ok(Some(serde_json::from_reader(BufReader::new(file))?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Save the sync state to a file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save_to_path(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::SyncState;
+    use crate::Task;
+
+    #[test]
+    fn state_round_trip() {
+        let dir = TempDir::new().expect("unable to create temporary directory");
+        let path = dir.path().join("sync_state.json");
+
+        assert_eq!(SyncState::load_from_path(&path).unwrap(), None);
+
+        let mut state = SyncState::new("http://localhost:8765".to_string());
+        state.base.version = 3;
+        state.base.tasks.push(Task::new("some task".to_string()));
+        state.save_to_path(&path).unwrap();
+
+        assert_eq!(SyncState::load_from_path(&path).unwrap(), Some(state));
+    }
+}
@@ -1,9 +1,14 @@
-use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-pub use self::priority::Priority;
+pub use self::{interval::Interval, priority::Priority};
 
+mod interval;
+mod merge;
+pub mod modification;
 mod priority;
 
 /// A task to be completed
@@ -13,9 +18,25 @@ pub struct Task {
     created: DateTime<Utc>,
     description: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    modified: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    start: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    intervals: Vec<Interval>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    end: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     completed: Option<DateTime<Utc>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    deleted: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     priority: Option<Priority>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    tags: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    extra: BTreeMap<String, serde_json::Value>,
 }
 
 impl Task {
@@ -24,14 +45,30 @@ impl Task {
     pub fn new(description: String) -> Self {
         let uuid = Uuid::new_v4();
         let created = Utc::now();
+        let modified = None;
+        let start = None;
+        let intervals = Vec::new();
+        let end = None;
         let completed = None;
+        let deleted = None;
         let priority = None;
+        let project = None;
+        let tags = BTreeSet::new();
+        let extra = BTreeMap::new();
         Self {
             uuid,
             created,
             description,
+            modified,
+            start,
+            intervals,
+            end,
             completed,
+            deleted,
             priority,
+            project,
+            tags,
+            extra,
         }
     }
 
@@ -41,18 +78,49 @@ impl Task {
         self.uuid
     }
 
+    /// Set the UUID of the [`Task`]
+    ///
+    /// Useful when importing a task from another tool, to preserve its
+    /// identity.
+    #[must_use]
+    pub const fn with_uuid(mut self, uuid: Uuid) -> Self {
+        self.uuid = uuid;
+        self
+    }
+
     /// The creation timestamp of the [`Task`]
     #[must_use]
     pub const fn created(&self) -> DateTime<Utc> {
         self.created
     }
 
+    /// Set the creation timestamp of the [`Task`]
+    #[must_use]
+    pub const fn with_created(mut self, created: DateTime<Utc>) -> Self {
+        self.created = created;
+        self
+    }
+
+    /// The timestamp of the last modification to the [`Task`]
+    ///
+    /// Tasks which have never been modified report their creation time.
+    #[must_use]
+    pub fn modified(&self) -> DateTime<Utc> {
+        self.modified.unwrap_or(self.created)
+    }
+
     /// The description of the [`Task`]
     #[must_use]
     pub const fn description(&self) -> &String {
         &self.description
     }
 
+    /// Set the description of this task
+    pub fn set_description(&mut self, description: String) {
+        self.description = description;
+        self.touch();
+    }
+
     /// Set the priority of the [`Task`]
     #[must_use]
     pub const fn with_priority(mut self, priority: Priority) -> Self {
@@ -67,8 +135,9 @@ impl Task {
     }
 
     /// Set the priority of this task
-    pub const fn set_priority(&mut self, priority: Option<Priority>) {
+    pub fn set_priority(&mut self, priority: Option<Priority>) {
         self.priority = priority;
+        self.touch();
     }
 
     /// Check if the task is completed
@@ -83,32 +152,257 @@ impl Task {
         self.completed
     }
 
+    /// Set the completion timestamp of the [`Task`], which also closes it
+    #[must_use]
+    pub const fn with_completed(mut self, completed: DateTime<Utc>) -> Self {
+        self.completed = Some(completed);
+        self.end = Some(completed);
+        self
+    }
+
     /// Mark the task as completed
+    ///
+    /// This also stops work on the task, and records the time it ended.
     pub fn mark_completed(&mut self) {
         if self.completed.is_none() {
-            self.completed = Some(Utc::now());
+            let now = Utc::now();
+            self.completed = Some(now);
+            self.end = Some(now);
+            self.close_interval(now);
+            self.modified = Some(now);
         }
     }
 
     /// Mark the task as active (not completed)
-    pub const fn mark_active(&mut self) {
-        self.completed = None;
+    pub fn mark_active(&mut self) {
+        if self.completed.is_some() {
+            self.completed = None;
+            self.end = None;
+            self.touch();
+        }
+    }
+
+    /// The time the [`Task`] was deleted, if it has been
+    ///
+    /// Deleted tasks are kept until they are purged, so that a deletion can
+    /// be synced to other replicas.
+    #[must_use]
+    pub const fn deleted(&self) -> Option<DateTime<Utc>> {
+        self.deleted
+    }
+
+    /// Check if the task is deleted
+    #[must_use]
+    pub const fn is_deleted(&self) -> bool {
+        self.deleted.is_some()
+    }
+
+    /// Set the deletion timestamp of the [`Task`], which also closes it
+    #[must_use]
+    pub const fn with_deleted(mut self, deleted: DateTime<Utc>) -> Self {
+        self.deleted = Some(deleted);
+        self.end = Some(deleted);
+        self
+    }
+
+    /// Mark the task as deleted
+    ///
+    /// This also stops work on the task, and records the time it ended.
+    pub fn mark_deleted(&mut self) {
+        if self.deleted.is_none() {
+            let now = Utc::now();
+            self.deleted = Some(now);
+            self.end = Some(now);
+            self.close_interval(now);
+            self.modified = Some(now);
+        }
+    }
+
+    /// The time work on the [`Task`] started, if it is in progress
+    #[must_use]
+    pub const fn start(&self) -> Option<DateTime<Utc>> {
+        self.start
+    }
+
+    /// Check if work on the task is in progress
+    #[must_use]
+    pub const fn is_started(&self) -> bool {
+        self.start.is_some()
+    }
+
+    /// Mark work on the task as started
+    ///
+    /// Has no effect if the task is already started.
+    pub fn mark_started(&mut self) {
+        if self.start.is_none() {
+            let now = Utc::now();
+            self.start = Some(now);
+            self.modified = Some(now);
+        }
+    }
+
+    /// Mark work on the task as stopped
+    ///
+    /// The time since it started is recorded as an [`Interval`].
+    pub fn mark_stopped(&mut self) {
+        if self.start.is_some() {
+            let now = Utc::now();
+            self.close_interval(now);
+            self.modified = Some(now);
+        }
+    }
+
+    fn close_interval(&mut self, now: DateTime<Utc>) {
+        if let Some(start) = self.start.take() {
+            self.intervals.push(Interval::new(start, now));
+        }
+    }
+
+    /// The finished periods of work on the [`Task`], oldest first
+    #[must_use]
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    /// Record a finished period of work on the [`Task`]
+    #[must_use]
+    pub fn with_interval(mut self, interval: Interval) -> Self {
+        self.intervals.push(interval);
+        self
+    }
+
+    /// The total time worked on the [`Task`] up to `now`, including work in
+    /// progress
+    #[must_use]
+    pub fn tracked(&self, now: DateTime<Utc>) -> Duration {
+        let running = self
+            .start
+            .map_or_else(Duration::zero, |start| (now - start).max(Duration::zero()));
+        self.intervals
+            .iter()
+            .map(Interval::duration)
+            .fold(running, |total, duration| total + duration)
+    }
+
+    /// The time the [`Task`] was closed, if it has been
+    #[must_use]
+    pub const fn end(&self) -> Option<DateTime<Utc>> {
+        self.end
+    }
+
+    /// The project the [`Task`] belongs to
+    #[must_use]
+    pub fn project(&self) -> Option<&str> {
+        self.project.as_deref()
+    }
+
+    /// Set the project of the [`Task`]
+    #[must_use]
+    pub fn with_project(mut self, project: String) -> Self {
+        self.project = Some(project);
+        self
+    }
+
+    /// Set the project of this task
+    pub fn set_project(&mut self, project: Option<String>) {
+        self.project = project;
+        self.touch();
+    }
+
+    /// The tags attached to the [`Task`]
+    #[must_use]
+    pub const fn tags(&self) -> &BTreeSet<String> {
+        &self.tags
+    }
+
+    /// Attach a tag to the [`Task`]
+    #[must_use]
+    pub fn with_tag(mut self, tag: String) -> Self {
+        self.tags.insert(tag);
+        self
+    }
+
+    /// Check whether the [`Task`] has a given tag
+    #[must_use]
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    /// Attach a tag to the [`Task`]
+    ///
+    /// Returns `false` if the tag was already present.
+    pub fn add_tag(&mut self, tag: String) -> bool {
+        let added = self.tags.insert(tag);
+        if added {
+            self.touch();
+        }
+        added
+    }
+
+    /// Remove a tag from the [`Task`]
+    ///
+    /// Returns `false` if the tag was not present.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let removed = self.tags.remove(tag);
+        if removed {
+            self.touch();
+        }
+        removed
+    }
+
+    /// Attributes carried over from other tools, which have no equivalent in
+    /// a [`Task`]
+    ///
+    /// These are kept so that they survive a round trip through `RustWarrior`.
+    #[must_use]
+    pub const fn extra(&self) -> &BTreeMap<String, serde_json::Value> {
+        &self.extra
+    }
+
+    /// Set an extra attribute (see [`Task::extra`])
+    #[must_use]
+    pub fn with_extra(mut self, key: String, value: serde_json::Value) -> Self {
+        self.extra.insert(key, value);
+        self
+    }
+
+    /// Set an extra attribute (see [`Task::extra`])
+    pub fn set_extra(&mut self, key: String, value: serde_json::Value) {
+        self.extra.insert(key, value);
+        self.touch();
+    }
+
+    /// Remove an extra attribute (see [`Task::extra`])
+    pub fn remove_extra(&mut self, key: &str) -> Option<serde_json::Value> {
+        let value = self.extra.remove(key);
+        if value.is_some() {
+            self.touch();
+        }
+        value
+    }
+
+    /// Record that the [`Task`] has just been modified
+    pub fn touch(&mut self) {
+        self.modified = Some(Utc::now());
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        str::FromStr,
+    };
 
-    use chrono::{DateTime, Utc};
+    use chrono::{DateTime, Duration, Utc};
     use test_case::test_case;
     use uuid::uuid;
 
-    use super::{Priority, Task};
+    use super::{Interval, Priority, Task};
 
     #[test_case(
         r#"{"uuid":"ee00fce2-f349-42b1-969e-17d4c6c612f5","created":"2023-01-14T09:57:04.275194707Z","description":"some made up task"}"#
-        => Task {uuid: uuid!("ee00fce2-f349-42b1-969e-17d4c6c612f5"), created: DateTime::<Utc>::from_str("2023-01-14T09:57:04.275194707Z").unwrap(), description: "some made up task".to_string(), completed: None, priority: None}
+        => Task {uuid: uuid!("ee00fce2-f349-42b1-969e-17d4c6c612f5"), created: DateTime::<Utc>::from_str("2023-01-14T09:57:04.275194707Z").unwrap(), description: "some made up task".to_string(), modified: None, start: None, intervals: Vec::new(), end: None, completed: None, deleted: None, priority: None, project: None, tags: BTreeSet::new(), extra: BTreeMap::new()}
         ; "minimal"
     )]
     #[test_case(
@@ -117,8 +411,16 @@ mod tests {
             uuid: uuid!("ee00fce2-f349-42b1-969e-17d4c6c612f5"),
             created: DateTime::<Utc>::from_str("2023-01-14T09:57:04.275194707Z").unwrap(),
             description: "some made up task".to_string(),
+            modified: None,
+            start: None,
+            intervals: Vec::new(),
+            end: None,
             completed: None,
-            priority: Some(Priority::Two)
+            deleted: None,
+            priority: Some(Priority::Two),
+            project: None,
+            tags: BTreeSet::new(),
+            extra: BTreeMap::new(),
         }
         ; "priority"
     )]
@@ -126,6 +428,52 @@ mod tests {
         serde_json::from_str(input).unwrap()
     }
 
+    #[test]
+    fn lifecycle_timestamps() {
+        let mut task = Task::new("description".to_string());
+        assert_eq!(task.modified(), task.created());
+
+        task.mark_started();
+        let started = task.start().unwrap();
+        assert_eq!(task.modified(), started);
+
+        task.mark_completed();
+        assert!(!task.is_started());
+        assert_eq!(task.end(), task.completed());
+        assert!(task.modified() >= started);
+
+        task.mark_active();
+        assert!(task.end().is_none());
+
+        task.mark_started();
+        task.mark_deleted();
+        assert!(task.is_deleted());
+        assert!(!task.is_started());
+        assert_eq!(task.end(), task.deleted());
+    }
+
+    #[test]
+    fn tracked_time() {
+        let now = Utc::now();
+        let mut task = Task::new("description".to_string()).with_interval(Interval::new(
+            now - Duration::hours(3),
+            now - Duration::hours(2),
+        ));
+        assert_eq!(task.tracked(now), Duration::hours(1));
+
+        task.mark_started();
+        let later = task.start().unwrap() + Duration::minutes(10);
+        assert_eq!(task.tracked(later), Duration::minutes(70));
+
+        task.mark_stopped();
+        assert_eq!(task.intervals().len(), 2);
+        assert!(!task.is_started());
+
+        task.mark_started();
+        task.mark_completed();
+        assert_eq!(task.intervals().len(), 3);
+    }
+
     #[test]
     fn priority() {
         let task = Task::new("description".to_string());
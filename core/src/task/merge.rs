@@ -0,0 +1,317 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, SubsecRound, Utc};
+
+use super::{Interval, Task};
+
+impl Task {
+    /// Merge two divergent versions of the same [`Task`].
+    ///
+    /// Each field is merged independently. A field changed on only one side
+    /// (relative to `base`) takes that side's value. Where both sides changed
+    /// a field, or no common ancestor is known, the most recently modified
+    /// version wins. Tags and extra attributes are merged one by one, so that
+    /// tags added on both sides are all kept, and the work intervals of both
+    /// sides are combined.
+    #[must_use]
+    pub fn merge(base: Option<&Self>, ours: &Self, theirs: &Self) -> Self {
+        let merger = FieldMerger {
+            base,
+            ours,
+            theirs,
+            theirs_newer: theirs.modified() > ours.modified(),
+        };
+
+        Self {
+            uuid: ours.uuid,
+            created: merger.pick(|t| &t.created),
+            description: merger.pick(|t| &t.description),
+            modified: ours.modified.max(theirs.modified),
+            start: merger.pick(|t| &t.start),
+            intervals: merger.union_intervals(),
+            end: merger.pick(|t| &t.end),
+            completed: merger.pick(|t| &t.completed),
+            deleted: merger.pick(|t| &t.deleted),
+            priority: merger.pick(|t| &t.priority),
+            project: merger.pick(|t| &t.project),
+            tags: merger.pick_set(|t| &t.tags),
+            extra: merger.pick_map(|t| &t.extra),
+        }
+    }
+
+    /// Update a [`Task`] with a version of it read from a file.
+    ///
+    /// A version with a modification time of its own is merged (see
+    /// [`Task::merge`]), so that an old export doesn't revert newer changes.
+    /// A version with no modification time, or the one this task already has,
+    /// was edited somewhere that doesn't record one: every field which differs
+    /// takes its value, and the task is touched if anything changed. Times
+    /// which only differ below a second are the same, as most formats don't
+    /// write fractions of a second. Work intervals from both are kept.
+    #[must_use]
+    pub fn update(&self, theirs: &Self) -> Self {
+        if theirs
+            .modified
+            .is_some_and(|modified| !same_time(self.modified(), modified))
+        {
+            return Self::merge(None, self, theirs);
+        }
+
+        let pick = |ours: Option<DateTime<Utc>>, theirs: Option<DateTime<Utc>>| match (ours, theirs)
+        {
+            (Some(ours), Some(theirs)) if same_time(ours, theirs) => Some(ours),
+            _ => theirs,
+        };
+        let intervals: BTreeSet<_> = self
+            .intervals
+            .iter()
+            .chain(theirs.intervals.iter().filter(|interval| {
+                !self.intervals.iter().any(|ours| {
+                    same_time(ours.start(), interval.start())
+                        && same_time(ours.end(), interval.end())
+                })
+            }))
+            .map(|interval| (interval.start(), interval.end()))
+            .collect();
+
+        let mut task = Self {
+            uuid: self.uuid,
+            created: pick(Some(self.created), Some(theirs.created)).unwrap_or(self.created),
+            description: theirs.description.clone(),
+            modified: self.modified,
+            start: pick(self.start, theirs.start),
+            intervals: intervals
+                .into_iter()
+                .map(|(start, end)| Interval::new(start, end))
+                .collect(),
+            end: pick(self.end, theirs.end),
+            completed: pick(self.completed, theirs.completed),
+            deleted: pick(self.deleted, theirs.deleted),
+            priority: theirs.priority,
+            project: theirs.project.clone(),
+            tags: theirs.tags.clone(),
+            extra: theirs.extra.clone(),
+        };
+        if task != *self {
+            task.touch();
+        }
+        task
+    }
+}
+
+/// Whether two times are the same, allowing `theirs` to have been written
+/// without fractions of a second
+fn same_time(ours: DateTime<Utc>, theirs: DateTime<Utc>) -> bool {
+    ours == theirs || ours.trunc_subsecs(0) == theirs
+}
+
+struct FieldMerger<'a> {
+    base: Option<&'a Task>,
+    ours: &'a Task,
+    theirs: &'a Task,
+    theirs_newer: bool,
+}
+
+impl FieldMerger<'_> {
+    fn pick<T: PartialEq + Clone>(&self, field: impl Fn(&Task) -> &T) -> T {
+        let ours = field(self.ours);
+        let theirs = field(self.theirs);
+
+        if self.take_theirs(self.base.map(&field), ours, theirs) {
+            theirs.clone()
+        } else {
+            ours.clone()
+        }
+    }
+
+    /// All the work tracked on either side, oldest first
+    ///
+    /// Intervals are only ever added, so none are lost to a conflict.
+    fn union_intervals(&self) -> Vec<Interval> {
+        let intervals: BTreeSet<_> = self
+            .ours
+            .intervals
+            .iter()
+            .chain(&self.theirs.intervals)
+            .map(|interval| (interval.start(), interval.end()))
+            .collect();
+        intervals
+            .into_iter()
+            .map(|(start, end)| Interval::new(start, end))
+            .collect()
+    }
+
+    /// Merge each element of a set on its own, by whether it is present
+    fn pick_set<T: Ord + Clone>(&self, field: impl Fn(&Task) -> &BTreeSet<T>) -> BTreeSet<T> {
+        let ours = field(self.ours);
+        let theirs = field(self.theirs);
+        let base = self.base.map(&field);
+
+        ours.union(theirs)
+            .filter(|element| {
+                let base = base.map(|base| base.contains(*element));
+                let (ours, theirs) = (ours.contains(*element), theirs.contains(*element));
+                if self.take_theirs(base.as_ref(), &ours, &theirs) {
+                    theirs
+                } else {
+                    ours
+                }
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Merge each entry of a map on its own, by its value or absence
+    fn pick_map<K: Ord + Clone, V: PartialEq + Clone>(
+        &self,
+        field: impl Fn(&Task) -> &BTreeMap<K, V>,
+    ) -> BTreeMap<K, V> {
+        let ours = field(self.ours);
+        let theirs = field(self.theirs);
+        let base = self.base.map(&field);
+
+        let keys: BTreeSet<&K> = ours.keys().chain(theirs.keys()).collect();
+        keys.into_iter()
+            .filter_map(|key| {
+                let base = base.map(|base| base.get(key));
+                let (ours, theirs) = (ours.get(key), theirs.get(key));
+                let value = if self.take_theirs(base.as_ref(), &ours, &theirs) {
+                    theirs
+                } else {
+                    ours
+                };
+                value.map(|value| (key.clone(), value.clone()))
+            })
+            .collect()
+    }
+
+    /// Whether a value should come from `theirs` rather than `ours`
+    fn take_theirs<T: PartialEq>(&self, base: Option<&T>, ours: &T, theirs: &T) -> bool {
+        match base {
+            _ if ours == theirs => false,
+            Some(base) if base == ours => true,
+            Some(base) if base == theirs => false,
+            _ => self.theirs_newer,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, SubsecRound, Utc};
+    use serde_json::json;
+
+    use crate::{Priority, Task};
+
+    #[test]
+    fn non_conflicting_fields_are_combined() {
+        let base = Task::new("some task".to_string());
+
+        let mut ours = base.clone();
+        ours.mark_completed();
+
+        let mut theirs = base.clone();
+        theirs.set_priority(Some(Priority::One));
+
+        let merged = Task::merge(Some(&base), &ours, &theirs);
+
+        assert!(merged.is_completed());
+        assert_eq!(merged.priority(), Some(Priority::One));
+    }
+
+    #[test]
+    fn conflicting_field_takes_latest_writer() {
+        let base = Task::new("some task".to_string());
+
+        let mut ours = base.clone();
+        ours.set_priority(Some(Priority::One));
+        ours.modified = Some(Utc::now() - Duration::minutes(5));
+
+        let mut theirs = base.clone();
+        theirs.set_priority(Some(Priority::Four));
+
+        let merged = Task::merge(Some(&base), &ours, &theirs);
+        assert_eq!(merged.priority(), Some(Priority::Four));
+        assert_eq!(merged.modified(), theirs.modified());
+
+        let merged = Task::merge(Some(&base), &theirs, &ours);
+        assert_eq!(merged.priority(), Some(Priority::Four));
+    }
+
+    #[test]
+    fn update_takes_edits_without_a_modification_time() {
+        let mut ours = Task::new("some task".to_string())
+            .with_tag("home".to_string())
+            .with_extra("room".to_string(), json!("kitchen"));
+        ours.mark_started();
+
+        // Written out and edited elsewhere, keeping the modification time
+        let mut theirs = ours.clone();
+        theirs.created = ours.created.trunc_subsecs(0);
+        theirs.start = ours.start.map(|start| start.trunc_subsecs(0));
+        theirs.description = "edited task".to_string();
+        theirs.tags.clear();
+        theirs.extra.clear();
+
+        let updated = ours.update(&theirs);
+        assert_eq!(updated.description(), "edited task");
+        assert!(updated.tags().is_empty());
+        assert!(updated.extra().is_empty());
+        assert_eq!(updated.created(), ours.created());
+        assert_eq!(updated.start(), ours.start());
+        assert!(updated.modified() >= ours.modified());
+        assert_ne!(updated.modified, ours.modified);
+
+        theirs.modified = None;
+        assert_eq!(ours.update(&theirs).description(), "edited task");
+
+        let mut unedited = ours.clone();
+        unedited.created = ours.created.trunc_subsecs(0);
+        assert_eq!(ours.update(&unedited), ours);
+    }
+
+    #[test]
+    fn update_merges_versions_with_a_modification_time() {
+        let mut ours = Task::new("some task".to_string());
+        ours.set_priority(Some(Priority::One));
+
+        let mut old = ours.clone();
+        old.set_description("old description".to_string());
+        old.modified = Some(ours.modified() - Duration::minutes(5));
+
+        let updated = ours.update(&old);
+        assert_eq!(updated, Task::merge(None, &ours, &old));
+        assert_eq!(updated.description(), "some task");
+    }
+
+    #[test]
+    fn tags_and_extras_are_merged_one_by_one() {
+        let base = Task::new("some task".to_string())
+            .with_tag("home".to_string())
+            .with_tag("errand".to_string())
+            .with_extra("room".to_string(), json!("kitchen"))
+            .with_extra("size".to_string(), json!("small"));
+
+        let mut ours = base.clone();
+        ours.add_tag("work".to_string());
+        ours.remove_tag("errand");
+        ours.set_extra("room".to_string(), json!("office"));
+        ours.modified = Some(Utc::now() - Duration::minutes(5));
+
+        let mut theirs = base.clone();
+        theirs.add_tag("urgent".to_string());
+        theirs.remove_extra("size");
+        theirs.set_extra("owner".to_string(), json!("sam"));
+
+        for merged in [
+            Task::merge(Some(&base), &ours, &theirs),
+            Task::merge(Some(&base), &theirs, &ours),
+        ] {
+            let tags: Vec<&str> = merged.tags().iter().map(String::as_str).collect();
+            assert_eq!(tags, ["home", "urgent", "work"]);
+            assert_eq!(merged.extra().get("room"), Some(&json!("office")));
+            assert_eq!(merged.extra().get("owner"), Some(&json!("sam")));
+            assert_eq!(merged.extra().get("size"), None);
+        }
+    }
+}
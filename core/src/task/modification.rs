@@ -0,0 +1,278 @@
+//! Partial updates to a [`Task`], written as command-line words
+//!
+//! Each word is one [`Change`]:
+//!
+//! - `+tag` adds a tag, and `-tag` removes it
+//! - `priority:2` sets the priority, and `priority:` clears it
+//! - `project:home` sets the project, and `project:` clears it
+//! - `description:text` replaces the description
+//! - `name:value` sets any other attribute, and `name:` removes it
+//!
+//! Any other words are joined with spaces into a new description. The
+//! attributes which `rw` keeps itself, such as `status`, `uuid` and the dates
+//! (see [`RESERVED`]), can't be set this way.
+
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use super::{Priority, Task};
+
+/// Attribute names which can't be set with `name:value`, as they aren't extra
+/// attributes
+pub const RESERVED: [&str; 12] = [
+    "id",
+    "uuid",
+    "status",
+    "created",
+    "modified",
+    "start",
+    "intervals",
+    "end",
+    "completed",
+    "deleted",
+    "tags",
+    "extra",
+];
+
+/// A single change to a [`Task`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Replace the description
+    Description(String),
+    /// Set or clear the priority
+    Priority(Option<Priority>),
+    /// Set or clear the project
+    Project(Option<String>),
+    /// Attach a tag
+    AddTag(String),
+    /// Remove a tag
+    RemoveTag(String),
+    /// Set an extra attribute to a string (see [`Task::extra`])
+    SetExtra(String, String),
+    /// Remove an extra attribute
+    RemoveExtra(String),
+}
+
+impl Change {
+    /// Apply the change, returning whether the task changed
+    pub fn apply(&self, task: &mut Task) -> bool {
+        match self {
+            Self::Description(description) => {
+                let changed = task.description() != description;
+                if changed {
+                    task.set_description(description.clone());
+                }
+                changed
+            }
+            Self::Priority(priority) => {
+                let changed = task.priority() != *priority;
+                if changed {
+                    task.set_priority(*priority);
+                }
+                changed
+            }
+            Self::Project(project) => {
+                let changed = task.project() != project.as_deref();
+                if changed {
+                    task.set_project(project.clone());
+                }
+                changed
+            }
+            Self::AddTag(tag) => task.add_tag(tag.clone()),
+            Self::RemoveTag(tag) => task.remove_tag(tag),
+            Self::SetExtra(name, value) => {
+                let value = Value::String(value.clone());
+                let changed = task.extra().get(name) != Some(&value);
+                if changed {
+                    task.set_extra(name.clone(), value);
+                }
+                changed
+            }
+            Self::RemoveExtra(name) => task.remove_extra(name).is_some(),
+        }
+    }
+}
+
+/// A set of [`Changes`](Change) to apply together
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Modification {
+    changes: Vec<Change>,
+}
+
+impl Modification {
+    /// Parse a modification from command-line words
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a tag or `description:` is empty, a priority is
+    /// not 1-4, or an attribute is [reserved](RESERVED).
+    pub fn parse<I, S>(words: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut changes = Vec::new();
+        let mut description = Vec::new();
+
+        for word in words {
+            let word = word.as_ref();
+            if let Some(change) = parse_word(word)? {
+                changes.push(change);
+            } else {
+                description.push(word.to_string());
+            }
+        }
+        if !description.is_empty() {
+            changes.insert(0, Change::Description(description.join(" ")));
+        }
+
+        Ok(Self { changes })
+    }
+
+    /// The individual changes, in the order they are applied
+    #[must_use]
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Whether there is nothing to change
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Apply every change, returning whether the task changed
+    pub fn apply(&self, task: &mut Task) -> bool {
+        let mut changed = false;
+        for change in &self.changes {
+            changed |= change.apply(task);
+        }
+        changed
+    }
+}
+
+impl FromStr for Modification {
+    type Err = Error;
+
+    /// Parse a modification from whitespace-separated words
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s.split_whitespace())
+    }
+}
+
+fn parse_word(word: &str) -> Result<Option<Change>, Error> {
+    if let Some(tag) = word.strip_prefix('+') {
+        return tag_name(tag).map(|tag| Some(Change::AddTag(tag)));
+    }
+    if let Some(tag) = word.strip_prefix('-') {
+        return tag_name(tag).map(|tag| Some(Change::RemoveTag(tag)));
+    }
+
+    let Some((name, value)) = word.split_once(':') else {
+        return Ok(None);
+    };
+    // URLs like `https://example.com` belong in the description
+    if !is_attribute_name(name) || value.starts_with("//") {
+        return Ok(None);
+    }
+
+    let change = match (name, value) {
+        ("description", "") => return Err(Error::EmptyDescription),
+        ("description", description) => Change::Description(description.to_string()),
+        ("priority", "") => Change::Priority(None),
+        ("priority", priority) => Change::Priority(Some(
+            priority
+                .parse()
+                .map_err(|_| Error::InvalidPriority(priority.to_string()))?,
+        )),
+        ("project", "") => Change::Project(None),
+        ("project", project) => Change::Project(Some(project.to_string())),
+        (name, _) if RESERVED.contains(&name) => return Err(Error::Reserved(name.to_string())),
+        (name, "") => Change::RemoveExtra(name.to_string()),
+        (name, value) => Change::SetExtra(name.to_string(), value.to_string()),
+    };
+    Ok(Some(change))
+}
+
+fn tag_name(tag: &str) -> Result<String, Error> {
+    if tag.is_empty() || tag.starts_with(['+', '-']) || tag.contains(char::is_whitespace) {
+        return Err(Error::InvalidTag(tag.to_string()));
+    }
+    Ok(tag.to_string())
+}
+
+fn is_attribute_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Error type for parsing a [`Modification`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Error {
+    /// A `+tag` or `-tag` word has no tag, or contains whitespace or another
+    /// sign
+    #[error("invalid tag {0:?}")]
+    InvalidTag(String),
+    /// A `description:` word has no description
+    #[error("the description can't be empty")]
+    EmptyDescription,
+    /// A `priority:` value is not 1-4
+    #[error("invalid priority {0:?}. Expected 1-4")]
+    InvalidPriority(String),
+    /// A `name:value` word names an attribute in [`RESERVED`]
+    #[error("{0:?} can't be modified, as rw keeps it itself")]
+    Reserved(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use test_case::test_case;
+
+    use super::{Change, Error, Modification};
+    use crate::task::{Priority, Task};
+
+    #[test_case("fix the sink" => vec![Change::Description("fix the sink".to_string())] ; "words")]
+    #[test_case("+home -work" => vec![Change::AddTag("home".to_string()), Change::RemoveTag("work".to_string())] ; "tags")]
+    #[test_case("priority:2 project:" => vec![Change::Priority(Some(Priority::Two)), Change::Project(None)] ; "attributes")]
+    #[test_case("estimate:3h due:" => vec![Change::SetExtra("estimate".to_string(), "3h".to_string()), Change::RemoveExtra("due".to_string())] ; "extras")]
+    #[test_case("call priority:1 mum" => vec![Change::Description("call mum".to_string()), Change::Priority(Some(Priority::One))] ; "mixed")]
+    #[test_case("at 10:30 http://example.com" => vec![Change::Description("at 10:30 http://example.com".to_string())] ; "colons in words")]
+    fn parse(input: &str) -> Vec<Change> {
+        input.parse::<Modification>().unwrap().changes().to_vec()
+    }
+
+    #[test_case("priority:7" => Error::InvalidPriority("7".to_string()) ; "priority")]
+    #[test_case("+" => Error::InvalidTag(String::new()) ; "empty tag")]
+    #[test_case("--yes" => Error::InvalidTag("-yes".to_string()) ; "option")]
+    #[test_case("description:" => Error::EmptyDescription ; "empty description")]
+    #[test_case("uuid:5b1b0fb2-6a1f-4a83-9f4b-46f2e3b2e0a1" => Error::Reserved("uuid".to_string()) ; "uuid")]
+    #[test_case("status:completed" => Error::Reserved("status".to_string()) ; "status")]
+    #[test_case("end:" => Error::Reserved("end".to_string()) ; "removing a date")]
+    fn parse_error(input: &str) -> Error {
+        input.parse::<Modification>().unwrap_err()
+    }
+
+    #[test]
+    fn apply() {
+        let mut task = Task::new("old".to_string())
+            .with_tag("work".to_string())
+            .with_priority(Priority::Four);
+        let modification: Modification = "new words priority:1 -work +home estimate:2h"
+            .parse()
+            .unwrap();
+
+        assert!(modification.apply(&mut task));
+        assert_eq!(task.description(), "new words");
+        assert_eq!(task.priority(), Some(Priority::One));
+        assert!(task.has_tag("home"));
+        assert!(!task.has_tag("work"));
+        assert_eq!(task.extra().get("estimate"), Some(&json!("2h")));
+
+        let unchanged = task.clone();
+        assert!(!modification.apply(&mut task));
+        assert_eq!(task, unchanged);
+    }
+}
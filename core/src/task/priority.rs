@@ -65,7 +65,7 @@ impl FromStr for Priority {
 
 impl Display for Priority {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}", u8::from(*self))
+        write!(f, "{}", u8::from(*self))
     }
 }
 
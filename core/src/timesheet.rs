@@ -0,0 +1,381 @@
+//! Totals of the time worked on tasks, for invoicing
+//!
+//! A [`Timesheet`] adds up the [`Intervals`](crate::task::Interval) recorded
+//! on each task within a period, including work in progress. Time is grouped
+//! by the day it was worked on, by project or by tag. A task with several tags
+//! counts towards each of them.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    ops::Range,
+    str::FromStr,
+};
+
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use uuid::Uuid;
+
+use crate::Task;
+
+/// How the time in a [`Timesheet`] is grouped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    /// The day the work was done, in the time zone given to
+    /// [`Timesheet::build`]. Work past midnight is split between the days.
+    Day,
+    /// [`Task::project`]
+    Project,
+    /// [`Task::tags`]
+    Tag,
+}
+
+impl GroupBy {
+    /// The name of the grouping, as parsed and used as a CSV header
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Project => "project",
+            Self::Tag => "tag",
+        }
+    }
+}
+
+impl fmt::Display for GroupBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "day" => Ok(Self::Day),
+            "project" => Ok(Self::Project),
+            "tag" => Ok(Self::Tag),
+            _ => Err(format!(
+                "unknown grouping '{s}'. Expected day, project or tag"
+            )),
+        }
+    }
+}
+
+/// The time worked on one group of a [`Timesheet`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    group: Option<String>,
+    duration: Duration,
+    tasks: usize,
+}
+
+impl Row {
+    /// The day as `YYYY-MM-DD`, the project or the tag. `None` holds the
+    /// tasks with no project or no tags.
+    #[must_use]
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    /// The time worked
+    #[must_use]
+    pub const fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The number of tasks worked on
+    #[must_use]
+    pub const fn tasks(&self) -> usize {
+        self.tasks
+    }
+}
+
+/// The time worked on tasks within a period, in groups
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timesheet {
+    group_by: GroupBy,
+    rows: Vec<Row>,
+}
+
+impl Timesheet {
+    /// Add up the time worked on `tasks` within `period`
+    ///
+    /// Work in progress counts until the end of the period, so the period
+    /// shouldn't end later than now. Days are taken in the time zone `tz`.
+    pub fn build<'a, Tz: TimeZone>(
+        tasks: impl IntoIterator<Item = &'a Task>,
+        period: Range<DateTime<Utc>>,
+        group_by: GroupBy,
+        tz: &Tz,
+    ) -> Self {
+        let mut groups: BTreeMap<Option<String>, (Duration, BTreeSet<Uuid>)> = BTreeMap::new();
+        let mut add = |group: Option<String>, duration: Duration, uuid: Uuid| {
+            let (total, tasks) = groups.entry(group).or_default();
+            *total += duration;
+            tasks.insert(uuid);
+        };
+
+        for task in tasks {
+            let running = task.start().map(|start| (start, period.end));
+            let worked = task
+                .intervals()
+                .iter()
+                .map(|interval| (interval.start(), interval.end()))
+                .chain(running)
+                .map(|(start, end)| (start.max(period.start), end.min(period.end)))
+                .filter(|(start, end)| start < end);
+
+            for (start, end) in worked {
+                match group_by {
+                    GroupBy::Day => {
+                        for (day, duration) in split_days(start, end, tz) {
+                            add(Some(day), duration, task.uuid());
+                        }
+                    }
+                    GroupBy::Project => {
+                        add(
+                            task.project().map(ToString::to_string),
+                            end - start,
+                            task.uuid(),
+                        );
+                    }
+                    GroupBy::Tag if task.tags().is_empty() => add(None, end - start, task.uuid()),
+                    GroupBy::Tag => {
+                        for tag in task.tags() {
+                            add(Some(tag.clone()), end - start, task.uuid());
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut rows: Vec<Row> = groups
+            .into_iter()
+            .map(|(group, (duration, tasks))| Row {
+                group,
+                duration,
+                tasks: tasks.len(),
+            })
+            .collect();
+        // Tasks with no project or tag come last
+        rows.sort_by_key(|row| row.group.is_none());
+
+        Self { group_by, rows }
+    }
+
+    /// How the time is grouped
+    #[must_use]
+    pub const fn group_by(&self) -> GroupBy {
+        self.group_by
+    }
+
+    /// The groups in order: days oldest first, projects and tags by name
+    #[must_use]
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    /// The time worked on every group
+    ///
+    /// With [`GroupBy::Tag`], time on a task with several tags is counted
+    /// once for each of them.
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        self.rows
+            .iter()
+            .map(Row::duration)
+            .fold(Duration::zero(), |total, duration| total + duration)
+    }
+
+    /// Round the time of each group up to a multiple of `granularity`
+    ///
+    /// Has no effect if `granularity` is zero or negative.
+    pub fn round_up(&mut self, granularity: Duration) {
+        let step = granularity.num_seconds();
+        if step <= 0 {
+            return;
+        }
+        for row in &mut self.rows {
+            let seconds = row.duration.num_seconds();
+            row.duration = Duration::seconds((seconds + step - 1) / step * step);
+        }
+    }
+
+    /// Render the timesheet as CSV, with the time in decimal hours
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the CSV can't be written.
+    pub fn render_csv(&self) -> Result<String, Error> {
+        let mut writer = ::csv::Writer::from_writer(Vec::new());
+        writer.write_record([self.group_by.name(), "hours", "tasks"])?;
+        for row in &self.rows {
+            writer.write_record([
+                row.group.clone().unwrap_or_default(),
+                hours(row.duration),
+                row.tasks.to_string(),
+            ])?;
+        }
+
+        let bytes = writer.into_inner().map_err(|e| Error::Io(e.into_error()))?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+/// Format a duration as hours to two decimal places, like `1.25`
+fn hours(duration: Duration) -> String {
+    let hundredths = (duration.num_seconds() * 100 + 1800) / 3600;
+    format!("{}.{:02}", hundredths / 100, hundredths % 100)
+}
+
+/// Split a period at each midnight in `tz`
+fn split_days<Tz: TimeZone>(
+    mut start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    tz: &Tz,
+) -> Vec<(String, Duration)> {
+    let mut days = Vec::new();
+    while start < end {
+        let date = start.with_timezone(tz).date_naive();
+        let midnight = date
+            .succ_opt()
+            .and_then(|next| {
+                tz.from_local_datetime(&next.and_time(NaiveTime::MIN))
+                    .earliest()
+            })
+            .map(|midnight| midnight.with_timezone(&Utc))
+            .filter(|midnight| *midnight > start)
+            .map_or(end, |midnight| midnight.min(end));
+        days.push((date.format("%Y-%m-%d").to_string(), midnight - start));
+        start = midnight;
+    }
+    days
+}
+
+/// Error type for rendering a [`Timesheet`]
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The CSV couldn't be written
+    #[error("failed to write CSV: {0}")]
+    Csv(#[from] ::csv::Error),
+    /// IO error
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, FixedOffset, Utc};
+    use test_case::test_case;
+
+    use super::{GroupBy, Timesheet};
+    use crate::task::{Interval, Task};
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        timestamp.parse().unwrap()
+    }
+
+    fn tasks() -> Vec<Task> {
+        vec![
+            Task::new("invoice".to_string())
+                .with_project("acme".to_string())
+                .with_tag("admin".to_string())
+                .with_tag("billable".to_string())
+                .with_interval(Interval::new(
+                    at("2024-01-01T09:00:00Z"),
+                    at("2024-01-01T10:10:00Z"),
+                )),
+            Task::new("deploy".to_string())
+                .with_project("acme".to_string())
+                .with_interval(Interval::new(
+                    at("2024-01-01T23:30:00Z"),
+                    at("2024-01-02T00:20:00Z"),
+                )),
+            Task::new("read".to_string()).with_interval(Interval::new(
+                at("2023-12-01T09:00:00Z"),
+                at("2023-12-01T10:00:00Z"),
+            )),
+        ]
+    }
+
+    fn rows(timesheet: &Timesheet) -> Vec<(Option<String>, i64, usize)> {
+        timesheet
+            .rows()
+            .iter()
+            .map(|row| {
+                (
+                    row.group().map(ToString::to_string),
+                    row.duration().num_minutes(),
+                    row.tasks(),
+                )
+            })
+            .collect()
+    }
+
+    #[test_case(GroupBy::Day => vec![(Some("2024-01-01".into()), 100, 2), (Some("2024-01-02".into()), 20, 1)] ; "day")]
+    #[test_case(GroupBy::Project => vec![(Some("acme".into()), 120, 2)] ; "project")]
+    #[test_case(GroupBy::Tag => vec![(Some("admin".into()), 70, 1), (Some("billable".into()), 70, 1), (None, 50, 1)] ; "tag")]
+    fn group(group_by: GroupBy) -> Vec<(Option<String>, i64, usize)> {
+        let tasks = tasks();
+        let timesheet = Timesheet::build(
+            &tasks,
+            at("2024-01-01T00:00:00Z")..at("2024-02-01T00:00:00Z"),
+            group_by,
+            &Utc,
+        );
+        rows(&timesheet)
+    }
+
+    #[test]
+    fn days_in_time_zone() {
+        let tasks = tasks();
+        let tz = FixedOffset::east_opt(3600).unwrap();
+        let timesheet = Timesheet::build(
+            &tasks,
+            at("2024-01-01T00:00:00Z")..at("2024-02-01T00:00:00Z"),
+            GroupBy::Day,
+            &tz,
+        );
+        assert_eq!(
+            rows(&timesheet),
+            vec![
+                (Some("2024-01-01".into()), 70, 1),
+                (Some("2024-01-02".into()), 50, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn clipped_to_period_with_work_in_progress() {
+        let mut task = Task::new("report".to_string());
+        task.mark_started();
+        let start = task.start().unwrap();
+
+        let timesheet = Timesheet::build(
+            [&task],
+            start - Duration::hours(1)..start + Duration::minutes(30),
+            GroupBy::Project,
+            &Utc,
+        );
+        assert_eq!(rows(&timesheet), vec![(None, 30, 1)]);
+    }
+
+    #[test]
+    fn round_up_and_render() {
+        let tasks = tasks();
+        let mut timesheet = Timesheet::build(
+            &tasks,
+            at("2024-01-01T00:00:00Z")..at("2024-02-01T00:00:00Z"),
+            GroupBy::Day,
+            &Utc,
+        );
+        timesheet.round_up(Duration::minutes(15));
+
+        assert_eq!(timesheet.total(), Duration::minutes(105 + 30));
+        assert_eq!(
+            timesheet.render_csv().unwrap(),
+            "day,hours,tasks\n2024-01-01,1.75,2\n2024-01-02,0.50,1\n"
+        );
+    }
+}
@@ -3,8 +3,12 @@
 mod resources;
 mod tools;
 
-use std::{future::Future, path::PathBuf};
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+};
 
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use rmcp::{
     ErrorData as McpError, ServerHandler,
     handler::server::{router::tool::ToolRouter, wrapper::Parameters},
@@ -15,7 +19,12 @@ use rmcp::{
     service::{RequestContext, RoleServer},
     tool, tool_handler, tool_router,
 };
-use rustwarrior_core::{OpenTask, Priority, Store};
+use rustwarrior_core::{
+    Config, Filter, OpenTask, Priority, Store,
+    context::{self, Context},
+    git,
+    store::backup,
+};
 
 use self::{
     resources::{
@@ -96,6 +105,28 @@ impl TaskHandler {
         tools::delete_task::handle(params)
     }
 
+    #[tool(
+        description = "Start working on a task, tracking the time until it is stopped",
+        annotations(read_only_hint = false, idempotent_hint = false)
+    )]
+    async fn start_task(
+        &self,
+        params: Parameters<tools::start_task::StartTaskParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tools::start_task::handle(params)
+    }
+
+    #[tool(
+        description = "Stop working on a task, or on every task in progress",
+        annotations(read_only_hint = false, idempotent_hint = false)
+    )]
+    async fn stop_task(
+        &self,
+        params: Parameters<tools::stop_task::StopTaskParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tools::stop_task::handle(params)
+    }
+
     #[tool(
         description = "Set the priority of a task",
         annotations(read_only_hint = false, idempotent_hint = false)
@@ -107,6 +138,18 @@ impl TaskHandler {
         tools::set_priority::handle(params)
     }
 
+    #[tool(
+        description = "Run a named report, like next, overdue, completed or all, optionally \
+                       narrowed by a filter",
+        annotations(read_only_hint = true, idempotent_hint = true)
+    )]
+    async fn run_report(
+        &self,
+        params: Parameters<tools::run_report::RunReportParams>,
+    ) -> Result<CallToolResult, McpError> {
+        tools::run_report::handle(params)
+    }
+
     #[tool(
         description = "Search tasks by description and/or priority",
         annotations(read_only_hint = true, idempotent_hint = true)
@@ -178,6 +221,17 @@ fn load_store_with_paths() -> Result<(Store, PathBuf, PathBuf), McpError> {
     Ok((store, data_dir, tasks_file))
 }
 
+/// Snapshot the tasks file before it is overwritten
+fn back_up(data_dir: &Path, tasks_file: &Path) -> Result<(), McpError> {
+    let backups_dir =
+        rustwarrior_core::store::paths::get_backups_dir(Some(data_dir)).map_err(|e| {
+            McpError::internal_error(format!("Failed to resolve backups directory: {e}"), None)
+        })?;
+    backup::rotate(tasks_file, &backups_dir, &load_config()?.retention())
+        .map_err(|e| McpError::internal_error(format!("Failed to back up tasks: {e}"), None))?;
+    Ok(())
+}
+
 pub fn with_store<F, T>(f: F) -> Result<T, McpError>
 where
     F: FnOnce(&Store) -> Result<T, McpError>,
@@ -196,9 +250,14 @@ where
     std::fs::create_dir_all(&data_dir).map_err(|e| {
         McpError::internal_error(format!("Failed to prepare data directory: {e}"), None)
     })?;
+    back_up(&data_dir, &tasks_file)?;
     store
         .save_to_path(&tasks_file)
         .map_err(|e| McpError::internal_error(format!("Failed to save tasks: {e}"), None))?;
+    // The change is saved, so a failed commit doesn't fail the tool call
+    if let Err(e) = git::auto_commit(&data_dir, "rustwarrior-mcp-server") {
+        tracing::warn!("Failed to commit changes: {e}");
+    }
 
     Ok(output)
 }
@@ -210,11 +269,67 @@ pub fn parse_priority(input: Option<u8>) -> Result<Option<Priority>, McpError> {
         .map_err(|e| McpError::invalid_params(e.to_string(), None))
 }
 
-pub fn normalize_filter(input: Option<&String>) -> Option<String> {
+pub fn parse_timestamp(input: Option<&String>) -> Result<Option<DateTime<Utc>>, McpError> {
     input
-        .map(|value| value.trim())
-        .filter(|value| !value.is_empty())
-        .map(str::to_lowercase)
+        .map(|value| {
+            let value = value.trim();
+            DateTime::parse_from_rfc3339(value)
+                .map(|timestamp| timestamp.to_utc())
+                .or_else(|_| {
+                    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                        .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+                })
+                .map_err(|_| {
+                    McpError::invalid_params(
+                        format!(
+                            "invalid timestamp '{value}'. Expected RFC 3339 (e.g. \
+                             2024-01-01T12:00:00Z) or a date (YYYY-MM-DD)"
+                        ),
+                        None,
+                    )
+                })
+        })
+        .transpose()
+}
+
+pub fn parse_filter(input: Option<&String>) -> Result<Filter, McpError> {
+    input
+        .map_or_else(|| Ok(Filter::default()), |value| Filter::parse(value))
+        .map_err(|e| McpError::invalid_params(format!("Invalid filter: {e}"), None))
+}
+
+pub fn load_config() -> Result<Config, McpError> {
+    Config::load_default().map_err(|e| McpError::internal_error(e.to_string(), None))
+}
+
+/// The context a request names, no context for `none`, or the active context
+/// if it names none
+pub fn resolve_context(
+    config: &Config,
+    name: Option<&String>,
+) -> Result<Option<Context>, McpError> {
+    match name.map(String::as_str) {
+        None => config
+            .context()
+            .map_err(|e| McpError::internal_error(e.to_string(), None)),
+        Some(context::NONE) => Ok(None),
+        Some(name) => config
+            .find_context(name)
+            .map(Some)
+            .map_err(|e| McpError::invalid_params(e.to_string(), None)),
+    }
+}
+
+/// A request's filter, combined with the filter of its context
+pub fn context_filter(
+    filter: Option<&String>,
+    context: Option<&String>,
+) -> Result<Filter, McpError> {
+    let filter = parse_filter(filter)?;
+    Ok(match resolve_context(&load_config()?, context)? {
+        Some(context) => filter.and(context.filter().clone()),
+        None => filter,
+    })
 }
 
 pub fn to_task_info(task: &OpenTask) -> TaskInfo {
@@ -223,6 +338,11 @@ pub fn to_task_info(task: &OpenTask) -> TaskInfo {
         description: task.description().clone(),
         priority: task.priority().map(u8::from),
         created: task.created().to_string(),
+        modified: task.modified().to_string(),
+        start: task.start().map(|s| s.to_string()),
+        end: task.end().map(|e| e.to_string()),
+        tracked_seconds: (task.is_started() || !task.intervals().is_empty())
+            .then(|| task.tracked(Utc::now()).num_seconds()),
         completed: task.completed().map(|c| c.to_string()),
         is_completed: task.is_completed(),
     }
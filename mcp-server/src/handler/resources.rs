@@ -3,36 +3,52 @@ use rmcp::model::{Annotated, Annotations, RawResource, Resource, ResourceContent
 
 pub(super) const DOC_RESOURCE_URI: &str = "rustwarrior://tasks/guide";
 pub(super) const DOC_RESOURCE_NAME: &str = "RustWarrior MCP quick guide";
-pub(super) const DOC_RESOURCE_TEXT: &str = r"RustWarrior task MCP server
+pub(super) const DOC_RESOURCE_TEXT: &str = r#"RustWarrior task MCP server
 
 What: lightweight task list for quick notes or per-session context.
 When to use: when asked to manage todos or remember personal/project tasks. Good for quick reminders, meeting prep, or small backlogs; not a full project tracker.
-Storage: tasks persist in open_tasks.jsonl under RUSTWARRIOR_DATA_DIR or your platform data dir (single-user/local).
+Storage: tasks persist in open_tasks.jsonl under RUSTWARRIOR_DATA_DIR, the data_dir in the user's config file, or the platform data dir (single-user/local).
 
 Tools
-- create_task(description, priority 1-4)
-- list_tasks(filter: active | completed | free-text, priority 1-4)
-- search_tasks(query, priority 1-4)
+- create_task(description, priority 1-4, defaulting to the user's default_priority setting, context)
+- list_tasks(filter: expression, or active/completed for status:open/status:completed, priority 1-4, modified_since: RFC 3339 or YYYY-MM-DD, context)
+- search_tasks(query, priority 1-4, filter: expression, context)
+- run_report(name, filter: expression to narrow it, context)
 - get_task(id)
 - set_priority(id, priority 1-4 or null to clear)
 - complete_task(id)
 - delete_task(id)
+- start_task(id, exclusive: stop other tasks in progress)
+- stop_task(id, or leave it out to stop every task in progress)
 
 Semantics
 - Priorities: 1 is highest, 4 is lowest. Invalid values return errors.
-- Filters: 'active' hides completed tasks, 'completed' hides active tasks, anything else filters descriptions case-insensitively.
+- Filters are expressions, shared with `rw list`:
+  - +tag / -tag: has or lacks a tag
+  - name:value compares an attribute (description, status, priority, project, created, modified, start, end, completed, deleted, or an extra attribute). An empty value means unset.
+  - name.modifier:value uses is, not, above, below, after, before, has, startswith, endswith, regex, any or none. priority.above:3 means more important than 3 (1 and 2).
+  - status is pending, started, completed, deleted, or open (pending or started).
+  - Dates are YYYY-MM-DD or RFC 3339, e.g. created.after:2024-01-01, or today or now. Extra attributes holding dates, like due, compare with after and before.
+  - /pattern/ matches the description with a regular expression; any other word matches descriptions containing it.
+  - Combine with and, or, not and parentheses, e.g. (+work or project:home) and not status:completed. Quote values with spaces: project:"big move".
 - IDs are zero-based and stable per task.
+- delete_task hides a task from list_tasks and search_tasks, unless the filter includes status:deleted. The other tools treat a deleted task as not found. It is removed for good when the user runs `rw purge`.
 - Time stamps are UTC strings and may differ from local time.
+- Tasks report created, modified, start (work in progress) and end (closed) times. Use modified_since to fetch only recent changes.
+- Reports are saved listings with a filter, sort order and row limit. The built-in reports are next (the ten most important open tasks), overdue (open tasks with a due date before today), completed (most recent first) and all. The user can override them and define others in the config file.
+- Contexts are named filters from the user's config file, like work = "+work". The active context's filter applies to list_tasks, search_tasks and run_report, and create_task gives new tasks its tags, project and priority. Pass context with another name to use that context instead, or "none" to ignore contexts.
+- start_task and stop_task record work intervals. tracked_seconds is the total time worked on a task, including work in progress.
 
 Typical flow
 - create_task -> list_tasks -> set_priority -> complete_task -> delete_task
-";
+"#;
 
 pub(super) const SERVER_INSTRUCTIONS: &str = r"RustWarrior task MCP server for managing todos or personal/project tasks. Use it when the user asks to remember or track tasks.
-Tasks are stored locally (RUSTWARRIOR_DATA_DIR or platform data dir).
-Tools: create_task(description, priority 1-4), list_tasks(filter=active|completed|text, priority 1-4), search_tasks(query, priority 1-4), get_task(id),
-set_priority(id, priority 1-4 or null), complete_task(id), delete_task(id).
-Docs: rustwarrior://tasks/guide (what/when/how, filters, priority rules).";
+Tasks are stored locally (RUSTWARRIOR_DATA_DIR, the config file's data_dir, or platform data dir).
+Tools: create_task(description, priority 1-4, context), list_tasks(filter expression, priority 1-4, modified_since, context), search_tasks(query, priority 1-4, filter, context), run_report(name, filter, context), get_task(id),
+set_priority(id, priority 1-4 or null), complete_task(id), delete_task(id), start_task(id, exclusive), stop_task(id?).
+The user's active context filters listings unless a request passes context none.
+Docs: rustwarrior://tasks/guide (what/when/how, filters, contexts, priority rules).";
 
 pub(super) fn documentation_resource() -> Resource {
     let mut raw = RawResource::new(DOC_RESOURCE_URI, DOC_RESOURCE_NAME);
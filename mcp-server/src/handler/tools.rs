@@ -3,8 +3,11 @@ pub mod create_task;
 pub mod delete_task;
 pub mod get_task;
 pub mod list_tasks;
+pub mod run_report;
 pub mod search_tasks;
 pub mod set_priority;
+pub mod start_task;
+pub mod stop_task;
 pub mod types;
 
 pub use types::TaskInfo;
@@ -17,9 +17,12 @@ pub struct CompleteTaskParams {
 pub fn handle(params: Parameters<CompleteTaskParams>) -> Result<CallToolResult, McpError> {
     let input = params.0;
     let result = with_store_mut(|store| {
-        let task = store.get_mut(input.id).ok_or_else(|| {
-            McpError::invalid_params(format!("Task {} not found", input.id), None)
-        })?;
+        let task = store
+            .get_mut(input.id)
+            .filter(|task| !task.is_deleted())
+            .ok_or_else(|| {
+                McpError::invalid_params(format!("Task {} not found", input.id), None)
+            })?;
         task.task_mut().mark_completed();
         Ok(json!({
             "id": input.id,
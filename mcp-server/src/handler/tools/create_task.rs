@@ -7,22 +7,36 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-use crate::handler::{parse_priority, with_store_mut};
+use crate::handler::{load_config, parse_priority, resolve_context, with_store_mut};
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct CreateTaskParams {
     pub description: String,
+    /// 1 (highest) to 4 (lowest). Defaults to the context's priority, then
+    /// the configured default priority
     #[serde(skip_serializing_if = "Option::is_none")]
     pub priority: Option<u8>,
+    /// The context whose tags, project and priority the task gets, or
+    /// `none`. Defaults to the active context
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
 }
 
 pub fn handle(params: Parameters<CreateTaskParams>) -> Result<CallToolResult, McpError> {
     let input = params.0;
     let priority = parse_priority(input.priority)?;
+    let config = load_config()?;
+    let context = resolve_context(&config, input.context.as_ref())?;
 
     let result = with_store_mut(|store| {
         let mut task = rustwarrior_core::Task::new(input.description);
-        if let Some(p) = priority {
+        if let Some(context) = &context {
+            context.apply_to(&mut task);
+        }
+        if let Some(p) = priority
+            .or_else(|| task.priority())
+            .or(config.default_priority)
+        {
             task.set_priority(Some(p));
         }
         let id = store.push(task);
@@ -17,17 +17,17 @@ pub struct DeleteTaskParams {
 pub fn handle(params: Parameters<DeleteTaskParams>) -> Result<CallToolResult, McpError> {
     let input = params.0;
     let result = with_store_mut(|store| {
-        if store.delete(input.id).is_some() {
-            Ok(json!({
-                "id": input.id,
-                "message": "Task deleted successfully"
-            }))
-        } else {
-            Err(McpError::invalid_params(
-                format!("Task {} not found", input.id),
-                None,
-            ))
-        }
+        let task = store
+            .get_mut(input.id)
+            .filter(|task| !task.is_deleted())
+            .ok_or_else(|| {
+                McpError::invalid_params(format!("Task {} not found", input.id), None)
+            })?;
+        task.task_mut().mark_deleted();
+        Ok(json!({
+            "id": input.id,
+            "message": "Task deleted successfully"
+        }))
     })?;
 
     Ok(CallToolResult {
@@ -19,6 +19,7 @@ pub fn handle(params: Parameters<GetTaskParams>) -> Result<CallToolResult, McpEr
     let info = with_store(|store| {
         store
             .get(input.id)
+            .filter(|task| !task.is_deleted())
             .map(to_task_info)
             .ok_or_else(|| McpError::invalid_params(format!("Task {} not found", input.id), None))
     })?;
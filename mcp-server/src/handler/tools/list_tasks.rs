@@ -1,64 +1,45 @@
+use chrono::{DateTime, Utc};
 use rmcp::{
     ErrorData as McpError,
     handler::server::wrapper::Parameters,
     model::{CallToolResult, Content},
 };
+use rustwarrior_core::{Filter, Priority, Store};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use super::TaskInfo;
-use crate::handler::{normalize_filter, parse_priority, to_task_info, with_store};
+use crate::handler::{context_filter, parse_priority, parse_timestamp, to_task_info, with_store};
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct ListTasksParams {
+    /// A filter expression, like `+work and priority.above:3` or
+    /// `status:open`. See the guide resource for the syntax. `active` and
+    /// `completed` on their own are kept as short forms of `status:open` and
+    /// `status:completed`
     #[serde(skip_serializing_if = "Option::is_none")]
     pub filter: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub priority: Option<u8>,
+    /// Only return tasks modified at or after this time (RFC 3339 timestamp
+    /// or YYYY-MM-DD date, UTC)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_since: Option<String>,
+    /// The context whose filter also applies, or `none`. Defaults to the
+    /// active context
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
 }
 
 pub fn handle(params: Parameters<ListTasksParams>) -> Result<CallToolResult, McpError> {
     let input = params.0;
     let priority_filter = parse_priority(input.priority)?;
-    let filter = normalize_filter(input.filter.as_ref());
-
-    let tasks: Vec<TaskInfo> = with_store(|store| {
-        let tasks = store
-            .iter()
-            .filter(|task| {
-                if let Some(filter_value) = filter.as_ref() {
-                    match filter_value.as_str() {
-                        "active" => {
-                            if task.is_completed() {
-                                return false;
-                            }
-                        }
-                        "completed" => {
-                            if !task.is_completed() {
-                                return false;
-                            }
-                        }
-                        text => {
-                            if !task.description().to_lowercase().contains(text) {
-                                return false;
-                            }
-                        }
-                    }
-                }
-
-                if let Some(pf) = priority_filter {
-                    if task.priority() != Some(pf) {
-                        return false;
-                    }
-                }
+    let filter = input.filter.map(|filter| expand_alias(&filter));
+    let filter = context_filter(filter.as_ref(), input.context.as_ref())?;
+    let modified_since = parse_timestamp(input.modified_since.as_ref())?;
 
-                true
-            })
-            .map(to_task_info)
-            .collect();
-        Ok(tasks)
-    })?;
+    let tasks = with_store(|store| Ok(list(store, &filter, priority_filter, modified_since)))?;
 
     let data = json!({ "tasks": tasks });
     Ok(CallToolResult {
@@ -68,3 +49,61 @@ pub fn handle(params: Parameters<ListTasksParams>) -> Result<CallToolResult, Mcp
         meta: None,
     })
 }
+
+/// The tasks in the store which pass every filter
+fn list(
+    store: &Store,
+    filter: &Filter,
+    priority: Option<Priority>,
+    modified_since: Option<DateTime<Utc>>,
+) -> Vec<TaskInfo> {
+    store
+        .matching(filter)
+        .filter(|task| priority.is_none_or(|priority| task.priority() == Some(priority)))
+        .filter(|task| modified_since.is_none_or(|since| task.modified() >= since))
+        .map(to_task_info)
+        .collect()
+}
+
+/// The status filters `list_tasks` took before it took filter expressions
+fn expand_alias(filter: &str) -> String {
+    match filter.trim() {
+        "active" => "status:open".to_string(),
+        "completed" => "status:completed".to_string(),
+        _ => filter.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+    use rustwarrior_core::{Filter, Store, Task};
+    use test_case::test_case;
+
+    use crate::handler::parse_timestamp;
+
+    fn store() -> Store {
+        let created = |timestamp: &str| DateTime::parse_from_rfc3339(timestamp).unwrap().to_utc();
+        let mut store = Store::new();
+        store.push(Task::new("May".to_string()).with_created(created("2024-05-31T23:59:59Z")));
+        store.push(Task::new("June".to_string()).with_created(created("2024-06-01T00:00:00Z")));
+        store.push(Task::new("July".to_string()).with_created(created("2024-07-01T12:00:00Z")));
+        let mut touched =
+            Task::new("Touched".to_string()).with_created(created("2024-01-01T00:00:00Z"));
+        touched.touch();
+        store.push(touched);
+        store
+    }
+
+    #[test_case(None, &["May", "June", "July", "Touched"] ; "unfiltered")]
+    #[test_case(Some("2024-06-01"), &["June", "July", "Touched"] ; "date")]
+    #[test_case(Some("2024-07-01T12:00:00Z"), &["July", "Touched"] ; "timestamp")]
+    #[test_case(Some("2024-07-01T13:00:00+02:00"), &["July", "Touched"] ; "offset")]
+    #[test_case(Some("2100-01-01"), &[] ; "future")]
+    fn modified_since(since: Option<&str>, expected: &[&str]) {
+        let since = parse_timestamp(since.map(ToString::to_string).as_ref()).unwrap();
+        let tasks = super::list(&store(), &Filter::default(), None, since);
+        let descriptions: Vec<_> = tasks.iter().map(|task| task.description.as_str()).collect();
+        assert_eq!(descriptions, expected);
+    }
+}
@@ -0,0 +1,65 @@
+use rmcp::{
+    ErrorData as McpError,
+    handler::server::wrapper::Parameters,
+    model::{CallToolResult, Content},
+};
+use rustwarrior_core::report::Report;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::TaskInfo;
+use crate::handler::{context_filter, load_config, to_task_info, with_store};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunReportParams {
+    /// The name of the report, like `next`, `overdue`, `completed`, `all`, or
+    /// one defined in the config file
+    pub name: String,
+    /// A filter expression to narrow the report, like `+work`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    /// The context whose filter also applies, or `none`. Defaults to the
+    /// active context
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+}
+
+pub fn handle(params: Parameters<RunReportParams>) -> Result<CallToolResult, McpError> {
+    let input = params.0;
+    let filter = context_filter(input.filter.as_ref(), input.context.as_ref())?;
+    let reports = load_config()?
+        .reports()
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+    let report = reports.get(&input.name).ok_or_else(|| {
+        let names: Vec<&str> = reports.iter().map(Report::name).collect();
+        McpError::invalid_params(
+            format!(
+                "Unknown report '{}'. Expected one of: {}",
+                input.name,
+                names.join(", ")
+            ),
+            None,
+        )
+    })?;
+
+    let tasks: Vec<TaskInfo> = with_store(|store| {
+        Ok(report
+            .run(store, &filter)
+            .into_iter()
+            .map(to_task_info)
+            .collect())
+    })?;
+
+    let data = json!({ "report": report.name(), "tasks": tasks });
+    Ok(CallToolResult {
+        content: vec![Content::text(format!(
+            "Report '{}' listed {} tasks",
+            report.name(),
+            tasks.len()
+        ))],
+        structured_content: Some(data),
+        is_error: Some(false),
+        meta: None,
+    })
+}
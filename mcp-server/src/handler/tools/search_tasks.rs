@@ -8,39 +8,44 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use super::TaskInfo;
-use crate::handler::{normalize_filter, parse_priority, to_task_info, with_store};
+use crate::handler::{context_filter, parse_priority, to_task_info, with_store};
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct SearchTasksParams {
+    /// Text to find in the description, ignoring case
     #[serde(skip_serializing_if = "Option::is_none")]
     pub query: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub priority: Option<u8>,
+    /// A filter expression to narrow the search, like `+work or /^Call/`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    /// The context whose filter also applies, or `none`. Defaults to the
+    /// active context
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
 }
 
 pub fn handle(params: Parameters<SearchTasksParams>) -> Result<CallToolResult, McpError> {
     let input = params.0;
     let priority_filter = parse_priority(input.priority)?;
-    let query = normalize_filter(input.query.as_ref());
+    let query = input
+        .query
+        .as_deref()
+        .map(str::trim)
+        .filter(|query| !query.is_empty())
+        .map(str::to_lowercase);
+    let filter = context_filter(input.filter.as_ref(), input.context.as_ref())?;
 
     let tasks: Vec<TaskInfo> = with_store(|store| {
         let tasks = store
-            .iter()
+            .matching(&filter)
             .filter(|task| {
-                if let Some(query_text) = query.as_ref() {
-                    if !task.description().to_lowercase().contains(query_text) {
-                        return false;
-                    }
-                }
-
-                if let Some(pf) = priority_filter {
-                    if task.priority() != Some(pf) {
-                        return false;
-                    }
-                }
-
-                true
+                query
+                    .as_ref()
+                    .is_none_or(|query| task.description().to_lowercase().contains(query))
             })
+            .filter(|task| priority_filter.is_none_or(|priority| task.priority() == Some(priority)))
             .map(to_task_info)
             .collect();
         Ok(tasks)
@@ -21,9 +21,12 @@ pub fn handle(params: Parameters<SetPriorityParams>) -> Result<CallToolResult, M
     let priority = parse_priority(input.priority)?;
 
     let result = with_store_mut(|store| {
-        let task = store.get_mut(input.id).ok_or_else(|| {
-            McpError::invalid_params(format!("Task {} not found", input.id), None)
-        })?;
+        let task = store
+            .get_mut(input.id)
+            .filter(|task| !task.is_deleted())
+            .ok_or_else(|| {
+                McpError::invalid_params(format!("Task {} not found", input.id), None)
+            })?;
         task.task_mut().set_priority(priority);
         Ok(json!({
             "id": input.id,
@@ -0,0 +1,59 @@
+use rmcp::{
+    ErrorData as McpError,
+    handler::server::wrapper::Parameters,
+    model::{CallToolResult, Content},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::handler::{load_config, to_task_info, with_store_mut};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StartTaskParams {
+    pub id: usize,
+    /// Stop any other task in progress. Defaults to the `exclusive` setting.
+    #[serde(default)]
+    pub exclusive: Option<bool>,
+}
+
+pub fn handle(params: Parameters<StartTaskParams>) -> Result<CallToolResult, McpError> {
+    let input = params.0;
+    let exclusive = input
+        .exclusive
+        .map_or_else(|| load_config().map(|config| config.exclusive()), Ok)?;
+    let result = with_store_mut(|store| {
+        let task = store
+            .get_mut(input.id)
+            .filter(|task| !task.is_deleted())
+            .ok_or_else(|| {
+                McpError::invalid_params(format!("Task {} not found", input.id), None)
+            })?;
+        if task.is_completed() {
+            return Err(McpError::invalid_params(
+                format!("Task {} is completed", input.id),
+                None,
+            ));
+        }
+        task.task_mut().mark_started();
+        let stopped = if exclusive {
+            store.stop_others(input.id)
+        } else {
+            Vec::new()
+        };
+        let info = store.get(input.id).map(to_task_info);
+        Ok(json!({
+            "id": input.id,
+            "task": info,
+            "stopped": stopped,
+            "message": "Task started"
+        }))
+    })?;
+
+    Ok(CallToolResult {
+        content: vec![Content::text(format!("Started task {}", input.id))],
+        structured_content: Some(result),
+        is_error: Some(false),
+        meta: None,
+    })
+}
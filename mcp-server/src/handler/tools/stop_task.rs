@@ -0,0 +1,68 @@
+use rmcp::{
+    ErrorData as McpError,
+    handler::server::wrapper::Parameters,
+    model::{CallToolResult, Content},
+};
+use rustwarrior_core::OpenTask;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::handler::{to_task_info, with_store_mut};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct StopTaskParams {
+    /// The task to stop. Every task in progress is stopped if this is left
+    /// out.
+    #[serde(default)]
+    pub id: Option<usize>,
+}
+
+pub fn handle(params: Parameters<StopTaskParams>) -> Result<CallToolResult, McpError> {
+    let input = params.0;
+    let (ids, result) = with_store_mut(|store| {
+        let ids: Vec<usize> = if let Some(id) = input.id {
+            let task = store
+                .get(id)
+                .filter(|task| !task.is_deleted())
+                .ok_or_else(|| McpError::invalid_params(format!("Task {id} not found"), None))?;
+            if !task.is_started() {
+                return Err(McpError::invalid_params(
+                    format!("Task {id} isn't started"),
+                    None,
+                ));
+            }
+            vec![id]
+        } else {
+            store
+                .iter()
+                .filter(|task| task.is_started())
+                .map(OpenTask::id)
+                .collect()
+        };
+        for id in &ids {
+            if let Some(task) = store.get_mut(*id) {
+                task.task_mut().mark_stopped();
+            }
+        }
+        let stopped: Vec<_> = ids
+            .iter()
+            .filter_map(|id| store.get(*id))
+            .map(to_task_info)
+            .collect();
+        Ok((ids, json!({ "stopped": stopped })))
+    })?;
+
+    let message = if ids.is_empty() {
+        "No task is started".to_string()
+    } else {
+        let ids: Vec<String> = ids.iter().map(ToString::to_string).collect();
+        format!("Stopped task {}", ids.join(", "))
+    };
+    Ok(CallToolResult {
+        content: vec![Content::text(message)],
+        structured_content: Some(result),
+        is_error: Some(false),
+        meta: None,
+    })
+}
@@ -8,6 +8,13 @@ pub struct TaskInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub priority: Option<u8>,
     pub created: String,
+    pub modified: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracked_seconds: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub completed: Option<String>,
     pub is_completed: bool,
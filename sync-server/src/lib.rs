@@ -0,0 +1,182 @@
+//! A minimal HTTP server holding the shared task list for `rw sync`
+//!
+//! The server stores a single versioned [`Snapshot`] and exposes it at
+//! `/tasks`:
+//!
+//! - `GET /tasks` returns the current snapshot.
+//! - `PUT /tasks` replaces it. The body is a snapshot whose `version` must
+//!   match the server's current version, otherwise the push is rejected with
+//!   `409 Conflict` and the client must pull and merge again. On success the
+//!   server responds with the new snapshot.
+//!
+//! All merging happens on the clients; see [`rustwarrior_core::Store::merge`].
+
+#![deny(
+    clippy::all,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs
+)]
+#![warn(clippy::pedantic, clippy::nursery)]
+#![allow(clippy::cargo_common_metadata)]
+
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    net::{SocketAddr, ToSocketAddrs},
+    path::{Path, PathBuf},
+};
+
+use rustwarrior_core::sync::Snapshot;
+use tiny_http::{Header, Method, Request, Response, StatusCode};
+
+/// The sync server
+pub struct Server {
+    http: tiny_http::Server,
+    state_file: PathBuf,
+    snapshot: Snapshot,
+}
+
+impl fmt::Debug for Server {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Server")
+            .field("addr", &self.http.server_addr())
+            .field("state_file", &self.state_file)
+            .field("version", &self.snapshot.version)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Server {
+    /// Bind the server to an address, serving the tasks stored in
+    /// `state_file`.
+    ///
+    /// The state file is created on the first push if it doesn't exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state file cannot be read, or the address
+    /// cannot be bound.
+    pub fn bind(addr: impl ToSocketAddrs, state_file: impl Into<PathBuf>) -> Result<Self, Error> {
+        let state_file = state_file.into();
+        let snapshot = load_snapshot(&state_file)?;
+        let http = tiny_http::Server::http(addr).map_err(Error::Bind)?;
+        Ok(Self {
+            http,
+            state_file,
+            snapshot,
+        })
+    }
+
+    /// The address the server is listening on
+    #[must_use]
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.http.server_addr().to_ip()
+    }
+
+    /// Serve requests until the process is stopped
+    ///
+    /// A request that fails, because the client hung up or a push can't be
+    /// saved, is logged and doesn't stop the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no more requests can be received.
+    pub fn run(mut self) -> Result<(), Error> {
+        loop {
+            let request = self.http.recv()?;
+            if let Err(e) = self.handle(request) {
+                eprintln!("warning: failed to handle request: {e}");
+            }
+        }
+    }
+
+    fn handle(&mut self, mut request: Request) -> Result<(), Error> {
+        if request.url() != "/tasks" {
+            return Ok(request.respond(Response::empty(StatusCode(404)))?);
+        }
+
+        match request.method() {
+            Method::Get => {
+                let response = json_response(StatusCode(200), &self.snapshot)?;
+                Ok(request.respond(response)?)
+            }
+            Method::Put => {
+                let pushed: Snapshot = match serde_json::from_reader(request.as_reader()) {
+                    Ok(snapshot) => snapshot,
+                    Err(e) => {
+                        let response =
+                            Response::from_string(e.to_string()).with_status_code(StatusCode(400));
+                        return Ok(request.respond(response)?);
+                    }
+                };
+
+                if pushed.version != self.snapshot.version {
+                    let response = json_response(StatusCode(409), &self.snapshot)?;
+                    return Ok(request.respond(response)?);
+                }
+
+                let snapshot = Snapshot {
+                    version: self.snapshot.version + 1,
+                    tasks: pushed.tasks,
+                };
+                if let Err(e) = save_snapshot(&self.state_file, &snapshot) {
+                    // The save failing is the error worth reporting, not the
+                    // response
+                    let _ = request.respond(Response::empty(StatusCode(500)));
+                    return Err(e);
+                }
+                self.snapshot = snapshot;
+
+                let response = json_response(StatusCode(200), &self.snapshot)?;
+                Ok(request.respond(response)?)
+            }
+            _ => Ok(request.respond(Response::empty(StatusCode(405)))?),
+        }
+    }
+}
+
+fn json_response(
+    status: StatusCode,
+    snapshot: &Snapshot,
+) -> Result<Response<io::Cursor<Vec<u8>>>, Error> {
+    let body = serde_json::to_vec(snapshot)?;
+    let header =
+        Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+    Ok(Response::from_data(body)
+        .with_status_code(status)
+        .with_header(header))
+}
+
+fn load_snapshot(path: &Path) -> Result<Snapshot, Error> {
+    match File::open(path) {
+        Ok(file) => Ok(serde_json::from_reader(BufReader::new(file))?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Snapshot::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn save_snapshot(path: &Path, snapshot: &Snapshot) -> Result<(), Error> {
+    let tmp = path.with_extension("tmp");
+    let mut writer = BufWriter::new(File::create(&tmp)?);
+    serde_json::to_writer(&mut writer, snapshot)?;
+    writer.flush()?;
+    drop(writer);
+    fs::rename(tmp, path)?;
+    Ok(())
+}
+
+/// Error type for sync server operations
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The listening socket could not be bound
+    #[error("failed to bind sync server: {0}")]
+    Bind(Box<dyn std::error::Error + Send + Sync>),
+    /// JSON serialization error
+    #[error("failed to serialise tasks: {0}")]
+    Json(#[from] serde_json::Error),
+    /// IO error
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
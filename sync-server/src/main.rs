@@ -0,0 +1,40 @@
+//! Sync server for sharing `RustWarrior` tasks between devices
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use rustwarrior_sync_server::Server;
+
+const STATE_FILE: &str = "sync_server.json";
+
+/// Serve a shared task list for `rw sync`
+#[derive(Debug, Parser)]
+struct Args {
+    /// The address to listen on
+    #[clap(long, default_value = "127.0.0.1:8765")]
+    listen: String,
+    /// The file holding the server's copy of the tasks. Defaults to
+    /// `sync_server.json` in the data directory
+    #[clap(long)]
+    state_file: Option<PathBuf>,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let state_file = match args.state_file {
+        Some(path) => path,
+        None => rustwarrior_core::paths::get_data_dir()?.join(STATE_FILE),
+    };
+
+    let server = Server::bind(&args.listen, &state_file)?;
+    eprintln!(
+        "Serving {} on http://{}",
+        state_file.display(),
+        server
+            .local_addr()
+            .map_or_else(|| args.listen.clone(), |addr| addr.to_string())
+    );
+    server.run()?;
+
+    Ok(())
+}